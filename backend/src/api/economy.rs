@@ -0,0 +1,472 @@
+use actix_web::{get, post, web, HttpResponse, Responder};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::database::{DbPool, Repository};
+use crate::generator::{market_rates, simulate_tick, TradeRoutes};
+use crate::models::{
+    AdvanceEconomyRequest, Belt, Economy, EconomyRequest, EconomyResponse, EconomyTick, ErrorResponse, Faction, HubSystem,
+    HubsQuery, HubsResponse, MarketResource, MarketResponse, Planet, Position3D, Resource, ResourceSummary, Star, StarSystem,
+    SystemMarket, TradeFlow, TradeFlowDetail, TradeFlowsQuery, TradeFlowsResponse, TradeNetwork,
+};
+use crate::spatial::{KdTree, TravelGraph};
+use super::lanes::project_systems;
+
+/// Hubs and flows listed in the economy overview
+const OVERVIEW_RESULTS: usize = 10;
+
+fn database_error(action: &str, e: anyhow::Error) -> HttpResponse {
+    log::error!("Failed to {}: {}", action, e);
+    HttpResponse::InternalServerError().json(ErrorResponse {
+        error: format!("Database error: {}", e),
+    })
+}
+
+/// A project's economy, or 404 when none has been started
+async fn project_economy(repo: &Repository, project_id: Uuid) -> Result<Economy, HttpResponse> {
+    match repo.get_economy(project_id).await {
+        Ok(Some(economy)) => Ok(economy),
+        Ok(None) => Err(HttpResponse::NotFound().json(ErrorResponse {
+            error: "Project has no economy; start one with POST /economy".to_string(),
+        })),
+        Err(e) => Err(database_error("get economy", e)),
+    }
+}
+
+/// Simulate `ticks` ticks and save the result
+/// Production and consumption are worked out afresh from the project's
+/// stars, planets, belts and territory, so changes to them take effect on
+/// the next tick; stock carries over unless the economy is restarted
+async fn run_economy(
+    repo: &Repository,
+    project_id: Uuid,
+    mut economy: Economy,
+    ticks: u32,
+    restart: bool,
+) -> Result<(), HttpResponse> {
+    let (_, systems) = project_systems(repo, project_id).await?;
+    let stars = repo.get_stars_by_project(project_id).await.map_err(|e| database_error("get stars", e))?;
+    let planets = repo.get_planets_by_project(project_id).await.map_err(|e| database_error("get planets", e))?;
+    let belts = repo.get_belts_by_project(project_id).await.map_err(|e| database_error("get belts", e))?;
+    let factions = repo.get_factions_by_project(project_id).await.map_err(|e| database_error("get factions", e))?;
+    let lanes = repo.get_lanes_by_project(project_id).await.map_err(|e| database_error("get lanes", e))?;
+    let previous: HashMap<Uuid, SystemMarket> = if restart {
+        HashMap::new()
+    } else {
+        let markets = repo.get_system_markets(project_id).await.map_err(|e| database_error("get markets", e))?;
+        markets.into_iter().map(|m| (m.system_id, m)).collect()
+    };
+
+    if economy.network == TradeNetwork::Lanes && lanes.is_empty() {
+        return Err(HttpResponse::BadRequest().json(ErrorResponse {
+            error: "Project has no lanes; generate them first or trade in straight lines".to_string(),
+        }));
+    }
+
+    // The ticks run on the blocking pool; each one routes every trade
+    let simulation = web::block(move || {
+        let positions: Vec<Position3D> = systems.iter().map(|s| Position3D { x: s.x_ly, y: s.y_ly, z: s.z_ly }).collect();
+        let routes = match economy.network {
+            TradeNetwork::Lanes => {
+                let by_id: HashMap<Uuid, usize> = systems.iter().enumerate().map(|(i, s)| (s.id.unwrap(), i)).collect();
+                TradeRoutes::Lanes(TravelGraph::from_lanes(
+                    systems.len(),
+                    lanes.iter().filter_map(|l| Some((*by_id.get(&l.system_a_id)?, *by_id.get(&l.system_b_id)?, l.length_ly))),
+                ))
+            }
+            TradeNetwork::StraightLine => TradeRoutes::StraightLine(KdTree::new(&positions)),
+        };
+
+        let capitals: HashSet<Uuid> = factions.iter().map(|f| f.capital_system_id).collect();
+        let mut system_stars: HashMap<Uuid, Vec<&Star>> = HashMap::new();
+        for star in &stars {
+            system_stars.entry(star.system_id).or_default().push(star);
+        }
+        let mut system_planets: HashMap<Uuid, Vec<&Planet>> = HashMap::new();
+        for planet in &planets {
+            system_planets.entry(planet.system_id).or_default().push(planet);
+        }
+        let mut system_belts: HashMap<Uuid, Vec<&Belt>> = HashMap::new();
+        for belt in &belts {
+            system_belts.entry(belt.system_id).or_default().push(belt);
+        }
+
+        let mut markets: Vec<SystemMarket> = systems
+            .iter()
+            .map(|system| {
+                let id = system.id.unwrap();
+                let (production, consumption) = market_rates(
+                    system,
+                    system_stars.get(&id).map_or(&[], |s| s.as_slice()),
+                    system_planets.get(&id).map_or(&[], |p| p.as_slice()),
+                    system_belts.get(&id).map_or(&[], |b| b.as_slice()),
+                    capitals.contains(&id),
+                );
+                SystemMarket {
+                    system_id: id,
+                    project_id,
+                    production,
+                    consumption,
+                    stock: previous.get(&id).map_or([0.0; Resource::COUNT], |m| m.stock),
+                    imports: [0.0; Resource::COUNT],
+                    exports: [0.0; Resource::COUNT],
+                    throughput: 0.0,
+                }
+            })
+            .collect();
+
+        let owners: Vec<Option<Uuid>> = systems.iter().map(|s| s.faction_id).collect();
+        let mut history = Vec::with_capacity(ticks as usize);
+        let mut flows = Vec::new();
+        for _ in 0..ticks {
+            let result = simulate_tick(&routes, &positions, &owners, &mut markets, economy.max_route_ly);
+            economy.tick += 1;
+            history.push(EconomyTick {
+                tick: economy.tick,
+                produced: result.produced,
+                consumed: result.consumed,
+                traded: result.traded,
+                shortage: result.shortage,
+            });
+            flows = result.flows;
+        }
+
+        let flows: Vec<TradeFlow> = flows
+            .into_iter()
+            .map(|flow| TradeFlow {
+                id: None,
+                project_id,
+                tick: economy.tick,
+                resource: flow.resource,
+                from_system_id: systems[flow.from].id.unwrap(),
+                to_system_id: systems[flow.to].id.unwrap(),
+                volume: flow.volume,
+            })
+            .collect();
+
+        (economy, markets, flows, history)
+    });
+    let (economy, markets, flows, history) = match simulation.await {
+        Ok(simulated) => simulated,
+        Err(e) => {
+            log::error!("Economy simulation was cancelled: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to simulate economy: {}", e),
+            }));
+        }
+    };
+
+    if let Err(e) = repo.save_economy(&economy, &markets, &flows, &history, restart).await {
+        log::error!("Failed to save economy: {}", e);
+        return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Failed to save economy: {}", e),
+        }));
+    }
+
+    log::info!(
+        "Simulated {} economy ticks for project {} (now at tick {}, {} flows)",
+        ticks,
+        project_id,
+        economy.tick,
+        flows.len()
+    );
+    Ok(())
+}
+
+/// A flow with its systems named, or None when either system is gone
+fn flow_detail(names: &HashMap<Uuid, &str>, flow: &TradeFlow) -> Option<TradeFlowDetail> {
+    Some(TradeFlowDetail {
+        resource: flow.resource,
+        from: names.get(&flow.from_system_id)?.to_string(),
+        to: names.get(&flow.to_system_id)?.to_string(),
+        volume: flow.volume,
+    })
+}
+
+/// The busiest systems, most throughput first
+fn hubs(systems: &[StarSystem], factions: &[Faction], markets: &[SystemMarket], limit: usize) -> Vec<HubSystem> {
+    let by_id: HashMap<Uuid, &StarSystem> = systems.iter().map(|s| (s.id.unwrap(), s)).collect();
+    let faction_names: HashMap<Uuid, &str> = factions.iter().map(|f| (f.id.unwrap(), f.name.as_str())).collect();
+
+    let mut busiest: Vec<(&StarSystem, &SystemMarket)> = markets
+        .iter()
+        .filter(|m| m.throughput > 0.0)
+        .filter_map(|m| Some((*by_id.get(&m.system_id)?, m)))
+        .collect();
+    busiest.sort_by(|a, b| b.1.throughput.total_cmp(&a.1.throughput).then_with(|| a.0.name.cmp(&b.0.name)));
+    busiest
+        .into_iter()
+        .take(limit)
+        .map(|(system, market)| HubSystem {
+            system_name: system.name.clone(),
+            faction: system.faction_id.and_then(|f| faction_names.get(&f)).map(|f| f.to_string()),
+            throughput: market.throughput,
+            imports: market.imports.iter().sum(),
+            exports: market.exports.iter().sum(),
+        })
+        .collect()
+}
+
+/// Overview of a project's economy as stored
+async fn economy_overview(repo: &Repository, project_id: Uuid) -> Result<EconomyResponse, HttpResponse> {
+    let economy = project_economy(repo, project_id).await?;
+    let (_, systems) = project_systems(repo, project_id).await?;
+    let factions = repo.get_factions_by_project(project_id).await.map_err(|e| database_error("get factions", e))?;
+    let markets = repo.get_system_markets(project_id).await.map_err(|e| database_error("get markets", e))?;
+    let flows = repo.get_trade_flows(project_id).await.map_err(|e| database_error("get trade flows", e))?;
+    let history = repo.get_economy_ticks(project_id).await.map_err(|e| database_error("get economy ticks", e))?;
+
+    let resources = Resource::ALL
+        .iter()
+        .map(|resource| {
+            let r = resource.index();
+            ResourceSummary {
+                resource: *resource,
+                production: markets.iter().map(|m| m.production[r]).sum(),
+                consumption: markets.iter().map(|m| m.consumption[r]).sum(),
+                stock: markets.iter().map(|m| m.stock[r]).sum(),
+                traded: markets.iter().map(|m| m.imports[r]).sum(),
+            }
+        })
+        .collect();
+
+    let names: HashMap<Uuid, &str> = systems.iter().map(|s| (s.id.unwrap(), s.name.as_str())).collect();
+    Ok(EconomyResponse {
+        project_id,
+        tick: economy.tick,
+        network: economy.network.to_string(),
+        max_route_ly: economy.max_route_ly,
+        resources,
+        history,
+        hubs: hubs(&systems, &factions, &markets, OVERVIEW_RESULTS),
+        flows: flows.iter().filter_map(|f| flow_detail(&names, f)).take(OVERVIEW_RESULTS).collect(),
+    })
+}
+
+/// POST /api/projects/{id}/economy
+/// Start a project's economy from empty stockpiles, replacing any running one
+#[post("/projects/{id}/economy")]
+async fn start_economy(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    req: web::Json<EconomyRequest>,
+) -> impl Responder {
+    if let Err(e) = req.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+
+    let project_id = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let network = match req.network().unwrap() {
+        Some(network) => network,
+        None => match repo.get_lanes_by_project(project_id).await {
+            Ok(lanes) if lanes.is_empty() => TradeNetwork::StraightLine,
+            Ok(_) => TradeNetwork::Lanes,
+            Err(e) => return database_error("get lanes", e),
+        },
+    };
+    let economy = Economy { project_id, tick: 0, network, max_route_ly: req.max_route_ly };
+
+    if let Err(response) = run_economy(&repo, project_id, economy, req.ticks(), true).await {
+        return response;
+    }
+    match economy_overview(&repo, project_id).await {
+        Ok(overview) => HttpResponse::Ok().json(overview),
+        Err(response) => response,
+    }
+}
+
+/// POST /api/projects/{id}/economy/advance
+/// Simulate more ticks of a project's economy
+#[post("/projects/{id}/economy/advance")]
+async fn advance_economy(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    req: web::Json<AdvanceEconomyRequest>,
+) -> impl Responder {
+    if let Err(e) = req.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+
+    let project_id = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let economy = match project_economy(&repo, project_id).await {
+        Ok(e) => e,
+        Err(response) => return response,
+    };
+    if let Err(response) = run_economy(&repo, project_id, economy, req.ticks(), false).await {
+        return response;
+    }
+    match economy_overview(&repo, project_id).await {
+        Ok(overview) => HttpResponse::Ok().json(overview),
+        Err(response) => response,
+    }
+}
+
+/// GET /api/projects/{id}/economy
+/// Totals, history, hubs and largest flows of a project's economy
+#[get("/projects/{id}/economy")]
+async fn get_economy(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let project_id = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    match economy_overview(&repo, project_id).await {
+        Ok(overview) => HttpResponse::Ok().json(overview),
+        Err(response) => response,
+    }
+}
+
+/// GET /api/projects/{id}/economy/flows
+/// Trade flows of the latest tick, largest first
+#[get("/projects/{id}/economy/flows")]
+async fn get_trade_flows(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    query: web::Query<TradeFlowsQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+    let resource = query.resource().unwrap();
+
+    let project_id = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let economy = match project_economy(&repo, project_id).await {
+        Ok(e) => e,
+        Err(response) => return response,
+    };
+    let (_, systems) = match project_systems(&repo, project_id).await {
+        Ok(p) => p,
+        Err(response) => return response,
+    };
+    let flows = match repo.get_trade_flows(project_id).await {
+        Ok(f) => f,
+        Err(e) => return database_error("get trade flows", e),
+    };
+
+    let names: HashMap<Uuid, &str> = systems.iter().map(|s| (s.id.unwrap(), s.name.as_str())).collect();
+    let matching: Vec<&TradeFlow> = flows.iter().filter(|f| resource.is_none_or(|r| f.resource == r)).collect();
+    HttpResponse::Ok().json(TradeFlowsResponse {
+        project_id,
+        tick: economy.tick,
+        total: matching.len(),
+        flows: matching.into_iter().filter_map(|f| flow_detail(&names, f)).take(query.limit()).collect(),
+    })
+}
+
+/// GET /api/projects/{id}/economy/hubs
+/// Systems with the most trade passing through them in the latest tick
+#[get("/projects/{id}/economy/hubs")]
+async fn get_trade_hubs(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    query: web::Query<HubsQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+
+    let project_id = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let economy = match project_economy(&repo, project_id).await {
+        Ok(e) => e,
+        Err(response) => return response,
+    };
+    let (_, systems) = match project_systems(&repo, project_id).await {
+        Ok(p) => p,
+        Err(response) => return response,
+    };
+    let factions = match repo.get_factions_by_project(project_id).await {
+        Ok(f) => f,
+        Err(e) => return database_error("get factions", e),
+    };
+    let markets = match repo.get_system_markets(project_id).await {
+        Ok(m) => m,
+        Err(e) => return database_error("get markets", e),
+    };
+
+    HttpResponse::Ok().json(HubsResponse {
+        project_id,
+        tick: economy.tick,
+        hubs: hubs(&systems, &factions, &markets, query.limit()),
+    })
+}
+
+/// GET /api/projects/{id}/systems/{system}/market
+/// One system's production, consumption, stock and trade
+#[get("/projects/{id}/systems/{system}/market")]
+async fn get_system_market(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String)>,
+) -> impl Responder {
+    let (project_id, system_name) = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let economy = match project_economy(&repo, project_id).await {
+        Ok(e) => e,
+        Err(response) => return response,
+    };
+    let system = match repo.get_star_system_by_name(project_id, &system_name).await {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: format!("Star system '{}' not found in project", system_name),
+            });
+        }
+        Err(e) => return database_error("get star system", e),
+    };
+    let market = match repo.get_system_market(system.id.unwrap()).await {
+        Ok(Some(m)) => m,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: format!("{} has no market yet; advance the economy", system_name),
+            });
+        }
+        Err(e) => return database_error("get market", e),
+    };
+    let faction = match system.faction_id {
+        Some(id) => match repo.get_factions_by_project(project_id).await {
+            Ok(factions) => factions.into_iter().find(|f| f.id == Some(id)).map(|f| f.name),
+            Err(e) => return database_error("get factions", e),
+        },
+        None => None,
+    };
+
+    HttpResponse::Ok().json(MarketResponse {
+        project_id,
+        system_name: system.name,
+        tick: economy.tick,
+        faction,
+        throughput: market.throughput,
+        resources: Resource::ALL
+            .iter()
+            .map(|resource| {
+                let r = resource.index();
+                MarketResource {
+                    resource: *resource,
+                    production: market.production[r],
+                    consumption: market.consumption[r],
+                    stock: market.stock[r],
+                    imported: market.imports[r],
+                    exported: market.exports[r],
+                }
+            })
+            .collect(),
+    })
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(start_economy)
+        .service(advance_economy)
+        .service(get_economy)
+        .service(get_trade_flows)
+        .service(get_trade_hubs)
+        .service(get_system_market);
+}
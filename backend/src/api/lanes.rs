@@ -0,0 +1,154 @@
+use actix_web::{get, post, web, HttpResponse, Responder};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::database::{DbPool, Repository};
+use crate::generator::{generate_lanes, random_seed};
+use crate::models::{ErrorResponse, Lane, LaneDetail, LaneRequest, LanesResponse, Project, StarSystem};
+
+/// Lanes with system names in place of ids, shortest first
+pub fn lane_details(systems: &[StarSystem], lanes: &[Lane]) -> Vec<LaneDetail> {
+    let names: HashMap<Uuid, &str> = systems.iter().map(|s| (s.id.unwrap(), s.name.as_str())).collect();
+    let mut details: Vec<LaneDetail> = lanes
+        .iter()
+        .map(|lane| {
+            let (a, b) = (names[&lane.system_a_id], names[&lane.system_b_id]);
+            LaneDetail {
+                from: a.min(b).to_string(),
+                to: a.max(b).to_string(),
+                length_ly: lane.length_ly,
+                chokepoint: lane.chokepoint,
+            }
+        })
+        .collect();
+    details.sort_by(|a, b| a.length_ly.total_cmp(&b.length_ly).then_with(|| a.from.cmp(&b.from)));
+    details
+}
+
+fn lanes_response(project_id: Uuid, systems: &[StarSystem], lanes: &[Lane]) -> LanesResponse {
+    LanesResponse {
+        project_id,
+        num_lanes: lanes.len(),
+        num_chokepoints: lanes.iter().filter(|l| l.chokepoint).count(),
+        total_length_ly: lanes.iter().map(|l| l.length_ly).sum(),
+        lanes: lane_details(systems, lanes),
+    }
+}
+
+/// Project and its systems, or the error response to send
+pub async fn project_systems(repo: &Repository, project_id: Uuid) -> Result<(Project, Vec<StarSystem>), HttpResponse> {
+    let project = match repo.get_project_by_id(project_id).await {
+        Ok(Some(p)) => p,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound().json(ErrorResponse {
+                error: "Project not found".to_string(),
+            }));
+        }
+        Err(e) => {
+            log::error!("Failed to get project: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }));
+        }
+    };
+
+    match repo.get_star_systems_by_project(project_id).await {
+        Ok(systems) => Ok((project, systems)),
+        Err(e) => {
+            log::error!("Failed to get star systems: {}", e);
+            Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }))
+        }
+    }
+}
+
+/// POST /api/projects/{id}/lanes
+/// Link a project's systems into a lane network, replacing any it already has
+#[post("/projects/{id}/lanes")]
+async fn generate_project_lanes(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    req: web::Json<LaneRequest>,
+) -> impl Responder {
+    if let Err(e) = req.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+    let params = match req.params() {
+        Ok(p) => p,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+        }
+    };
+
+    let project_id = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let (project, systems) = match project_systems(&repo, project_id).await {
+        Ok(p) => p,
+        Err(response) => return response,
+    };
+    let seed = req.seed.or(project.seed).unwrap_or_else(random_seed);
+
+    // Neighbourhood graphs and chokepoint detection over thousands of
+    // systems take a while; keep them off the async workers
+    let linked = systems.clone();
+    let lanes = match web::block(move || generate_lanes(project_id, &linked, &params, seed)).await {
+        Ok(Ok(l)) => l,
+        Ok(Err(e)) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: format!("Lane error: {}", e),
+            });
+        }
+        Err(e) => {
+            log::error!("Lane generation was cancelled: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to generate lanes: {}", e),
+            });
+        }
+    };
+
+    if let Err(e) = repo.replace_lanes(project_id, &lanes).await {
+        log::error!("Failed to insert lanes: {}", e);
+        return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Failed to save lanes: {}", e),
+        });
+    }
+
+    log::info!("Linked project {} with {} {} lanes (seed {})", project_id, lanes.len(), params.rule, seed);
+
+    HttpResponse::Ok().json(lanes_response(project_id, &systems, &lanes))
+}
+
+/// GET /api/projects/{id}/lanes
+/// Get a project's lane network
+#[get("/projects/{id}/lanes")]
+async fn get_project_lanes(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let project_id = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let (_, systems) = match project_systems(&repo, project_id).await {
+        Ok(p) => p,
+        Err(response) => return response,
+    };
+
+    let lanes = match repo.get_lanes_by_project(project_id).await {
+        Ok(l) => l,
+        Err(e) => {
+            log::error!("Failed to get lanes: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    HttpResponse::Ok().json(lanes_response(project_id, &systems, &lanes))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(generate_project_lanes)
+        .service(get_project_lanes);
+}
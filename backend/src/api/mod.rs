@@ -1,5 +1,10 @@
 pub mod projects;
 pub mod health;
+pub mod spatial;
+pub mod lanes;
+pub mod navigation;
+pub mod territories;
+pub mod economy;
 
 use actix_web::web;
 
@@ -8,5 +13,10 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
         web::scope("/api")
             .configure(health::configure)
             .configure(projects::configure)
+            .configure(spatial::configure)
+            .configure(lanes::configure)
+            .configure(navigation::configure)
+            .configure(territories::configure)
+            .configure(economy::configure)
     );
 }
@@ -0,0 +1,311 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::database::{DbPool, Repository};
+use crate::models::{
+    ErrorResponse, Position3D, ReachabilityQuery, ReachabilityResponse, ReachableSystem, RouteHop, RouteQuery,
+    RouteResponse, UnreachableComponent,
+};
+use crate::spatial::{components, find_route, reachable, ProjectIndex, SpatialCache, TravelGraph, DAYS_PER_YEAR};
+use super::spatial::project_index;
+
+/// Index of every named system, or an error naming the first unknown one
+fn find_systems(index: &ProjectIndex, names: &[String]) -> Result<Vec<usize>, String> {
+    names
+        .iter()
+        .map(|name| index.find(name).ok_or_else(|| format!("Star system '{}' not found in project", name)))
+        .collect()
+}
+
+/// Stored lanes of a project as a travel graph over the index's systems
+async fn lane_graph(repo: &Repository, index: &ProjectIndex, project_id: Uuid) -> Result<TravelGraph<'static>, HttpResponse> {
+    let lanes = match repo.get_lanes_by_project(project_id).await {
+        Ok(l) => l,
+        Err(e) => {
+            log::error!("Failed to get lanes: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }));
+        }
+    };
+    if lanes.is_empty() {
+        return Err(HttpResponse::BadRequest().json(ErrorResponse {
+            error: "Project has no lanes; generate them first or give max_jump_ly".to_string(),
+        }));
+    }
+
+    let by_id: HashMap<Uuid, usize> = index.systems.iter().enumerate().map(|(i, s)| (s.id.unwrap(), i)).collect();
+    Ok(TravelGraph::from_lanes(
+        index.systems.len(),
+        lanes.iter().map(|l| (by_id[&l.system_a_id], by_id[&l.system_b_id], l.length_ly)),
+    ))
+}
+
+/// GET /api/projects/{id}/route
+/// Best route between two systems along lanes or in free flight
+#[get("/projects/{id}/route")]
+async fn plan_route(
+    pool: web::Data<DbPool>,
+    cache: web::Data<SpatialCache>,
+    path: web::Path<Uuid>,
+    query: web::Query<RouteQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+    let cost = query.cost().unwrap();
+
+    let project_id = path.into_inner();
+    let index = match project_index(&pool, &cache, project_id).await {
+        Ok(index) => index,
+        Err(response) => return response,
+    };
+    let repo = Repository::new(pool.get_ref().clone());
+
+    // Stops in travel order: start, waypoints, destination
+    let mut stop_names = vec![query.from.clone()];
+    stop_names.extend(query.via());
+    stop_names.push(query.to.clone());
+    let stops = match find_systems(&index, &stop_names) {
+        Ok(s) => s,
+        Err(e) => return HttpResponse::NotFound().json(ErrorResponse { error: e }),
+    };
+
+    let mut blocked = vec![false; index.systems.len()];
+    match find_systems(&index, &query.avoid()) {
+        Ok(avoided) => avoided.into_iter().for_each(|i| blocked[i] = true),
+        Err(e) => return HttpResponse::NotFound().json(ErrorResponse { error: e }),
+    }
+    if let Some(classes) = query.avoid_classes().unwrap() {
+        let stars = match repo.get_stars_by_project(project_id).await {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to get stars: {}", e);
+                return HttpResponse::InternalServerError().json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                });
+            }
+        };
+        let avoided: HashSet<Uuid> = stars
+            .iter()
+            .filter(|s| classes.contains(&s.spectral_class))
+            .map(|s| s.system_id)
+            .collect();
+        for (i, system) in index.systems.iter().enumerate() {
+            if avoided.contains(&system.id.unwrap()) {
+                blocked[i] = true;
+            }
+        }
+    }
+    // The stops themselves are always allowed
+    for &stop in &stops {
+        blocked[stop] = false;
+    }
+
+    let lanes = match query.max_jump_ly {
+        Some(_) => None,
+        None => match lane_graph(&repo, &index, project_id).await {
+            Ok(graph) => Some(graph),
+            Err(response) => return response,
+        },
+    };
+    let network = if lanes.is_some() { "lanes" } else { "free_flight" };
+
+    // Search every leg on the blocking pool; a wide jump range links most
+    // pairs of systems and the search can take a while
+    let (searched, max_jump_ly, legs) = (Arc::clone(&index), query.max_jump_ly, stops.clone());
+    let search = web::block(move || {
+        let graph = match max_jump_ly {
+            Some(max_jump_ly) => TravelGraph::FreeFlight { tree: &searched.tree, max_jump_ly },
+            None => lanes.unwrap(),
+        };
+        let mut route = vec![legs[0]];
+        for leg in legs.windows(2) {
+            match find_route(&graph, &searched.positions, leg[0], leg[1], cost, &blocked) {
+                Some(path) => route.extend(&path[1..]),
+                None => return Err((leg[0], leg[1])),
+            }
+        }
+        Ok(route)
+    });
+    let route = match search.await {
+        Ok(Ok(route)) => route,
+        Ok(Err((from, to))) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: format!(
+                    "No route from {} to {} over {}",
+                    index.systems[from].name,
+                    index.systems[to].name,
+                    network.replace('_', " ")
+                ),
+            });
+        }
+        Err(e) => {
+            log::error!("Route search was cancelled: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to plan route: {}", e),
+            });
+        }
+    };
+
+    let hop_time = |distance_ly: f64| {
+        query
+            .speed_c
+            .map(|speed| distance_ly / speed * DAYS_PER_YEAR + query.jump_time_days.unwrap_or(0.0))
+    };
+    let mut total_distance_ly = 0.0;
+    let hops: Vec<RouteHop> = route
+        .windows(2)
+        .map(|hop| {
+            let (a, b) = (index.position(hop[0]), index.position(hop[1]));
+            let distance_ly = distance(&a, &b);
+            total_distance_ly += distance_ly;
+            RouteHop {
+                from: index.systems[hop[0]].name.clone(),
+                to: index.systems[hop[1]].name.clone(),
+                distance_ly,
+                cumulative_distance_ly: total_distance_ly,
+                time_days: hop_time(distance_ly),
+            }
+        })
+        .collect();
+
+    let (start, end) = (index.position(stops[0]), index.position(*stops.last().unwrap()));
+    HttpResponse::Ok().json(RouteResponse {
+        project_id,
+        from: query.from.clone(),
+        to: query.to.clone(),
+        network: network.to_string(),
+        cost: cost.to_string(),
+        systems: route.iter().map(|&i| index.systems[i].name.clone()).collect(),
+        jumps: hops.len(),
+        total_time_days: query.speed_c.map(|_| hops.iter().filter_map(|h| h.time_days).sum()),
+        hops,
+        total_distance_ly,
+        straight_line_ly: distance(&start, &end),
+    })
+}
+
+/// GET /api/projects/{id}/systems/{system}/reachable
+/// Every system reachable from one system within a number of jumps, and the
+/// parts of the network it cannot reach at all
+#[get("/projects/{id}/systems/{system}/reachable")]
+async fn reachable_systems(
+    pool: web::Data<DbPool>,
+    cache: web::Data<SpatialCache>,
+    path: web::Path<(Uuid, String)>,
+    query: web::Query<ReachabilityQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+
+    let (project_id, origin_name) = path.into_inner();
+    let index = match project_index(&pool, &cache, project_id).await {
+        Ok(index) => index,
+        Err(response) => return response,
+    };
+    let origin = match find_systems(&index, std::slice::from_ref(&origin_name)) {
+        Ok(found) => found[0],
+        Err(e) => return HttpResponse::NotFound().json(ErrorResponse { error: e }),
+    };
+
+    let repo = Repository::new(pool.get_ref().clone());
+    let lanes = match query.max_jump_ly {
+        Some(_) => None,
+        None => match lane_graph(&repo, &index, project_id).await {
+            Ok(graph) => Some(graph),
+            Err(response) => return response,
+        },
+    };
+    let network = if lanes.is_some() { "lanes" } else { "free_flight" };
+
+    let (searched, max_jump_ly, max_jumps) = (Arc::clone(&index), query.max_jump_ly, query.max_jumps);
+    let search = web::block(move || {
+        let graph = match max_jump_ly {
+            Some(max_jump_ly) => TravelGraph::FreeFlight { tree: &searched.tree, max_jump_ly },
+            None => lanes.unwrap(),
+        };
+        (
+            reachable(&graph, &searched.positions, origin, max_jumps),
+            components(&graph, &searched.positions),
+        )
+    });
+    let (reach, labels) = match search.await {
+        Ok(found) => found,
+        Err(e) => {
+            log::error!("Reachability search was cancelled: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to search reachable systems: {}", e),
+            });
+        }
+    };
+    let mut found: Vec<(usize, usize, f64)> = reach
+        .iter()
+        .enumerate()
+        .filter_map(|(i, r)| r.map(|r| (i, r.jumps, r.distance_ly)))
+        .collect();
+    found.sort_by(|a, b| a.1.cmp(&b.1).then(a.2.total_cmp(&b.2)).then(a.0.cmp(&b.0)));
+    let systems: Vec<ReachableSystem> = found
+        .iter()
+        .map(|&(i, jumps, distance_ly)| ReachableSystem {
+            system_name: index.systems[i].name.clone(),
+            jumps,
+            distance_ly,
+            previous: reach[i].unwrap().previous.map(|p| index.systems[p].name.clone()),
+        })
+        .collect();
+
+    // Group every system by component; the origin's is the one it can reach
+    let num_components = labels.iter().max().map_or(0, |&c| c + 1);
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); num_components];
+    for (i, &c) in labels.iter().enumerate() {
+        members[c].push(i);
+    }
+    let connected = members[labels[origin]].len();
+    let start = index.position(origin);
+    let mut unreachable_components: Vec<UnreachableComponent> = members
+        .iter()
+        .enumerate()
+        .filter(|(c, _)| *c != labels[origin])
+        .map(|(_, systems)| {
+            let (nearest, nearest_distance_ly) = systems
+                .iter()
+                .map(|&i| (i, distance(&start, &index.position(i))))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            UnreachableComponent {
+                size: systems.len(),
+                systems: systems.iter().map(|&i| index.systems[i].name.clone()).collect(),
+                nearest_system: index.systems[nearest].name.clone(),
+                nearest_distance_ly,
+            }
+        })
+        .collect();
+    unreachable_components.sort_by(|a, b| {
+        b.size.cmp(&a.size).then(a.nearest_distance_ly.total_cmp(&b.nearest_distance_ly))
+    });
+
+    HttpResponse::Ok().json(ReachabilityResponse {
+        project_id,
+        origin: origin_name,
+        network: network.to_string(),
+        max_jump_ly: query.max_jump_ly,
+        max_jumps: query.max_jumps,
+        beyond_max_jumps: connected - systems.len(),
+        reachable: systems,
+        num_components,
+        unreachable_components,
+    })
+}
+
+fn distance(a: &Position3D, b: &Position3D) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(plan_route)
+        .service(reachable_systems);
+}
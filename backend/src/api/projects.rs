@@ -1,14 +1,27 @@
 use actix_web::{get, post, web, HttpResponse, Responder};
 use uuid::Uuid;
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
 use std::time::Instant;
 
 use crate::database::{DbPool, Repository};
-use crate::generator::generate_star_systems;
+use crate::generator::{generate_star_systems, minimum_separation, random_seed, sky_view, generate_constellations, render_skybox, GeneratedGalaxy, SkyboxOptions, GenerationParams,
+    read_catalogue, import_catalogue, generate_lanes, host_habitable_zone, component_label, MAX_IMPORT_SYSTEMS};
 use crate::models::{
     GenerateProjectRequest, GenerateProjectResponse, ProjectListResponse,
     ProjectSummary, ProjectStarsResponse, SystemWithStars, Position,
-    StarDetail, ErrorResponse,
+    StarDetail, OrbitDetail, ErrorResponse, ProjectZonesResponse, Position3D,
+    SystemPlanetsResponse, PlanetDetail, HabitableQuery, HabitableResponse, HabitablePlanet,
+    SystemTreeResponse, BodyNode, Body, MoonDetail, BeltDetail, Star, StarSystem, Planet, Orbit,
+    PlanetQuery, PlanetSearchResponse, PlanetMatch, SkyQuery, SkyResponse, SkyFrame, SkyStar,
+    ConstellationRequest, ConstellationsResponse, ConstellationDetail, ConstellationStarDetail, Constellation,
+    SkyboxQuery, ImportCatalogueRequest, ImportCatalogueResponse, DistributionType, StarFormationHistory,
+    LaneRequest,
 };
+use super::lanes::lane_details;
 
 /// POST /api/projects/generate
 /// Generate a new project with star systems
@@ -24,27 +37,40 @@ async fn generate_project(
         return HttpResponse::BadRequest().json(ErrorResponse { error: e });
     }
 
+    let distribution = match req.distribution() {
+        Ok(d) => d,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+        }
+    };
+    let imf = match req.imf() {
+        Ok(imf) => imf,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+        }
+    };
+    let star_formation = match req.star_formation() {
+        Ok(sfh) => sfh,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+        }
+    };
+    let seed = req.seed.unwrap_or_else(random_seed);
+
+    let mut params = GenerationParams {
+        num_systems: req.num_stars as usize,
+        distribution,
+        zones: Vec::new(),
+        min_separation_ly: req.min_separation_ly,
+        imf,
+        star_formation,
+        seed,
+    };
+
     let repo = Repository::new(pool.get_ref().clone());
 
     // Create project
-    let project_id = match repo
-        .create_project(
-            &req.name,
-            None,
-            &req.distribution_type,
-            req.num_stars,
-            if req.distribution_type == "cube" {
-                Some((
-                    req.size_x_ly.unwrap(),
-                    req.size_y_ly.unwrap(),
-                    req.size_z_ly.unwrap(),
-                ))
-            } else {
-                None
-            },
-            req.radius_ly,
-        )
-        .await
+    let project_id = match repo.create_project(&req.name, None, &params).await
     {
         Ok(id) => id,
         Err(e) => {
@@ -55,73 +81,312 @@ async fn generate_project(
         }
     };
 
-    // Generate star systems
-    let (systems, stars) = match generate_star_systems(
-        project_id,
-        req.num_stars as usize,
-        &req.distribution_type,
-        if req.distribution_type == "cube" {
-            Some((
-                req.size_x_ly.unwrap(),
-                req.size_y_ly.unwrap(),
-                req.size_z_ly.unwrap(),
-            ))
-        } else {
-            None
-        },
-        req.radius_ly,
-    ) {
-        Ok(result) => result,
-        Err(e) => {
+    // Density zones are stored first so systems can reference them
+    params.zones = req.density_zones(project_id);
+    if let Err(e) = repo.insert_density_zones(&params.zones).await {
+        log::error!("Failed to insert density zones: {}", e);
+        if let Err(e) = repo.delete_project(project_id).await {
+            log::error!("Failed to delete project after insert error: {}", e);
+        }
+        return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        });
+    }
+
+    // Generate star systems on the blocking pool; large galaxies take seconds
+    let (generation_params, lanes) = (params.clone(), req.lanes.clone());
+    let generated = web::block(move || {
+        generate_star_systems(project_id, &generation_params)
+            .and_then(|galaxy| with_lanes(galaxy, lanes.as_ref(), project_id, seed))
+    })
+    .await;
+    let galaxy = match generated {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => {
+            // Generation errors come from unsatisfiable parameters; drop the
+            // half-created project so the name can be reused
             log::error!("Failed to generate star systems: {}", e);
-            return HttpResponse::InternalServerError().json(ErrorResponse {
+            if let Err(e) = repo.delete_project(project_id).await {
+                log::error!("Failed to delete project after generation error: {}", e);
+            }
+            return HttpResponse::BadRequest().json(ErrorResponse {
                 error: format!("Generation error: {}", e),
             });
         }
+        Err(e) => {
+            log::error!("Star system generation was cancelled: {}", e);
+            if let Err(e) = repo.delete_project(project_id).await {
+                log::error!("Failed to delete project after generation error: {}", e);
+            }
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to generate star systems: {}", e),
+            });
+        }
     };
 
-    // Count system types
-    let (solo_count, binary_count, trinary_count) = crate::generator::count_system_types(&systems);
+    if let Err(response) = insert_galaxy(&repo, project_id, &galaxy).await {
+        if let Err(e) = repo.delete_project(project_id).await {
+            log::error!("Failed to delete project after insert error: {}", e);
+        }
+        return response;
+    }
 
-    // Insert into database
-    if let Err(e) = repo.insert_star_systems(&systems).await {
-        log::error!("Failed to insert star systems: {}", e);
-        return HttpResponse::InternalServerError().json(ErrorResponse {
+    let response = project_response(project_id, &req.name, &galaxy, &params, start_time);
+
+    log::info!(
+        "Generated project '{}' (seed {}): {} systems, {} stars, {} planets, {} moons in {}ms",
+        req.name,
+        seed,
+        galaxy.systems.len(),
+        galaxy.stars.len(),
+        galaxy.planets.len(),
+        galaxy.moons.len(),
+        response.generation_time_ms
+    );
+
+    HttpResponse::Ok().json(response)
+}
+
+/// Store every generated body of a project, then refresh its statistics
+async fn insert_galaxy(repo: &Repository, project_id: Uuid, galaxy: &GeneratedGalaxy) -> Result<(), HttpResponse> {
+    let database_error = |what: &str, e: anyhow::Error| {
+        log::error!("Failed to insert {}: {}", what, e);
+        HttpResponse::InternalServerError().json(ErrorResponse {
             error: format!("Database error: {}", e),
+        })
+    };
+
+    repo.insert_star_systems(&galaxy.systems).await.map_err(|e| database_error("star systems", e))?;
+    repo.insert_stars(&galaxy.stars).await.map_err(|e| database_error("stars", e))?;
+    repo.insert_orbits(&galaxy.orbits).await.map_err(|e| database_error("orbits", e))?;
+    repo.insert_planets(&galaxy.planets).await.map_err(|e| database_error("planets", e))?;
+    repo.insert_moons(&galaxy.moons).await.map_err(|e| database_error("moons", e))?;
+    repo.insert_belts(&galaxy.belts).await.map_err(|e| database_error("belts", e))?;
+    repo.insert_lanes(&galaxy.lanes).await.map_err(|e| database_error("lanes", e))?;
+
+    // Update project statistics
+    if let Err(e) = repo.update_project_stats(project_id).await {
+        log::error!("Failed to update project stats: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Run the optional lane stage over freshly generated systems
+fn with_lanes(
+    mut galaxy: GeneratedGalaxy,
+    lanes: Option<&LaneRequest>,
+    project_id: Uuid,
+    seed: u64,
+) -> Result<GeneratedGalaxy, String> {
+    if let Some(lanes) = lanes {
+        galaxy.lanes = generate_lanes(project_id, &galaxy.systems, &lanes.params()?, lanes.seed.unwrap_or(seed))?;
+    }
+    Ok(galaxy)
+}
+
+/// Counts reported after a project has been generated or imported
+fn project_response(
+    project_id: Uuid,
+    name: &str,
+    galaxy: &GeneratedGalaxy,
+    params: &GenerationParams,
+    start_time: Instant,
+) -> GenerateProjectResponse {
+    let (solo_count, binary_count, trinary_count, higher_order_count) =
+        crate::generator::count_system_types(&galaxy.systems);
+
+    let positions: Vec<Position3D> = galaxy
+        .systems
+        .iter()
+        .map(|s| Position3D { x: s.x_ly, y: s.y_ly, z: s.z_ly })
+        .collect();
+
+    GenerateProjectResponse {
+        project_id,
+        name: name.to_string(),
+        num_star_systems: galaxy.systems.len() as i32,
+        num_stars_total: galaxy.stars.len() as i32,
+        solo_systems: solo_count,
+        binary_systems: binary_count,
+        trinary_systems: trinary_count,
+        higher_order_systems: higher_order_count,
+        num_planets_total: galaxy.planets.len() as i32,
+        num_moons_total: galaxy.moons.len() as i32,
+        num_belts_total: galaxy.belts.len() as i32,
+        num_lanes_total: galaxy.lanes.len() as i32,
+        seed: params.seed,
+        min_separation_ly: params.min_separation_ly,
+        achieved_separation_ly: minimum_separation(&positions),
+        generation_time_ms: start_time.elapsed().as_millis(),
+    }
+}
+
+/// POST /api/projects/import
+/// Create a project from a HYG or Gaia catalogue file on the server,
+/// optionally filled out with procedural systems beyond its completeness radius
+#[post("/projects/import")]
+async fn import_project(
+    pool: web::Data<DbPool>,
+    req: web::Json<ImportCatalogueRequest>,
+) -> impl Responder {
+    let start_time = Instant::now();
+
+    if let Err(e) = req.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+
+    let format = match req.format() {
+        Ok(f) => f,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+        }
+    };
+    let imf = match req.imf() {
+        Ok(imf) => imf,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+        }
+    };
+    let radius_ly = req.radius_ly();
+    let catalogue_radius_ly = req.completeness_radius_ly.unwrap_or(radius_ly);
+
+    // Catalogues are far larger than a request body, so they are read from disk
+    let catalogue_dir = env::var("CATALOGUE_DIR").unwrap_or_else(|_| "catalogues".to_string());
+    let path = Path::new(&catalogue_dir).join(&req.file);
+    let file = match File::open(&path) {
+        Ok(file) => file,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: format!("Cannot open catalogue '{}': {}", req.file, e),
+            });
+        }
+    };
+
+    // Parsing a full catalogue takes seconds; keep it off the async workers
+    let catalogue = match web::block(move || read_catalogue(BufReader::new(file), format, catalogue_radius_ly)).await {
+        Ok(Ok(c)) => c,
+        Ok(Err(e)) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: format!("Catalogue error: {}", e),
+            });
+        }
+        Err(e) => {
+            log::error!("Catalogue reading was cancelled: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to read catalogue: {}", e),
+            });
+        }
+    };
+    if catalogue.systems.is_empty() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("No catalogue stars within {} ly", catalogue_radius_ly),
+        });
+    }
+
+    let filled_systems = req
+        .completeness_radius_ly
+        .map_or(0, |completeness| catalogue.fill_count(radius_ly, completeness));
+    let num_systems = catalogue.systems.len() + filled_systems;
+    if num_systems > MAX_IMPORT_SYSTEMS {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!(
+                "Import would create {} systems (at most {}); use a smaller radius",
+                num_systems, MAX_IMPORT_SYSTEMS
+            ),
         });
     }
 
-    if let Err(e) = repo.insert_stars(&stars).await {
-        log::error!("Failed to insert stars: {}", e);
+    let params = GenerationParams {
+        num_systems,
+        distribution: DistributionType::Sphere { radius: radius_ly },
+        zones: Vec::new(),
+        min_separation_ly: None,
+        imf,
+        star_formation: StarFormationHistory::Constant,
+        seed: req.seed.unwrap_or_else(random_seed),
+    };
+
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let description = format!("Imported from {}", req.file);
+    let project_id = match repo.create_project(&req.name, Some(&description), &params).await {
+        Ok(id) => id,
+        Err(e) => {
+            log::error!("Failed to create project: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    if let Err(e) = repo.set_project_catalogue(project_id, catalogue.format, req.completeness_radius_ly).await {
+        log::error!("Failed to record project catalogue: {}", e);
+        if let Err(e) = repo.delete_project(project_id).await {
+            log::error!("Failed to delete project after catalogue error: {}", e);
+        }
         return HttpResponse::InternalServerError().json(ErrorResponse {
             error: format!("Database error: {}", e),
         });
     }
 
-    // Update project statistics
-    if let Err(e) = repo.update_project_stats(project_id).await {
-        log::error!("Failed to update project stats: {}", e);
+    // The catalogue moves to the blocking pool with the import; keep its counts
+    let (catalogue_format, catalogue_systems, skipped_stars) =
+        (catalogue.format, catalogue.systems.len(), catalogue.skipped_stars);
+    let (import_params, completeness_radius_ly, lanes) = (params.clone(), req.completeness_radius_ly, req.lanes.clone());
+    let imported = web::block(move || {
+        import_catalogue(project_id, &catalogue, &import_params, completeness_radius_ly)
+            .and_then(|galaxy| with_lanes(galaxy, lanes.as_ref(), project_id, import_params.seed))
+    })
+    .await;
+    let galaxy = match imported {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => {
+            log::error!("Failed to import catalogue: {}", e);
+            if let Err(e) = repo.delete_project(project_id).await {
+                log::error!("Failed to delete project after import error: {}", e);
+            }
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: format!("Generation error: {}", e),
+            });
+        }
+        Err(e) => {
+            log::error!("Catalogue import was cancelled: {}", e);
+            if let Err(e) = repo.delete_project(project_id).await {
+                log::error!("Failed to delete project after import error: {}", e);
+            }
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to import catalogue: {}", e),
+            });
+        }
+    };
+
+    if let Err(response) = insert_galaxy(&repo, project_id, &galaxy).await {
+        if let Err(e) = repo.delete_project(project_id).await {
+            log::error!("Failed to delete project after insert error: {}", e);
+        }
+        return response;
     }
 
-    let generation_time_ms = start_time.elapsed().as_millis();
+    let project = project_response(project_id, &req.name, &galaxy, &params, start_time);
 
     log::info!(
-        "Generated project '{}': {} systems, {} stars in {}ms",
+        "Imported project '{}' from {} ({}): {} catalogue and {} procedural systems, {} stars skipped in {}ms",
         req.name,
-        systems.len(),
-        stars.len(),
-        generation_time_ms
+        req.file,
+        catalogue_format,
+        catalogue_systems,
+        filled_systems,
+        skipped_stars,
+        project.generation_time_ms
     );
 
-    HttpResponse::Ok().json(GenerateProjectResponse {
-        project_id,
-        name: req.name.clone(),
-        num_star_systems: systems.len() as i32,
-        num_stars_total: stars.len() as i32,
-        solo_systems: solo_count,
-        binary_systems: binary_count,
-        trinary_systems: trinary_count,
-        generation_time_ms,
+    HttpResponse::Ok().json(ImportCatalogueResponse {
+        project,
+        catalogue: catalogue_format,
+        catalogue_systems: catalogue_systems as i32,
+        filled_systems: filled_systems as i32,
+        skipped_stars: skipped_stars as i32,
     })
 }
 
@@ -150,6 +415,7 @@ async fn list_projects(pool: web::Data<DbPool>) -> impl Responder {
             num_stars_total: p.total_stars,
             created_at: p.created_at.to_rfc3339(),
             distribution_type: p.distribution_type,
+            catalogue: p.catalogue,
         })
         .collect();
 
@@ -229,32 +495,68 @@ async fn get_project_stars(
         }
     };
 
-    // Group stars by system
+    // Get orbits
+    let orbits = match repo.get_orbits_by_project(*project_id).await {
+        Ok(o) => o,
+        Err(e) => {
+            log::error!("Failed to get orbits: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let lanes = match repo.get_lanes_by_project(*project_id).await {
+        Ok(l) => l,
+        Err(e) => {
+            log::error!("Failed to get lanes: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+    let lanes = lane_details(&systems, &lanes);
+
+    // Group stars and orbits by system
+    let mut system_stars: HashMap<Uuid, Vec<Star>> = HashMap::new();
+    for star in stars {
+        system_stars.entry(star.system_id).or_default().push(star);
+    }
+    let mut system_orbits: HashMap<Uuid, Vec<Orbit>> = HashMap::new();
+    for orbit in orbits {
+        system_orbits.entry(orbit.system_id).or_default().push(orbit);
+    }
     let mut systems_with_stars: Vec<SystemWithStars> = Vec::new();
 
     for system in systems {
-        let system_stars: Vec<StarDetail> = stars
+        let members = system_stars.get(&system.id.unwrap()).map_or(&[][..], |s| s.as_slice());
+        let system_orbits: Vec<OrbitDetail> = system_orbits
+            .get(&system.id.unwrap())
+            .map_or(&[][..], |o| o.as_slice())
             .iter()
-            .filter(|s| s.system_id == system.id.unwrap())
-            .map(|s| StarDetail {
-                name: s.name.clone(),
-                spectral_class: format!("{}{}", s.spectral_class.to_char(), s.spectral_subclass.unwrap_or(0)),
-                temperature_k: s.temperature_k,
-                mass_solar: s.mass_solar,
-                radius_solar: s.radius_solar,
-                luminosity_solar: s.luminosity_solar,
+            .map(|o| OrbitDetail {
+                level: o.level,
+                primary: component_label(&system.name, members, &o.primary_component),
+                secondary: component_label(&system.name, members, &o.secondary_component),
+                semi_major_axis_au: o.semi_major_axis_au,
+                eccentricity: o.eccentricity,
+                inclination_deg: o.inclination_deg,
+                period_years: o.period_years,
+                mass_ratio: o.mass_ratio,
             })
             .collect();
 
         systems_with_stars.push(SystemWithStars {
             system_name: system.name.clone(),
             system_type: system.system_type.to_string(),
+            zone_id: system.zone_id,
             position: Position {
                 x: system.x_ly,
                 y: system.y_ly,
                 z: system.z_ly,
             },
-            stars: system_stars,
+            stars: members.iter().map(star_detail).collect(),
+            orbits: system_orbits,
         });
     }
 
@@ -262,12 +564,868 @@ async fn get_project_stars(
         project_id: *project_id,
         project_name: project.name,
         systems: systems_with_stars,
+        lanes,
+    })
+}
+
+/// GET /api/projects/{id}/zones
+/// Get the density zones of a project
+#[get("/projects/{id}/zones")]
+async fn get_project_zones(
+    pool: web::Data<DbPool>,
+    project_id: web::Path<Uuid>,
+) -> impl Responder {
+    let repo = Repository::new(pool.get_ref().clone());
+
+    match repo.get_project_by_id(*project_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: "Project not found".to_string(),
+            });
+        }
+        Err(e) => {
+            log::error!("Failed to get project: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let zones = match repo.get_density_zones_by_project(*project_id).await {
+        Ok(z) => z,
+        Err(e) => {
+            log::error!("Failed to get density zones: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    HttpResponse::Ok().json(ProjectZonesResponse {
+        project_id: *project_id,
+        zones,
+    })
+}
+
+/// GET /api/projects/{id}/systems/{system}/planets
+/// Get the planets of one star system, addressed by name (e.g. S0001)
+#[get("/projects/{id}/systems/{system}/planets")]
+async fn get_system_planets(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String)>,
+) -> impl Responder {
+    let (project_id, system_name) = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let system = match repo.get_star_system_by_name(project_id, &system_name).await {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: format!("Star system '{}' not found in project", system_name),
+            });
+        }
+        Err(e) => {
+            log::error!("Failed to get star system: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+    let system_id = system.id.unwrap();
+
+    let stars = match repo.get_stars_by_system(system_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get stars: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let planets = match repo.get_planets_by_system(system_id).await {
+        Ok(p) => p,
+        Err(e) => {
+            log::error!("Failed to get planets: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    // Stars are ordered by name, so planets follow the a, b, c order
+    let planets: Vec<PlanetDetail> = stars
+        .iter()
+        .flat_map(|star| {
+            planets
+                .iter()
+                .filter(move |p| Some(p.star_id) == star.id)
+                .map(planet_detail)
+        })
+        .collect();
+
+    HttpResponse::Ok().json(SystemPlanetsResponse {
+        project_id,
+        system_name: system.name,
+        planets,
+    })
+}
+
+/// GET /api/projects/{id}/systems/{system}/sky
+/// Every star visible from one system: sky position, distance and apparent
+/// magnitude, brightest first
+#[get("/projects/{id}/systems/{system}/sky")]
+async fn get_system_sky(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String)>,
+    query: web::Query<SkyQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+    let frame = query.frame().unwrap_or_default();
+    let limiting_magnitude = query.limiting_magnitude();
+
+    let (project_id, system_name) = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let observer = match repo.get_star_system_by_name(project_id, &system_name).await {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: format!("Star system '{}' not found in project", system_name),
+            });
+        }
+        Err(e) => {
+            log::error!("Failed to get star system: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let systems = match repo.get_star_systems_by_project(project_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get star systems: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let stars = match repo.get_stars_by_project(project_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get stars: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    HttpResponse::Ok().json(SkyResponse {
+        project_id,
+        stars: sky_view(&observer, &systems, &stars, frame, limiting_magnitude),
+        observer: observer.name,
+        frame,
+        limiting_magnitude,
     })
 }
 
+/// POST /api/projects/{id}/systems/{system}/constellations
+/// Group the brightest stars seen from one system into constellations,
+/// replacing any it already has
+#[post("/projects/{id}/systems/{system}/constellations")]
+async fn generate_system_constellations(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String)>,
+    req: web::Json<ConstellationRequest>,
+) -> impl Responder {
+    if let Err(e) = req.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+    let seed = req.seed.unwrap_or_else(random_seed);
+
+    let (project_id, system_name) = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let (observer, sky) = match observer_sky(&repo, project_id, &system_name).await {
+        Ok(s) => s,
+        Err(response) => return response,
+    };
+    let limiting_magnitude = req.limiting_magnitude.unwrap_or(f64::INFINITY);
+    let visible: Vec<SkyStar> = sky.iter().filter(|s| s.apparent_magnitude <= limiting_magnitude).cloned().collect();
+
+    let constellations = generate_constellations(&observer, &visible, seed);
+    if let Err(e) = repo.replace_constellations(observer.id.unwrap(), &constellations).await {
+        log::error!("Failed to insert constellations: {}", e);
+        return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Failed to save constellations: {}", e),
+        });
+    }
+
+    HttpResponse::Ok().json(ConstellationsResponse {
+        project_id,
+        observer: observer.name,
+        seed: Some(seed),
+        constellations: constellations.iter().map(|c| constellation_detail(c, &sky)).collect(),
+    })
+}
+
+/// GET /api/projects/{id}/systems/{system}/constellations
+/// Get the constellations seen from one system
+#[get("/projects/{id}/systems/{system}/constellations")]
+async fn get_system_constellations(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String)>,
+) -> impl Responder {
+    let (project_id, system_name) = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let (observer, sky) = match observer_sky(&repo, project_id, &system_name).await {
+        Ok(s) => s,
+        Err(response) => return response,
+    };
+
+    let constellations = match repo.get_constellations_by_observer(observer.id.unwrap()).await {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Failed to get constellations: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    HttpResponse::Ok().json(ConstellationsResponse {
+        project_id,
+        observer: observer.name,
+        seed: constellations.first().map(|c| c.seed),
+        constellations: constellations.iter().map(|c| constellation_detail(c, &sky)).collect(),
+    })
+}
+
+/// GET /api/projects/{id}/systems/{system}/skybox
+/// Render the sky seen from one system as a PNG, optionally with its
+/// constellation figures and names
+#[get("/projects/{id}/systems/{system}/skybox")]
+async fn get_system_skybox(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String)>,
+    query: web::Query<SkyboxQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+    let options = SkyboxOptions {
+        projection: query.projection().unwrap_or_default(),
+        size: query.size(),
+        limiting_magnitude: query.limiting_magnitude(),
+        lines: query.constellations.unwrap_or(false),
+        labels: query.labels.unwrap_or(false),
+    };
+
+    let (project_id, system_name) = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let (observer, sky) = match observer_sky(&repo, project_id, &system_name).await {
+        Ok(s) => s,
+        Err(response) => return response,
+    };
+
+    let constellations = if options.lines || options.labels {
+        match repo.get_constellations_by_observer(observer.id.unwrap()).await {
+            Ok(c) => c,
+            Err(e) => {
+                log::error!("Failed to get constellations: {}", e);
+                return HttpResponse::InternalServerError().json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                });
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    // Large images take a while to draw and encode, so keep them off the
+    // async workers
+    match web::block(move || render_skybox(&sky, &constellations, &options)).await {
+        Ok(Ok(png)) => HttpResponse::Ok().content_type("image/png").body(png),
+        Ok(Err(e)) => {
+            log::error!("Failed to encode skybox: {}", e);
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to render skybox: {}", e),
+            })
+        }
+        Err(e) => {
+            log::error!("Skybox rendering was cancelled: {}", e);
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to render skybox: {}", e),
+            })
+        }
+    }
+}
+
+/// GET /api/projects/{id}/habitable
+/// Rank a project's planets by habitability, optionally filtered by host
+/// spectral class, system type and distance from a point
+#[get("/projects/{id}/habitable")]
+async fn get_habitable_planets(
+    pool: web::Data<DbPool>,
+    project_id: web::Path<Uuid>,
+    query: web::Query<HabitableQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+    let spectral_classes = query.spectral_classes().unwrap_or_default();
+    let system_type = query.system_type().unwrap_or_default();
+    let point = query.reference_point().unwrap_or_default();
+
+    let repo = Repository::new(pool.get_ref().clone());
+
+    match repo.get_project_by_id(*project_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: "Project not found".to_string(),
+            });
+        }
+        Err(e) => {
+            log::error!("Failed to get project: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let systems = match repo.get_star_systems_by_project(*project_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get star systems: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let stars = match repo.get_stars_by_project(*project_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get stars: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let orbits = match repo.get_orbits_by_project(*project_id).await {
+        Ok(o) => o,
+        Err(e) => {
+            log::error!("Failed to get orbits: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let planets = match repo.get_habitable_planets(*project_id, query.min_score.unwrap_or(0.0)).await {
+        Ok(p) => p,
+        Err(e) => {
+            log::error!("Failed to get planets: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    // Circumbinary zones need every star and orbit of the planet's system
+    let mut system_stars: HashMap<Uuid, Vec<Star>> = HashMap::new();
+    for star in &stars {
+        system_stars.entry(star.system_id).or_default().push(star.clone());
+    }
+    let mut system_orbits: HashMap<Uuid, Vec<Orbit>> = HashMap::new();
+    for orbit in orbits {
+        system_orbits.entry(orbit.system_id).or_default().push(orbit);
+    }
+
+    let systems: HashMap<Uuid, _> = systems.into_iter().filter_map(|s| Some((s.id?, s))).collect();
+    let stars: HashMap<Uuid, _> = stars.into_iter().filter_map(|s| Some((s.id?, s))).collect();
+
+    let mut matches: Vec<HabitablePlanet> = planets
+        .into_iter()
+        .filter_map(|planet| {
+            let star = stars.get(&planet.star_id)?;
+            let system = systems.get(&planet.system_id)?;
+
+            if spectral_classes.as_ref().is_some_and(|classes| !classes.contains(&star.spectral_class)) {
+                return None;
+            }
+            if system_type.as_ref().is_some_and(|t| *t != system.system_type) {
+                return None;
+            }
+
+            let distance_ly = point.map(|p| {
+                ((system.x_ly - p.x).powi(2) + (system.y_ly - p.y).powi(2) + (system.z_ly - p.z).powi(2)).sqrt()
+            });
+            if let (Some(distance), Some(max)) = (distance_ly, query.max_distance_ly) {
+                if distance > max {
+                    return None;
+                }
+            }
+
+            let habitable_zone = host_habitable_zone(
+                &planet,
+                system_stars.get(&planet.system_id).map_or(&[], Vec::as_slice),
+                system_orbits.get(&planet.system_id).map_or(&[], Vec::as_slice),
+            );
+
+            Some(HabitablePlanet {
+                star: planet.host_name().to_string(),
+                circumbinary: planet.barycentre_orbit_id.is_some(),
+                name: planet.name,
+                system_name: system.name.clone(),
+                system_type: system.system_type.to_string(),
+                spectral_class: star.spectral_type(),
+                position: Position {
+                    x: system.x_ly,
+                    y: system.y_ly,
+                    z: system.z_ly,
+                },
+                distance_ly,
+                planet_type: planet.planet_type.to_string(),
+                semi_major_axis_au: planet.semi_major_axis_au,
+                eccentricity: planet.eccentricity,
+                mass_earth: planet.mass_earth,
+                radius_earth: planet.radius_earth,
+                insolation_earth: planet.insolation_earth,
+                habitable_zone,
+                habitability: planet.habitability,
+                profile: planet.profile,
+            })
+        })
+        .collect();
+
+    // Best score first; nearer systems win ties
+    matches.sort_by(|a, b| {
+        b.habitability
+            .total_cmp(&a.habitability)
+            .then(a.distance_ly.unwrap_or(0.0).total_cmp(&b.distance_ly.unwrap_or(0.0)))
+    });
+    let total_matches = matches.len();
+    matches.truncate(query.limit());
+
+    HttpResponse::Ok().json(HabitableResponse {
+        project_id: *project_id,
+        total_matches,
+        planets: matches,
+    })
+}
+
+/// GET /api/projects/{id}/planets
+/// Find a project's planets by host spectral class, planet type, climate,
+/// atmosphere, water cover and tidal locking
+#[get("/projects/{id}/planets")]
+async fn search_planets(
+    pool: web::Data<DbPool>,
+    project_id: web::Path<Uuid>,
+    query: web::Query<PlanetQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+    let spectral_classes = query.spectral_classes().unwrap_or_default();
+    let planet_type = query.planet_type().unwrap_or_default();
+    let climates = query.climates().unwrap_or_default();
+    let atmosphere = query.atmosphere().unwrap_or_default();
+
+    let repo = Repository::new(pool.get_ref().clone());
+
+    match repo.get_project_by_id(*project_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: "Project not found".to_string(),
+            });
+        }
+        Err(e) => {
+            log::error!("Failed to get project: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let systems = match repo.get_star_systems_by_project(*project_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get star systems: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let stars = match repo.get_stars_by_project(*project_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get stars: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let planets = match repo.get_planets_by_project(*project_id).await {
+        Ok(p) => p,
+        Err(e) => {
+            log::error!("Failed to get planets: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let systems: HashMap<Uuid, _> = systems.into_iter().filter_map(|s| Some((s.id?, s))).collect();
+    let stars: HashMap<Uuid, _> = stars.into_iter().filter_map(|s| Some((s.id?, s))).collect();
+
+    let mut matches: Vec<PlanetMatch> = planets
+        .iter()
+        .filter(|planet| {
+            planet_type.is_none_or(|t| planet.planet_type == t)
+                && climates.as_ref().is_none_or(|c| c.contains(&planet.profile.climate))
+                && atmosphere.is_none_or(|a| planet.profile.atmosphere == a)
+                && query.min_water_fraction.is_none_or(|w| planet.profile.water_fraction >= w)
+                && query.tidally_locked.is_none_or(|locked| planet.profile.tidally_locked == locked)
+        })
+        .filter_map(|planet| {
+            let star = stars.get(&planet.star_id)?;
+            let system = systems.get(&planet.system_id)?;
+            if spectral_classes.as_ref().is_some_and(|classes| !classes.contains(&star.spectral_class)) {
+                return None;
+            }
+
+            Some(PlanetMatch {
+                system_name: system.name.clone(),
+                spectral_class: star.spectral_type(),
+                position: Position {
+                    x: system.x_ly,
+                    y: system.y_ly,
+                    z: system.z_ly,
+                },
+                planet: planet_detail(planet),
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.planet
+            .habitability
+            .total_cmp(&a.planet.habitability)
+            .then_with(|| a.planet.name.cmp(&b.planet.name))
+    });
+    let total_matches = matches.len();
+    matches.truncate(query.limit());
+
+    HttpResponse::Ok().json(PlanetSearchResponse {
+        project_id: *project_id,
+        total_matches,
+        planets: matches,
+    })
+}
+
+/// GET /api/projects/{id}/systems/{system}
+/// Get one star system as a tree: system → stars and barycentres → planets
+/// and belts → moons
+#[get("/projects/{id}/systems/{system}")]
+async fn get_system_tree(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String)>,
+) -> impl Responder {
+    let (project_id, system_name) = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let system = match repo.get_star_system_by_name(project_id, &system_name).await {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: format!("Star system '{}' not found in project", system_name),
+            });
+        }
+        Err(e) => {
+            log::error!("Failed to get star system: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+    let system_id = system.id.unwrap();
+
+    let stars = match repo.get_stars_by_system(system_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get stars: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let orbits = match repo.get_orbits_by_system(system_id).await {
+        Ok(o) => o,
+        Err(e) => {
+            log::error!("Failed to get orbits: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let planets = match repo.get_planets_by_system(system_id).await {
+        Ok(p) => p,
+        Err(e) => {
+            log::error!("Failed to get planets: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let moons = match repo.get_moons_by_system(system_id).await {
+        Ok(m) => m,
+        Err(e) => {
+            log::error!("Failed to get moons: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let belts = match repo.get_belts_by_system(system_id).await {
+        Ok(b) => b,
+        Err(e) => {
+            log::error!("Failed to get belts: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let planet_node = |planet: &Planet| {
+        let moon_nodes = moons
+            .iter()
+            .filter(|m| Some(m.planet_id) == planet.id)
+            .map(|m| BodyNode {
+                body: Body::Moon(MoonDetail {
+                    name: m.name.clone(),
+                    orbit_index: m.orbit_index,
+                    semi_major_axis_km: m.semi_major_axis_km,
+                    eccentricity: m.eccentricity,
+                    period_days: m.period_days,
+                    mass_earth: m.mass_earth,
+                    radius_km: m.radius_km,
+                }),
+                children: Vec::new(),
+            })
+            .collect();
+        BodyNode { body: Body::Planet(planet_detail(planet)), children: moon_nodes }
+    };
+
+    let mut children: Vec<BodyNode> = stars
+        .iter()
+        .map(|star| {
+            // Planets and belts interleaved outwards from the star
+            let mut children: Vec<(f64, BodyNode)> = planets
+                .iter()
+                .filter(|p| Some(p.star_id) == star.id && p.barycentre_orbit_id.is_none())
+                .map(|planet| (planet.semi_major_axis_au, planet_node(planet)))
+                .chain(belts.iter().filter(|b| Some(b.star_id) == star.id).map(|b| {
+                    let node = BodyNode {
+                        body: Body::Belt(BeltDetail {
+                            name: b.name.clone(),
+                            belt_type: b.belt_type.to_string(),
+                            inner_radius_au: b.inner_radius_au,
+                            outer_radius_au: b.outer_radius_au,
+                            mass_earth: b.mass_earth,
+                        }),
+                        children: Vec::new(),
+                    };
+                    (b.inner_radius_au, node)
+                }))
+                .collect();
+            children.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            BodyNode {
+                body: Body::Star(star_detail(star)),
+                children: children.into_iter().map(|(_, node)| node).collect(),
+            }
+        })
+        .collect();
+
+    // Circumbinary planets hang off the barycentre they orbit
+    let mut barycentres: Vec<Uuid> = Vec::new();
+    for id in planets.iter().filter_map(|p| p.barycentre_orbit_id) {
+        if !barycentres.contains(&id) {
+            barycentres.push(id);
+        }
+    }
+    for id in barycentres {
+        let orbiting: Vec<&Planet> = planets.iter().filter(|p| p.barycentre_orbit_id == Some(id)).collect();
+        children.push(BodyNode {
+            body: Body::Barycentre {
+                name: orbiting[0].host_name().to_string(),
+                habitable_zone: host_habitable_zone(orbiting[0], &stars, &orbits),
+            },
+            children: orbiting.into_iter().map(planet_node).collect(),
+        });
+    }
+
+    HttpResponse::Ok().json(SystemTreeResponse {
+        project_id,
+        root: BodyNode {
+            body: Body::System {
+                name: system.name,
+                system_type: system.system_type.to_string(),
+                position: Position {
+                    x: system.x_ly,
+                    y: system.y_ly,
+                    z: system.z_ly,
+                },
+            },
+            children,
+        },
+    })
+}
+
+/// Observer system and its whole sky (RA/Dec, brightest first), or the
+/// error response to return
+async fn observer_sky(repo: &Repository, project_id: Uuid, system_name: &str) -> Result<(StarSystem, Vec<SkyStar>), HttpResponse> {
+    let observer = match repo.get_star_system_by_name(project_id, system_name).await {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound().json(ErrorResponse {
+                error: format!("Star system '{}' not found in project", system_name),
+            }));
+        }
+        Err(e) => {
+            log::error!("Failed to get star system: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }));
+        }
+    };
+
+    let systems = match repo.get_star_systems_by_project(project_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get star systems: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }));
+        }
+    };
+
+    let stars = match repo.get_stars_by_project(project_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get stars: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }));
+        }
+    };
+
+    let sky = sky_view(&observer, &systems, &stars, SkyFrame::Equatorial, f64::INFINITY);
+    Ok((observer, sky))
+}
+
+fn constellation_detail(constellation: &Constellation, sky: &[SkyStar]) -> ConstellationDetail {
+    let by_id: HashMap<Uuid, &SkyStar> = sky.iter().map(|s| (s.star_id, s)).collect();
+
+    let stars = constellation
+        .stars
+        .iter()
+        .filter_map(|member| {
+            let seen = by_id.get(&member.star_id)?;
+            Some(ConstellationStarDetail {
+                designation: member.designation.clone(),
+                star: seen.name.clone(),
+                system_name: seen.system_name.clone(),
+                spectral_class: seen.spectral_class.clone(),
+                ra_deg: seen.longitude_deg,
+                dec_deg: seen.latitude_deg,
+                apparent_magnitude: seen.apparent_magnitude,
+            })
+        })
+        .collect();
+    let lines = constellation
+        .stars
+        .iter()
+        .filter_map(|member| {
+            let from = by_id.get(&member.star_id)?;
+            let to = by_id.get(&member.line_to_star_id?)?;
+            Some([from.name.clone(), to.name.clone()])
+        })
+        .collect();
+
+    ConstellationDetail {
+        name: constellation.name.clone(),
+        stars,
+        lines,
+    }
+}
+
+fn star_detail(star: &Star) -> StarDetail {
+    StarDetail {
+        name: star.name.clone(),
+        spectral_class: star.spectral_type(),
+        luminosity_class: star.luminosity_class.map(|lc| lc.to_string()),
+        temperature_k: star.temperature_k,
+        mass_solar: star.mass_solar,
+        radius_solar: star.radius_solar,
+        luminosity_solar: star.luminosity_solar,
+        age_gyr: star.age_gyr,
+        metallicity_feh: star.metallicity_feh,
+        evolutionary_stage: star.evolutionary_stage.to_string(),
+        habitable_zone: star.habitable_zone,
+        photometry: star.photometry.clone(),
+    }
+}
+
+fn planet_detail(planet: &Planet) -> PlanetDetail {
+    PlanetDetail {
+        name: planet.name.clone(),
+        star: planet.host_name().to_string(),
+        circumbinary: planet.barycentre_orbit_id.is_some(),
+        orbit_index: planet.orbit_index,
+        planet_type: planet.planet_type.to_string(),
+        semi_major_axis_au: planet.semi_major_axis_au,
+        eccentricity: planet.eccentricity,
+        period_years: planet.period_years,
+        mass_earth: planet.mass_earth,
+        radius_earth: planet.radius_earth,
+        ring: planet.ring,
+        insolation_earth: planet.insolation_earth,
+        habitability: planet.habitability,
+        profile: planet.profile,
+    }
+}
+
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(generate_project)
+        .service(import_project)
         .service(list_projects)
         .service(get_project)
-        .service(get_project_stars);
+        .service(get_project_stars)
+        .service(get_project_zones)
+        .service(get_system_tree)
+        .service(get_system_planets)
+        .service(get_system_sky)
+        .service(generate_system_constellations)
+        .service(get_system_constellations)
+        .service(get_system_skybox)
+        .service(get_habitable_planets)
+        .service(search_planets);
 }
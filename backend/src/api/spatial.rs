@@ -0,0 +1,273 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+use crate::database::{DbPool, Repository};
+use crate::models::{
+    BoxQuery, CylinderQuery, ErrorResponse, NearestQuery, Position, Position3D, QueryPoint, RadiusQuery,
+    SpatialMatch, SpatialQueryResponse,
+};
+use crate::spatial::{ProjectIndex, SpatialCache};
+
+/// Spatial index of a project, built from the database on first use
+pub async fn project_index(
+    pool: &DbPool,
+    cache: &SpatialCache,
+    project_id: Uuid,
+) -> Result<Arc<ProjectIndex>, HttpResponse> {
+    if let Some(index) = cache.get(project_id) {
+        return Ok(index);
+    }
+
+    let repo = Repository::new(pool.clone());
+    let project = match repo.get_project_by_id(project_id).await {
+        Ok(Some(p)) => p,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound().json(ErrorResponse {
+                error: "Project not found".to_string(),
+            }));
+        }
+        Err(e) => {
+            log::error!("Failed to get project: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }));
+        }
+    };
+
+    let systems = match repo.get_star_systems_by_project(project_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("Failed to get star systems: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }));
+        }
+    };
+
+    let start_time = Instant::now();
+    let complete = systems.len() == project.num_star_systems as usize;
+    let index = ProjectIndex::new(systems);
+    log::info!(
+        "Built spatial index for project {}: {} systems in {}ms",
+        project_id,
+        index.systems.len(),
+        start_time.elapsed().as_millis()
+    );
+
+    // A project still being written is indexed for this request only
+    if complete {
+        Ok(cache.insert(project_id, index))
+    } else {
+        Ok(Arc::new(index))
+    }
+}
+
+/// Position of a query point, and the system it names if any
+fn resolve_point(index: &ProjectIndex, point: &QueryPoint) -> Result<(Position3D, Option<usize>), String> {
+    match point {
+        QueryPoint::Position(position) => Ok((*position, None)),
+        QueryPoint::System(name) => index
+            .find(name)
+            .map(|i| (index.position(i), Some(i)))
+            .ok_or_else(|| format!("Star system '{}' not found in project", name)),
+    }
+}
+
+fn spatial_match(index: &ProjectIndex, i: usize, distance_ly: f64, along_axis_ly: Option<f64>) -> SpatialMatch {
+    let system = &index.systems[i];
+    SpatialMatch {
+        system_name: system.name.clone(),
+        system_type: system.system_type.to_string(),
+        position: Position { x: system.x_ly, y: system.y_ly, z: system.z_ly },
+        distance_ly,
+        along_axis_ly,
+    }
+}
+
+/// GET /api/projects/{id}/spatial/nearest
+/// The k systems closest to a point or to another system
+#[get("/projects/{id}/spatial/nearest")]
+async fn nearest_systems(
+    pool: web::Data<DbPool>,
+    cache: web::Data<SpatialCache>,
+    path: web::Path<Uuid>,
+    query: web::Query<NearestQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+
+    let project_id = path.into_inner();
+    let index = match project_index(&pool, &cache, project_id).await {
+        Ok(index) => index,
+        Err(response) => return response,
+    };
+
+    let start_time = Instant::now();
+    let (origin, origin_system) = match resolve_point(&index, &query.point().unwrap()) {
+        Ok(point) => point,
+        Err(e) => return HttpResponse::NotFound().json(ErrorResponse { error: e }),
+    };
+
+    // One extra so the origin system can be dropped
+    let k = query.k();
+    let systems: Vec<SpatialMatch> = index
+        .tree
+        .nearest(&origin, k + 1)
+        .into_iter()
+        .filter(|(i, _)| Some(*i) != origin_system)
+        .take(k)
+        .map(|(i, distance)| spatial_match(&index, i, distance, None))
+        .collect();
+
+    HttpResponse::Ok().json(SpatialQueryResponse {
+        project_id,
+        total: systems.len(),
+        systems,
+        query_time_us: start_time.elapsed().as_micros(),
+    })
+}
+
+/// GET /api/projects/{id}/spatial/radius
+/// Systems within a distance of a point or another system, nearest first
+#[get("/projects/{id}/spatial/radius")]
+async fn systems_within_radius(
+    pool: web::Data<DbPool>,
+    cache: web::Data<SpatialCache>,
+    path: web::Path<Uuid>,
+    query: web::Query<RadiusQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+
+    let project_id = path.into_inner();
+    let index = match project_index(&pool, &cache, project_id).await {
+        Ok(index) => index,
+        Err(response) => return response,
+    };
+
+    let start_time = Instant::now();
+    let (center, center_system) = match resolve_point(&index, &query.point().unwrap()) {
+        Ok(point) => point,
+        Err(e) => return HttpResponse::NotFound().json(ErrorResponse { error: e }),
+    };
+
+    let mut found = index.tree.within_radius(&center, query.radius_ly);
+    found.retain(|(i, _)| Some(*i) != center_system);
+    let total = found.len();
+    let systems = found
+        .into_iter()
+        .take(query.limit())
+        .map(|(i, distance)| spatial_match(&index, i, distance, None))
+        .collect();
+
+    HttpResponse::Ok().json(SpatialQueryResponse {
+        project_id,
+        total,
+        systems,
+        query_time_us: start_time.elapsed().as_micros(),
+    })
+}
+
+/// GET /api/projects/{id}/spatial/box
+/// Systems inside an axis-aligned box, nearest its centre first
+#[get("/projects/{id}/spatial/box")]
+async fn systems_in_box(
+    pool: web::Data<DbPool>,
+    cache: web::Data<SpatialCache>,
+    path: web::Path<Uuid>,
+    query: web::Query<BoxQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+
+    let project_id = path.into_inner();
+    let index = match project_index(&pool, &cache, project_id).await {
+        Ok(index) => index,
+        Err(response) => return response,
+    };
+
+    let start_time = Instant::now();
+    let center = query.center();
+    let mut found: Vec<(usize, f64)> = index
+        .tree
+        .within_box(&query.min(), &query.max())
+        .into_iter()
+        .map(|i| {
+            let p = index.position(i);
+            (i, ((p.x - center.x).powi(2) + (p.y - center.y).powi(2) + (p.z - center.z).powi(2)).sqrt())
+        })
+        .collect();
+    found.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+
+    let total = found.len();
+    let systems = found
+        .into_iter()
+        .take(query.limit())
+        .map(|(i, distance)| spatial_match(&index, i, distance, None))
+        .collect();
+
+    HttpResponse::Ok().json(SpatialQueryResponse {
+        project_id,
+        total,
+        systems,
+        query_time_us: start_time.elapsed().as_micros(),
+    })
+}
+
+/// GET /api/projects/{id}/spatial/cylinder
+/// Systems within a distance of the segment between two points or systems,
+/// in order along it
+#[get("/projects/{id}/spatial/cylinder")]
+async fn systems_in_cylinder(
+    pool: web::Data<DbPool>,
+    cache: web::Data<SpatialCache>,
+    path: web::Path<Uuid>,
+    query: web::Query<CylinderQuery>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+
+    let project_id = path.into_inner();
+    let index = match project_index(&pool, &cache, project_id).await {
+        Ok(index) => index,
+        Err(response) => return response,
+    };
+
+    let start_time = Instant::now();
+    let (start, _) = match resolve_point(&index, &query.start().unwrap()) {
+        Ok(point) => point,
+        Err(e) => return HttpResponse::NotFound().json(ErrorResponse { error: e }),
+    };
+    let (end, _) = match resolve_point(&index, &query.end().unwrap()) {
+        Ok(point) => point,
+        Err(e) => return HttpResponse::NotFound().json(ErrorResponse { error: e }),
+    };
+
+    let found = index.tree.within_cylinder(&start, &end, query.radius_ly);
+    let total = found.len();
+    let systems = found
+        .into_iter()
+        .take(query.limit())
+        .map(|hit| spatial_match(&index, hit.index, hit.axis_distance, Some(hit.along)))
+        .collect();
+
+    HttpResponse::Ok().json(SpatialQueryResponse {
+        project_id,
+        total,
+        systems,
+        query_time_us: start_time.elapsed().as_micros(),
+    })
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(nearest_systems)
+        .service(systems_within_radius)
+        .service(systems_in_box)
+        .service(systems_in_cylinder);
+}
@@ -0,0 +1,283 @@
+use actix_web::{get, post, web, HttpResponse, Responder};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::database::{DbPool, Repository};
+use crate::generator::{partition_territories, random_seed, seed_capitals, seeded_rng, CapitalSeed};
+use crate::models::{
+    ContestedResponse, ContestedSystem, ErrorResponse, Faction, FactionSummary, FactionsResponse, Position,
+    StarSystem, TerritoryRequest, TerritoryResponse, TerritorySystem,
+};
+use crate::spatial::SpatialCache;
+use super::lanes::project_systems;
+
+/// A project's systems and factions, or the error response to send
+async fn project_factions(repo: &Repository, project_id: Uuid) -> Result<(Vec<StarSystem>, Vec<Faction>), HttpResponse> {
+    let (_, systems) = project_systems(repo, project_id).await?;
+    match repo.get_factions_by_project(project_id).await {
+        Ok(factions) => Ok((systems, factions)),
+        Err(e) => {
+            log::error!("Failed to get factions: {}", e);
+            Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }))
+        }
+    }
+}
+
+/// Summary of every faction, skipping any whose capital is not among
+/// `systems` (a partition replaced between the two reads)
+fn factions_response(project_id: Uuid, systems: &[StarSystem], factions: &[Faction]) -> FactionsResponse {
+    let names: HashMap<Uuid, &str> = systems.iter().map(|s| (s.id.unwrap(), s.name.as_str())).collect();
+    let mut summaries: Vec<FactionSummary> = factions
+        .iter()
+        .filter_map(|faction| {
+            let capital = names.get(&faction.capital_system_id)?;
+            let held: Vec<&StarSystem> = systems.iter().filter(|s| s.faction_id == faction.id).collect();
+            Some(FactionSummary {
+                name: faction.name.clone(),
+                capital: capital.to_string(),
+                strength: faction.strength,
+                num_systems: held.len(),
+                num_border_systems: held.iter().filter(|s| s.border).count(),
+                num_contested_systems: held.iter().filter(|s| s.contested_by.is_some()).count(),
+            })
+        })
+        .collect();
+    summaries.sort_by(|a, b| b.num_systems.cmp(&a.num_systems).then_with(|| a.name.cmp(&b.name)));
+
+    FactionsResponse {
+        project_id,
+        num_factions: summaries.len(),
+        unclaimed_systems: systems.iter().filter(|s| s.faction_id.is_none()).count(),
+        contested_systems: systems.iter().filter(|s| s.contested_by.is_some()).count(),
+        factions: summaries,
+    }
+}
+
+/// POST /api/projects/{id}/territories
+/// Divide a project's systems among factions, replacing any it already has
+#[post("/projects/{id}/territories")]
+async fn partition_project(
+    pool: web::Data<DbPool>,
+    cache: web::Data<SpatialCache>,
+    path: web::Path<Uuid>,
+    req: web::Json<TerritoryRequest>,
+) -> impl Responder {
+    if let Err(e) = req.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+    let params = req.params().unwrap();
+
+    let project_id = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let (project, systems) = match project_systems(&repo, project_id).await {
+        Ok(p) => p,
+        Err(response) => return response,
+    };
+    let lanes = match repo.get_lanes_by_project(project_id).await {
+        Ok(l) => l,
+        Err(e) => {
+            log::error!("Failed to get lanes: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+    let seed = req.seed.or(project.seed).unwrap_or_else(random_seed);
+    let mut rng = seeded_rng(seed);
+
+    let capitals = match (&req.capitals, req.num_factions) {
+        (Some(capitals), _) => {
+            let by_name: HashMap<&str, usize> = systems.iter().enumerate().map(|(i, s)| (s.name.as_str(), i)).collect();
+            let mut seeds = Vec::with_capacity(capitals.len());
+            for (i, capital) in capitals.iter().enumerate() {
+                let Some(&index) = by_name.get(capital.system.as_str()) else {
+                    return HttpResponse::NotFound().json(ErrorResponse {
+                        error: format!("Star system '{}' not found in project", capital.system),
+                    });
+                };
+                seeds.push(CapitalSeed {
+                    name: req.faction_name(i),
+                    capital: index,
+                    strength: capital.strength.unwrap_or(1.0),
+                });
+            }
+            seeds
+        }
+        (None, count) => seed_capitals(&mut rng, &systems, count.unwrap_or(1)),
+    };
+
+    // Lane floods and expansions over a large map take a while; keep them
+    // off the async workers
+    let partition = web::block(move || {
+        partition_territories(&mut rng, project_id, &systems, &lanes, &capitals, &params)
+    });
+    let territories = match partition.await {
+        Ok(Ok(t)) => t,
+        Ok(Err(e)) => {
+            return HttpResponse::BadRequest().json(ErrorResponse {
+                error: format!("Territory error: {}", e),
+            });
+        }
+        Err(e) => {
+            log::error!("Territory partition was cancelled: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to partition territories: {}", e),
+            });
+        }
+    };
+
+    if let Err(e) = repo.replace_territories(project_id, &territories.factions, &territories.systems).await {
+        log::error!("Failed to save territories: {}", e);
+        return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Failed to save territories: {}", e),
+        });
+    }
+    cache.remove(project_id);
+
+    log::info!(
+        "Divided project {} among {} factions by {} (seed {})",
+        project_id,
+        territories.factions.len(),
+        params.method,
+        seed
+    );
+
+    HttpResponse::Ok().json(factions_response(project_id, &territories.systems, &territories.factions))
+}
+
+/// GET /api/projects/{id}/factions
+/// Every faction of a project with the size of its territory
+#[get("/projects/{id}/factions")]
+async fn get_project_factions(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let project_id = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    match project_factions(&repo, project_id).await {
+        Ok((systems, factions)) => HttpResponse::Ok().json(factions_response(project_id, &systems, &factions)),
+        Err(response) => response,
+    }
+}
+
+/// Systems of one faction, nearest its capital first, keeping those
+/// `include` accepts
+async fn faction_systems(
+    pool: &DbPool,
+    project_id: Uuid,
+    faction_name: &str,
+    include: impl Fn(&StarSystem) -> bool,
+) -> HttpResponse {
+    let repo = Repository::new(pool.clone());
+    let (systems, factions) = match project_factions(&repo, project_id).await {
+        Ok(f) => f,
+        Err(response) => return response,
+    };
+    let Some(faction) = factions.iter().find(|f| f.name == faction_name) else {
+        return HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("Faction '{}' not found in project", faction_name),
+        });
+    };
+
+    let rivals: HashMap<Uuid, &str> = factions.iter().map(|f| (f.id.unwrap(), f.name.as_str())).collect();
+    let Some(capital) = systems.iter().find(|s| s.id == Some(faction.capital_system_id)) else {
+        log::error!("Capital of faction {} is missing from project {}", faction_name, project_id);
+        return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Capital of faction '{}' not found in project", faction_name),
+        });
+    };
+    let mut territory: Vec<TerritorySystem> = systems
+        .iter()
+        .filter(|s| s.faction_id == faction.id && include(s))
+        .map(|s| TerritorySystem {
+            system_name: s.name.clone(),
+            position: Position { x: s.x_ly, y: s.y_ly, z: s.z_ly },
+            distance_to_capital_ly: ((s.x_ly - capital.x_ly).powi(2)
+                + (s.y_ly - capital.y_ly).powi(2)
+                + (s.z_ly - capital.z_ly).powi(2))
+            .sqrt(),
+            border: s.border,
+            contested_by: s.contested_by.and_then(|r| rivals.get(&r)).map(|r| r.to_string()),
+        })
+        .collect();
+    territory.sort_by(|a, b| {
+        a.distance_to_capital_ly.total_cmp(&b.distance_to_capital_ly).then_with(|| a.system_name.cmp(&b.system_name))
+    });
+
+    HttpResponse::Ok().json(TerritoryResponse {
+        project_id,
+        faction: faction.name.clone(),
+        capital: capital.name.clone(),
+        total: territory.len(),
+        systems: territory,
+    })
+}
+
+/// GET /api/projects/{id}/factions/{name}/territory
+/// Every system a faction holds
+#[get("/projects/{id}/factions/{name}/territory")]
+async fn get_faction_territory(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String)>,
+) -> impl Responder {
+    let (project_id, name) = path.into_inner();
+    faction_systems(&pool, project_id, &name, |_| true).await
+}
+
+/// GET /api/projects/{id}/factions/{name}/borders
+/// Systems of a faction next to another's territory or unclaimed space
+#[get("/projects/{id}/factions/{name}/borders")]
+async fn get_faction_borders(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String)>,
+) -> impl Responder {
+    let (project_id, name) = path.into_inner();
+    faction_systems(&pool, project_id, &name, |s| s.border).await
+}
+
+/// GET /api/projects/{id}/territories/contested
+/// Systems a second faction has a nearly equal claim to
+#[get("/projects/{id}/territories/contested")]
+async fn get_contested_systems(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let project_id = path.into_inner();
+    let repo = Repository::new(pool.get_ref().clone());
+
+    let (systems, factions) = match project_factions(&repo, project_id).await {
+        Ok(f) => f,
+        Err(response) => return response,
+    };
+    let names: HashMap<Uuid, &str> = factions.iter().map(|f| (f.id.unwrap(), f.name.as_str())).collect();
+
+    let contested: Vec<ContestedSystem> = systems
+        .iter()
+        .filter_map(|s| {
+            Some(ContestedSystem {
+                system_name: s.name.clone(),
+                owner: names.get(&s.faction_id?)?.to_string(),
+                contested_by: names.get(&s.contested_by?)?.to_string(),
+                border: s.border,
+            })
+        })
+        .collect();
+
+    HttpResponse::Ok().json(ContestedResponse {
+        project_id,
+        total: contested.len(),
+        systems: contested,
+    })
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(partition_project)
+        .service(get_project_factions)
+        .service(get_faction_territory)
+        .service(get_faction_borders)
+        .service(get_contested_systems);
+}
@@ -1,7 +1,8 @@
 use uuid::Uuid;
-use tokio_postgres::Row;
+use tokio_postgres::{Row, Transaction};
 use crate::database::DbPool;
-use crate::models::{Project, StarSystem, Star, SpectralClass};
+use crate::models::{Project, CatalogueFormat, StarSystem, Star, Orbit, Planet, PlanetType, WorldProfile, Atmosphere, Climate, Moon, Ring, Belt, BeltType, Constellation, ConstellationStar, Lane, Faction, Economy, SystemMarket, TradeFlow, EconomyTick, TradeNetwork, Resource, Amounts, HabitableZone, Photometry, SpectralClass, LuminosityClass, EvolutionaryStage, DensityZone, ZoneType, Axis};
+use crate::generator::GenerationParams;
 use anyhow::{Result, Context};
 
 /// Repository for database operations
@@ -23,34 +24,55 @@ impl Repository {
         &self,
         name: &str,
         description: Option<&str>,
-        distribution_type: &str,
-        num_star_systems: i32,
-        cube_params: Option<(f64, f64, f64)>,
-        sphere_params: Option<f64>,
+        params: &GenerationParams,
     ) -> Result<Uuid> {
         let client = self.pool.get().await?;
 
+        let distribution = &params.distribution;
+        let num_star_systems = params.num_systems as i32;
+
+        let cube_params = distribution.cube_params();
         let size_x = cube_params.map(|(x, _, _)| x);
         let size_y = cube_params.map(|(_, y, _)| y);
         let size_z = cube_params.map(|(_, _, z)| z);
-        let radius = sphere_params;
+        // BIGINT is signed; store the seed's bit pattern
+        let seed = params.seed as i64;
 
         let row = client
             .query_one(
                 "INSERT INTO stellar.projects
                  (name, description, distribution_type, num_star_systems,
-                  size_x_ly, size_y_ly, size_z_ly, radius_ly)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                  size_x_ly, size_y_ly, size_z_ly, radius_ly, seed,
+                  arm_count, pitch_angle_deg, arm_spread_ly, scale_length_ly,
+                  scale_height_ly, effective_radius_ly, axis_ratio, min_separation_ly, imf,
+                  star_formation, burst_age_gyr, burst_spread_gyr, sfh_timescale_gyr)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9,
+                         $10, $11, $12, $13, $14, $15, $16, $17, $18,
+                         $19, $20, $21, $22)
                  RETURNING id",
                 &[
                     &name,
                     &description,
-                    &distribution_type,
+                    &distribution.to_string(),
                     &num_star_systems,
                     &size_x,
                     &size_y,
                     &size_z,
-                    &radius,
+                    &distribution.radius(),
+                    &seed,
+                    &distribution.arm_count(),
+                    &distribution.pitch_angle_deg(),
+                    &distribution.arm_spread(),
+                    &distribution.scale_length(),
+                    &distribution.scale_height(),
+                    &distribution.effective_radius(),
+                    &distribution.axis_ratio(),
+                    &params.min_separation_ly,
+                    &params.imf.to_string(),
+                    &params.star_formation.to_string(),
+                    &params.star_formation.burst_age_gyr(),
+                    &params.star_formation.burst_spread_gyr(),
+                    &params.star_formation.timescale_gyr(),
                 ],
             )
             .await
@@ -68,7 +90,11 @@ impl Repository {
                 "SELECT id, name, description, created_at, updated_at,
                         distribution_type, num_star_systems,
                         size_x_ly, size_y_ly, size_z_ly, radius_ly,
-                        total_stars, solo_systems, binary_systems, trinary_systems
+                        total_stars, solo_systems, binary_systems, trinary_systems,
+                        seed, arm_count, pitch_angle_deg, arm_spread_ly, scale_length_ly,
+                        scale_height_ly, effective_radius_ly, axis_ratio, min_separation_ly, imf,
+                        star_formation, burst_age_gyr, burst_spread_gyr, sfh_timescale_gyr,
+                        higher_order_systems, catalogue, completeness_radius_ly
                  FROM stellar.projects
                  ORDER BY created_at DESC",
                 &[],
@@ -87,7 +113,11 @@ impl Repository {
                 "SELECT id, name, description, created_at, updated_at,
                         distribution_type, num_star_systems,
                         size_x_ly, size_y_ly, size_z_ly, radius_ly,
-                        total_stars, solo_systems, binary_systems, trinary_systems
+                        total_stars, solo_systems, binary_systems, trinary_systems,
+                        seed, arm_count, pitch_angle_deg, arm_spread_ly, scale_length_ly,
+                        scale_height_ly, effective_radius_ly, axis_ratio, min_separation_ly, imf,
+                        star_formation, burst_age_gyr, burst_spread_gyr, sfh_timescale_gyr,
+                        higher_order_systems, catalogue, completeness_radius_ly
                  FROM stellar.projects
                  WHERE id = $1",
                 &[&project_id],
@@ -97,6 +127,37 @@ impl Repository {
         Ok(row.map(|r| self.row_to_project(&r)))
     }
 
+    /// Record the star catalogue an imported project was read from
+    pub async fn set_project_catalogue(
+        &self,
+        project_id: Uuid,
+        format: CatalogueFormat,
+        completeness_radius_ly: Option<f64>,
+    ) -> Result<()> {
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                "UPDATE stellar.projects SET catalogue = $2, completeness_radius_ly = $3 WHERE id = $1",
+                &[&project_id, &format.to_string(), &completeness_radius_ly],
+            )
+            .await
+            .context("Failed to record project catalogue")?;
+
+        Ok(())
+    }
+
+    /// Delete a project and everything generated for it
+    pub async fn delete_project(&self, project_id: Uuid) -> Result<()> {
+        let client = self.pool.get().await?;
+
+        client
+            .execute("DELETE FROM stellar.projects WHERE id = $1", &[&project_id])
+            .await?;
+
+        Ok(())
+    }
+
     /// Update project statistics
     pub async fn update_project_stats(&self, project_id: Uuid) -> Result<()> {
         let client = self.pool.get().await?;
@@ -111,6 +172,66 @@ impl Repository {
         Ok(())
     }
 
+    // =========================================================================
+    // DENSITY ZONE OPERATIONS
+    // =========================================================================
+
+    /// Insert density zones in batch
+    pub async fn insert_density_zones(&self, zones: &[DensityZone]) -> Result<Vec<Uuid>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        let mut ids = Vec::with_capacity(zones.len());
+
+        for zone in zones {
+            let row = transaction
+                .query_one(
+                    "INSERT INTO stellar.density_zones
+                     (id, project_id, zone_index, zone_type, center_x_ly, center_y_ly, center_z_ly,
+                      radius_ly, star_share, gradient_axis, gradient_strength)
+                     VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                     RETURNING id",
+                    &[
+                        &zone.id,
+                        &zone.project_id,
+                        &zone.zone_index,
+                        &zone.zone_type.to_string(),
+                        &zone.center_x_ly,
+                        &zone.center_y_ly,
+                        &zone.center_z_ly,
+                        &zone.radius_ly,
+                        &zone.star_share,
+                        &zone.gradient_axis.map(|a| a.to_string()),
+                        &zone.gradient_strength,
+                    ],
+                )
+                .await?;
+
+            ids.push(row.get(0));
+        }
+
+        transaction.commit().await?;
+        Ok(ids)
+    }
+
+    /// Get density zones for a project
+    pub async fn get_density_zones_by_project(&self, project_id: Uuid) -> Result<Vec<DensityZone>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, project_id, zone_index, zone_type, center_x_ly, center_y_ly, center_z_ly,
+                        radius_ly, star_share, gradient_axis, gradient_strength
+                 FROM stellar.density_zones
+                 WHERE project_id = $1
+                 ORDER BY zone_index",
+                &[&project_id],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_density_zone(&row)).collect())
+    }
+
     // =========================================================================
     // STAR SYSTEM OPERATIONS
     // =========================================================================
@@ -126,16 +247,19 @@ impl Repository {
             let row = transaction
                 .query_one(
                     "INSERT INTO stellar.star_systems
-                     (project_id, name, system_type, position, x_ly, y_ly, z_ly)
-                     VALUES ($1, $2, $3, ST_MakePoint($4, $5, $6), $4, $5, $6)
+                     (id, project_id, name, system_type, position, x_ly, y_ly, z_ly, zone_id)
+                     VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4,
+                             ST_MakePoint($5, $6, $7), $5, $6, $7, $8)
                      RETURNING id",
                     &[
+                        &system.id,
                         &system.project_id,
                         &system.name,
                         &system.system_type.to_string(),
                         &system.x_ly,
                         &system.y_ly,
                         &system.z_ly,
+                        &system.zone_id,
                     ],
                 )
                 .await?;
@@ -153,7 +277,8 @@ impl Repository {
 
         let rows = client
             .query(
-                "SELECT id, project_id, name, system_type, x_ly, y_ly, z_ly
+                "SELECT id, project_id, name, system_type, x_ly, y_ly, z_ly, zone_id,
+                        faction_id, contested_by_faction_id, border
                  FROM stellar.star_systems
                  WHERE project_id = $1
                  ORDER BY name",
@@ -164,6 +289,23 @@ impl Repository {
         Ok(rows.into_iter().map(|row| self.row_to_star_system(&row)).collect())
     }
 
+    /// Get a project's star system by name (e.g. S0001)
+    pub async fn get_star_system_by_name(&self, project_id: Uuid, name: &str) -> Result<Option<StarSystem>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                "SELECT id, project_id, name, system_type, x_ly, y_ly, z_ly, zone_id,
+                        faction_id, contested_by_faction_id, border
+                 FROM stellar.star_systems
+                 WHERE project_id = $1 AND name = $2",
+                &[&project_id, &name],
+            )
+            .await?;
+
+        Ok(row.map(|r| self.row_to_star_system(&r)))
+    }
+
     // =========================================================================
     // STAR OPERATIONS
     // =========================================================================
@@ -179,21 +321,38 @@ impl Repository {
             let row = transaction
                 .query_one(
                     "INSERT INTO stellar.stars
-                     (system_id, project_id, name, spectral_class, spectral_subclass,
-                      luminosity_class, temperature_k, mass_solar, radius_solar, luminosity_solar)
-                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                     (id, system_id, project_id, name, spectral_class, spectral_subclass,
+                      luminosity_class, temperature_k, mass_solar, radius_solar, luminosity_solar,
+                      age_gyr, metallicity_feh, evolutionary_stage,
+                      hz_optimistic_inner_au, hz_conservative_inner_au, hz_conservative_outer_au, hz_optimistic_outer_au,
+                      absolute_magnitude_v, bolometric_correction, color_index_bv, color_hex, component)
+                     VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4, $5, $6, $7, $8, $9, $10, $11,
+                             $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23)
                      RETURNING id",
                     &[
+                        &star.id,
                         &star.system_id,
                         &star.project_id,
                         &star.name,
-                        &star.spectral_class.to_char().to_string(),
+                        &star.spectral_class.code(),
                         &star.spectral_subclass,
-                        &star.luminosity_class,
+                        &star.luminosity_class.map(|lc| lc.code()),
                         &star.temperature_k,
                         &star.mass_solar,
                         &star.radius_solar,
                         &star.luminosity_solar,
+                        &star.age_gyr,
+                        &star.metallicity_feh,
+                        &star.evolutionary_stage.to_string(),
+                        &star.habitable_zone.map(|hz| hz.optimistic_inner_au),
+                        &star.habitable_zone.map(|hz| hz.conservative_inner_au),
+                        &star.habitable_zone.map(|hz| hz.conservative_outer_au),
+                        &star.habitable_zone.map(|hz| hz.optimistic_outer_au),
+                        &star.photometry.as_ref().map(|p| p.absolute_magnitude_v),
+                        &star.photometry.as_ref().map(|p| p.bolometric_correction),
+                        &star.photometry.as_ref().map(|p| p.color_index_bv),
+                        &star.photometry.as_ref().map(|p| p.color_hex.as_str()),
+                        &star.component,
                     ],
                 )
                 .await?;
@@ -205,6 +364,80 @@ impl Repository {
         Ok(ids)
     }
 
+    /// Insert component orbits of multiple systems (batch)
+    pub async fn insert_orbits(&self, orbits: &[Orbit]) -> Result<Vec<Uuid>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        let mut ids = Vec::with_capacity(orbits.len());
+
+        for orbit in orbits {
+            let row = transaction
+                .query_one(
+                    "INSERT INTO stellar.orbits
+                     (id, system_id, project_id, level, primary_component, secondary_component,
+                      semi_major_axis_au, eccentricity, inclination_deg, period_years, mass_ratio)
+                     VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                     RETURNING id",
+                    &[
+                        &orbit.id,
+                        &orbit.system_id,
+                        &orbit.project_id,
+                        &orbit.level,
+                        &orbit.primary_component,
+                        &orbit.secondary_component,
+                        &orbit.semi_major_axis_au,
+                        &orbit.eccentricity,
+                        &orbit.inclination_deg,
+                        &orbit.period_years,
+                        &orbit.mass_ratio,
+                    ],
+                )
+                .await?;
+
+            ids.push(row.get(0));
+        }
+
+        transaction.commit().await?;
+        Ok(ids)
+    }
+
+    /// Get component orbits for a project, innermost first within each system
+    pub async fn get_orbits_by_project(&self, project_id: Uuid) -> Result<Vec<Orbit>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, system_id, project_id, level, primary_component, secondary_component,
+                        semi_major_axis_au, eccentricity, inclination_deg, period_years, mass_ratio
+                 FROM stellar.orbits
+                 WHERE project_id = $1
+                 ORDER BY system_id, level",
+                &[&project_id],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_orbit(&row)).collect())
+    }
+
+    /// Get component orbits for one star system, innermost first
+    pub async fn get_orbits_by_system(&self, system_id: Uuid) -> Result<Vec<Orbit>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, system_id, project_id, level, primary_component, secondary_component,
+                        semi_major_axis_au, eccentricity, inclination_deg, period_years, mass_ratio
+                 FROM stellar.orbits
+                 WHERE system_id = $1
+                 ORDER BY level",
+                &[&system_id],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_orbit(&row)).collect())
+    }
+
     /// Get stars for a project
     pub async fn get_stars_by_project(&self, project_id: Uuid) -> Result<Vec<Star>> {
         let client = self.pool.get().await?;
@@ -213,7 +446,11 @@ impl Repository {
             .query(
                 "SELECT s.id, s.system_id, s.project_id, s.name,
                         s.spectral_class, s.spectral_subclass, s.luminosity_class,
-                        s.temperature_k, s.mass_solar, s.radius_solar, s.luminosity_solar
+                        s.temperature_k, s.mass_solar, s.radius_solar, s.luminosity_solar,
+                        s.age_gyr, s.metallicity_feh, s.evolutionary_stage,
+                        s.hz_optimistic_inner_au, s.hz_conservative_inner_au,
+                        s.hz_conservative_outer_au, s.hz_optimistic_outer_au,
+                        s.absolute_magnitude_v, s.bolometric_correction, s.color_index_bv, s.color_hex, s.component
                  FROM stellar.stars s
                  WHERE s.project_id = $1
                  ORDER BY s.name",
@@ -232,10 +469,14 @@ impl Repository {
             .query(
                 "SELECT id, system_id, project_id, name,
                         spectral_class, spectral_subclass, luminosity_class,
-                        temperature_k, mass_solar, radius_solar, luminosity_solar
+                        temperature_k, mass_solar, radius_solar, luminosity_solar,
+                        age_gyr, metallicity_feh, evolutionary_stage,
+                        hz_optimistic_inner_au, hz_conservative_inner_au,
+                        hz_conservative_outer_au, hz_optimistic_outer_au,
+                        absolute_magnitude_v, bolometric_correction, color_index_bv, color_hex, component
                  FROM stellar.stars
                  WHERE system_id = $1
-                 ORDER BY name",
+                 ORDER BY component",
                 &[&system_id],
             )
             .await?;
@@ -243,6 +484,711 @@ impl Repository {
         Ok(rows.into_iter().map(|row| self.row_to_star(&row)).collect())
     }
 
+    // =========================================================================
+    // PLANET OPERATIONS
+    // =========================================================================
+
+    /// Insert planets in batch
+    pub async fn insert_planets(&self, planets: &[Planet]) -> Result<Vec<Uuid>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        let mut ids = Vec::with_capacity(planets.len());
+
+        for planet in planets {
+            let row = transaction
+                .query_one(
+                    "INSERT INTO stellar.planets
+                     (id, star_id, system_id, project_id, name, orbit_index, planet_type,
+                      semi_major_axis_au, eccentricity, period_years, mass_earth, radius_earth,
+                      insolation_earth, habitability, ring_inner_radius_km, ring_outer_radius_km,
+                      barycentre_orbit_id, albedo, equilibrium_temperature_k, surface_temperature_k,
+                      atmosphere, surface_pressure_bar, water_fraction, tidally_locked, day_length_hours,
+                      climate)
+                     VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12,
+                             $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26)
+                     RETURNING id",
+                    &[
+                        &planet.id,
+                        &planet.star_id,
+                        &planet.system_id,
+                        &planet.project_id,
+                        &planet.name,
+                        &planet.orbit_index,
+                        &planet.planet_type.to_string(),
+                        &planet.semi_major_axis_au,
+                        &planet.eccentricity,
+                        &planet.period_years,
+                        &planet.mass_earth,
+                        &planet.radius_earth,
+                        &planet.insolation_earth,
+                        &planet.habitability,
+                        &planet.ring.map(|r| r.inner_radius_km),
+                        &planet.ring.map(|r| r.outer_radius_km),
+                        &planet.barycentre_orbit_id,
+                        &planet.profile.albedo,
+                        &planet.profile.equilibrium_temperature_k,
+                        &planet.profile.surface_temperature_k,
+                        &planet.profile.atmosphere.to_string(),
+                        &planet.profile.surface_pressure_bar,
+                        &planet.profile.water_fraction,
+                        &planet.profile.tidally_locked,
+                        &planet.profile.day_length_hours,
+                        &planet.profile.climate.to_string(),
+                    ],
+                )
+                .await?;
+
+            ids.push(row.get(0));
+        }
+
+        transaction.commit().await?;
+        Ok(ids)
+    }
+
+    /// Get planets for a specific star system, innermost first around each
+    /// star, circumbinary planets after the star's own
+    pub async fn get_planets_by_system(&self, system_id: Uuid) -> Result<Vec<Planet>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, star_id, system_id, project_id, name, orbit_index, planet_type,
+                        semi_major_axis_au, eccentricity, period_years, mass_earth, radius_earth,
+                        insolation_earth, habitability, ring_inner_radius_km, ring_outer_radius_km,
+                        barycentre_orbit_id, albedo, equilibrium_temperature_k, surface_temperature_k,
+                        atmosphere, surface_pressure_bar, water_fraction, tidally_locked,
+                        day_length_hours, climate
+                 FROM stellar.planets
+                 WHERE system_id = $1
+                 ORDER BY star_id, barycentre_orbit_id NULLS FIRST, orbit_index",
+                &[&system_id],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_planet(&row)).collect())
+    }
+
+    /// Get every planet of a project
+    pub async fn get_planets_by_project(&self, project_id: Uuid) -> Result<Vec<Planet>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, star_id, system_id, project_id, name, orbit_index, planet_type,
+                        semi_major_axis_au, eccentricity, period_years, mass_earth, radius_earth,
+                        insolation_earth, habitability, ring_inner_radius_km, ring_outer_radius_km,
+                        barycentre_orbit_id, albedo, equilibrium_temperature_k, surface_temperature_k,
+                        atmosphere, surface_pressure_bar, water_fraction, tidally_locked,
+                        day_length_hours, climate
+                 FROM stellar.planets
+                 WHERE project_id = $1
+                 ORDER BY name",
+                &[&project_id],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_planet(&row)).collect())
+    }
+
+    /// Get a project's planets with a habitability score of at least `min_score`
+    pub async fn get_habitable_planets(&self, project_id: Uuid, min_score: f64) -> Result<Vec<Planet>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, star_id, system_id, project_id, name, orbit_index, planet_type,
+                        semi_major_axis_au, eccentricity, period_years, mass_earth, radius_earth,
+                        insolation_earth, habitability, ring_inner_radius_km, ring_outer_radius_km,
+                        barycentre_orbit_id, albedo, equilibrium_temperature_k, surface_temperature_k,
+                        atmosphere, surface_pressure_bar, water_fraction, tidally_locked,
+                        day_length_hours, climate
+                 FROM stellar.planets
+                 WHERE project_id = $1 AND habitability > 0 AND habitability >= $2
+                 ORDER BY habitability DESC",
+                &[&project_id, &min_score],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_planet(&row)).collect())
+    }
+
+    // =========================================================================
+    // MOON AND BELT OPERATIONS
+    // =========================================================================
+
+    /// Insert moons in batch
+    pub async fn insert_moons(&self, moons: &[Moon]) -> Result<Vec<Uuid>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        let mut ids = Vec::with_capacity(moons.len());
+
+        for moon in moons {
+            let row = transaction
+                .query_one(
+                    "INSERT INTO stellar.moons
+                     (id, planet_id, star_id, system_id, project_id, name, orbit_index,
+                      semi_major_axis_km, eccentricity, period_days, mass_earth, radius_km)
+                     VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                     RETURNING id",
+                    &[
+                        &moon.id,
+                        &moon.planet_id,
+                        &moon.star_id,
+                        &moon.system_id,
+                        &moon.project_id,
+                        &moon.name,
+                        &moon.orbit_index,
+                        &moon.semi_major_axis_km,
+                        &moon.eccentricity,
+                        &moon.period_days,
+                        &moon.mass_earth,
+                        &moon.radius_km,
+                    ],
+                )
+                .await?;
+
+            ids.push(row.get(0));
+        }
+
+        transaction.commit().await?;
+        Ok(ids)
+    }
+
+    /// Get moons for a specific star system, innermost first around each planet
+    pub async fn get_moons_by_system(&self, system_id: Uuid) -> Result<Vec<Moon>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, planet_id, star_id, system_id, project_id, name, orbit_index,
+                        semi_major_axis_km, eccentricity, period_days, mass_earth, radius_km
+                 FROM stellar.moons
+                 WHERE system_id = $1
+                 ORDER BY planet_id, orbit_index",
+                &[&system_id],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_moon(&row)).collect())
+    }
+
+    /// Insert belts in batch
+    pub async fn insert_belts(&self, belts: &[Belt]) -> Result<Vec<Uuid>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        let mut ids = Vec::with_capacity(belts.len());
+
+        for belt in belts {
+            let row = transaction
+                .query_one(
+                    "INSERT INTO stellar.belts
+                     (id, star_id, system_id, project_id, name, belt_type,
+                      inner_radius_au, outer_radius_au, mass_earth)
+                     VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4, $5, $6, $7, $8, $9)
+                     RETURNING id",
+                    &[
+                        &belt.id,
+                        &belt.star_id,
+                        &belt.system_id,
+                        &belt.project_id,
+                        &belt.name,
+                        &belt.belt_type.to_string(),
+                        &belt.inner_radius_au,
+                        &belt.outer_radius_au,
+                        &belt.mass_earth,
+                    ],
+                )
+                .await?;
+
+            ids.push(row.get(0));
+        }
+
+        transaction.commit().await?;
+        Ok(ids)
+    }
+
+    /// Get belts for a specific star system, innermost first around each star
+    pub async fn get_belts_by_system(&self, system_id: Uuid) -> Result<Vec<Belt>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, star_id, system_id, project_id, name, belt_type,
+                        inner_radius_au, outer_radius_au, mass_earth
+                 FROM stellar.belts
+                 WHERE system_id = $1
+                 ORDER BY star_id, inner_radius_au",
+                &[&system_id],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_belt(&row)).collect())
+    }
+
+    /// Get belts for a project
+    pub async fn get_belts_by_project(&self, project_id: Uuid) -> Result<Vec<Belt>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, star_id, system_id, project_id, name, belt_type,
+                        inner_radius_au, outer_radius_au, mass_earth
+                 FROM stellar.belts
+                 WHERE project_id = $1",
+                &[&project_id],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_belt(&row)).collect())
+    }
+
+    // =========================================================================
+    // CONSTELLATION OPERATIONS
+    // =========================================================================
+
+    /// Replace the constellations of one observer system
+    pub async fn replace_constellations(&self, observer_system_id: Uuid, constellations: &[Constellation]) -> Result<()> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        transaction
+            .execute("DELETE FROM stellar.constellations WHERE observer_system_id = $1", &[&observer_system_id])
+            .await?;
+
+        for constellation in constellations {
+            // BIGINT is signed; store the seed's bit pattern
+            let seed = constellation.seed as i64;
+            let row = transaction
+                .query_one(
+                    "INSERT INTO stellar.constellations (id, project_id, observer_system_id, name, seed)
+                     VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4, $5)
+                     RETURNING id",
+                    &[
+                        &constellation.id,
+                        &constellation.project_id,
+                        &constellation.observer_system_id,
+                        &constellation.name,
+                        &seed,
+                    ],
+                )
+                .await?;
+            let constellation_id: Uuid = row.get(0);
+
+            for star in &constellation.stars {
+                transaction
+                    .execute(
+                        "INSERT INTO stellar.constellation_stars
+                         (constellation_id, star_id, bayer_index, designation, line_to_star_id)
+                         VALUES ($1, $2, $3, $4, $5)",
+                        &[&constellation_id, &star.star_id, &star.bayer_index, &star.designation, &star.line_to_star_id],
+                    )
+                    .await?;
+            }
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Get the constellations of one observer system with their stars,
+    /// brightest first
+    pub async fn get_constellations_by_observer(&self, observer_system_id: Uuid) -> Result<Vec<Constellation>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, project_id, observer_system_id, name, seed
+                 FROM stellar.constellations
+                 WHERE observer_system_id = $1
+                 ORDER BY name",
+                &[&observer_system_id],
+            )
+            .await?;
+        let mut constellations: Vec<Constellation> = rows
+            .into_iter()
+            .map(|row| Constellation {
+                id: Some(row.get(0)),
+                project_id: row.get(1),
+                observer_system_id: row.get(2),
+                name: row.get(3),
+                seed: row.get::<_, i64>(4) as u64,
+                stars: Vec::new(),
+            })
+            .collect();
+
+        let star_rows = client
+            .query(
+                "SELECT cs.constellation_id, cs.star_id, cs.bayer_index, cs.designation, cs.line_to_star_id
+                 FROM stellar.constellation_stars cs
+                 JOIN stellar.constellations c ON c.id = cs.constellation_id
+                 WHERE c.observer_system_id = $1
+                 ORDER BY cs.bayer_index",
+                &[&observer_system_id],
+            )
+            .await?;
+        for row in star_rows {
+            let constellation_id: Uuid = row.get(0);
+            if let Some(constellation) = constellations.iter_mut().find(|c| c.id == Some(constellation_id)) {
+                constellation.stars.push(ConstellationStar {
+                    star_id: row.get(1),
+                    bayer_index: row.get(2),
+                    designation: row.get(3),
+                    line_to_star_id: row.get(4),
+                });
+            }
+        }
+
+        Ok(constellations)
+    }
+
+    // =========================================================================
+    // LANE OPERATIONS
+    // =========================================================================
+
+    /// Insert lanes in batch
+    pub async fn insert_lanes(&self, lanes: &[Lane]) -> Result<()> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        Self::insert_lanes_in(&transaction, lanes).await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Replace the lane network of a project
+    pub async fn replace_lanes(&self, project_id: Uuid, lanes: &[Lane]) -> Result<()> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        transaction
+            .execute("DELETE FROM stellar.lanes WHERE project_id = $1", &[&project_id])
+            .await?;
+        Self::insert_lanes_in(&transaction, lanes).await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn insert_lanes_in(transaction: &Transaction<'_>, lanes: &[Lane]) -> Result<()> {
+        for lane in lanes {
+            transaction
+                .execute(
+                    "INSERT INTO stellar.lanes (id, project_id, system_a_id, system_b_id, length_ly, chokepoint)
+                     VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4, $5, $6)",
+                    &[&lane.id, &lane.project_id, &lane.system_a_id, &lane.system_b_id, &lane.length_ly, &lane.chokepoint],
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Get the lanes of a project
+    pub async fn get_lanes_by_project(&self, project_id: Uuid) -> Result<Vec<Lane>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, project_id, system_a_id, system_b_id, length_ly, chokepoint
+                 FROM stellar.lanes
+                 WHERE project_id = $1",
+                &[&project_id],
+            )
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Lane {
+                id: Some(row.get(0)),
+                project_id: row.get(1),
+                system_a_id: row.get(2),
+                system_b_id: row.get(3),
+                length_ly: row.get(4),
+                chokepoint: row.get(5),
+            })
+            .collect())
+    }
+
+    // =========================================================================
+    // FACTION OPERATIONS
+    // =========================================================================
+
+    /// Replace the factions of a project and the ownership of its systems
+    pub async fn replace_territories(&self, project_id: Uuid, factions: &[Faction], systems: &[StarSystem]) -> Result<()> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        transaction
+            .execute(
+                "UPDATE stellar.star_systems
+                 SET faction_id = NULL, contested_by_faction_id = NULL, border = FALSE
+                 WHERE project_id = $1",
+                &[&project_id],
+            )
+            .await?;
+        transaction
+            .execute("DELETE FROM stellar.factions WHERE project_id = $1", &[&project_id])
+            .await?;
+
+        for faction in factions {
+            transaction
+                .execute(
+                    "INSERT INTO stellar.factions (id, project_id, name, capital_system_id, strength)
+                     VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4, $5)",
+                    &[&faction.id, &faction.project_id, &faction.name, &faction.capital_system_id, &faction.strength],
+                )
+                .await?;
+        }
+
+        for system in systems.iter().filter(|s| s.faction_id.is_some()) {
+            transaction
+                .execute(
+                    "UPDATE stellar.star_systems
+                     SET faction_id = $2, contested_by_faction_id = $3, border = $4
+                     WHERE id = $1",
+                    &[&system.id, &system.faction_id, &system.contested_by, &system.border],
+                )
+                .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Get the factions of a project
+    pub async fn get_factions_by_project(&self, project_id: Uuid) -> Result<Vec<Faction>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, project_id, name, capital_system_id, strength
+                 FROM stellar.factions
+                 WHERE project_id = $1
+                 ORDER BY name",
+                &[&project_id],
+            )
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Faction {
+                id: Some(row.get(0)),
+                project_id: row.get(1),
+                name: row.get(2),
+                capital_system_id: row.get(3),
+                strength: row.get(4),
+            })
+            .collect())
+    }
+
+    // =========================================================================
+    // ECONOMY OPERATIONS
+    // =========================================================================
+
+    /// Save a project's economy after one or more ticks: its clock, every
+    /// market, the flows of the latest tick and the totals of each new tick.
+    /// A restart first drops the history of the previous run
+    pub async fn save_economy(
+        &self,
+        economy: &Economy,
+        markets: &[SystemMarket],
+        flows: &[TradeFlow],
+        ticks: &[EconomyTick],
+        restart: bool,
+    ) -> Result<()> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        transaction
+            .execute(
+                "INSERT INTO stellar.economies (project_id, tick, network, max_route_ly)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (project_id) DO UPDATE
+                 SET tick = $2, network = $3, max_route_ly = $4, updated_at = NOW()",
+                &[&economy.project_id, &economy.tick, &economy.network.to_string(), &economy.max_route_ly],
+            )
+            .await?;
+
+        if restart {
+            transaction
+                .execute("DELETE FROM stellar.economy_ticks WHERE project_id = $1", &[&economy.project_id])
+                .await?;
+        }
+
+        for market in markets {
+            transaction
+                .execute(
+                    "INSERT INTO stellar.system_markets
+                     (system_id, project_id, production, consumption, stock, imports, exports, throughput)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                     ON CONFLICT (system_id) DO UPDATE
+                     SET production = $3, consumption = $4, stock = $5, imports = $6, exports = $7, throughput = $8",
+                    &[
+                        &market.system_id,
+                        &market.project_id,
+                        &market.production.as_slice(),
+                        &market.consumption.as_slice(),
+                        &market.stock.as_slice(),
+                        &market.imports.as_slice(),
+                        &market.exports.as_slice(),
+                        &market.throughput,
+                    ],
+                )
+                .await?;
+        }
+
+        transaction
+            .execute("DELETE FROM stellar.trade_flows WHERE project_id = $1", &[&economy.project_id])
+            .await?;
+        for flow in flows {
+            transaction
+                .execute(
+                    "INSERT INTO stellar.trade_flows
+                     (id, project_id, tick, resource, from_system_id, to_system_id, volume)
+                     VALUES (COALESCE($1, gen_random_uuid()), $2, $3, $4, $5, $6, $7)",
+                    &[
+                        &flow.id,
+                        &flow.project_id,
+                        &flow.tick,
+                        &flow.resource.to_string(),
+                        &flow.from_system_id,
+                        &flow.to_system_id,
+                        &flow.volume,
+                    ],
+                )
+                .await?;
+        }
+
+        for tick in ticks {
+            transaction
+                .execute(
+                    "INSERT INTO stellar.economy_ticks (project_id, tick, produced, consumed, traded, shortage)
+                     VALUES ($1, $2, $3, $4, $5, $6)",
+                    &[&economy.project_id, &tick.tick, &tick.produced, &tick.consumed, &tick.traded, &tick.shortage],
+                )
+                .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Get the economy of a project, if one has been started
+    pub async fn get_economy(&self, project_id: Uuid) -> Result<Option<Economy>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                "SELECT project_id, tick, network, max_route_ly
+                 FROM stellar.economies
+                 WHERE project_id = $1",
+                &[&project_id],
+            )
+            .await?;
+
+        Ok(row.map(|row| {
+            let network: String = row.get(2);
+            Economy {
+                project_id: row.get(0),
+                tick: row.get(1),
+                network: TradeNetwork::from_name(&network).unwrap_or(TradeNetwork::StraightLine),
+                max_route_ly: row.get(3),
+            }
+        }))
+    }
+
+    /// Get the markets of a project's systems
+    pub async fn get_system_markets(&self, project_id: Uuid) -> Result<Vec<SystemMarket>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT system_id, project_id, production, consumption, stock, imports, exports, throughput
+                 FROM stellar.system_markets
+                 WHERE project_id = $1",
+                &[&project_id],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_system_market(&row)).collect())
+    }
+
+    /// Get the market of one system
+    pub async fn get_system_market(&self, system_id: Uuid) -> Result<Option<SystemMarket>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                "SELECT system_id, project_id, production, consumption, stock, imports, exports, throughput
+                 FROM stellar.system_markets
+                 WHERE system_id = $1",
+                &[&system_id],
+            )
+            .await?;
+
+        Ok(row.map(|r| self.row_to_system_market(&r)))
+    }
+
+    /// Get the trade flows of a project's latest tick, largest first
+    pub async fn get_trade_flows(&self, project_id: Uuid) -> Result<Vec<TradeFlow>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, project_id, tick, resource, from_system_id, to_system_id, volume
+                 FROM stellar.trade_flows
+                 WHERE project_id = $1
+                 ORDER BY volume DESC",
+                &[&project_id],
+            )
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let resource: String = row.get(3);
+                TradeFlow {
+                    id: Some(row.get(0)),
+                    project_id: row.get(1),
+                    tick: row.get(2),
+                    resource: Resource::from_name(&resource).unwrap_or(Resource::Goods),
+                    from_system_id: row.get(4),
+                    to_system_id: row.get(5),
+                    volume: row.get(6),
+                }
+            })
+            .collect())
+    }
+
+    /// Get the totals of every tick of a project's economy, oldest first
+    pub async fn get_economy_ticks(&self, project_id: Uuid) -> Result<Vec<EconomyTick>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT tick, produced, consumed, traded, shortage
+                 FROM stellar.economy_ticks
+                 WHERE project_id = $1
+                 ORDER BY tick",
+                &[&project_id],
+            )
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| EconomyTick {
+                tick: row.get(0),
+                produced: row.get(1),
+                consumed: row.get(2),
+                traded: row.get(3),
+                shortage: row.get(4),
+            })
+            .collect())
+    }
+
     // =========================================================================
     // HELPER METHODS
     // =========================================================================
@@ -260,21 +1206,34 @@ impl Repository {
             size_y_ly: row.get(8),
             size_z_ly: row.get(9),
             radius_ly: row.get(10),
+            arm_count: row.get(16),
+            pitch_angle_deg: row.get(17),
+            arm_spread_ly: row.get(18),
+            scale_length_ly: row.get(19),
+            scale_height_ly: row.get(20),
+            effective_radius_ly: row.get(21),
+            axis_ratio: row.get(22),
+            min_separation_ly: row.get(23),
+            imf: row.get(24),
+            star_formation: row.get(25),
+            burst_age_gyr: row.get(26),
+            burst_spread_gyr: row.get(27),
+            sfh_timescale_gyr: row.get(28),
             total_stars: row.get(11),
             solo_systems: row.get(12),
             binary_systems: row.get(13),
             trinary_systems: row.get(14),
+            higher_order_systems: row.get(29),
+            catalogue: row.get(30),
+            completeness_radius_ly: row.get(31),
+            seed: row.get::<_, Option<i64>>(15).map(|s| s as u64),
         }
     }
 
     fn row_to_star_system(&self, row: &Row) -> StarSystem {
         let system_type_str: String = row.get(3);
-        let system_type = match system_type_str.as_str() {
-            "solo" => crate::models::SystemType::Solo,
-            "binary" => crate::models::SystemType::Binary,
-            "trinary" => crate::models::SystemType::Trinary,
-            _ => crate::models::SystemType::Solo,
-        };
+        let system_type = crate::models::SystemType::from_name(&system_type_str)
+            .unwrap_or(crate::models::SystemType::Solo);
 
         StarSystem {
             id: Some(row.get(0)),
@@ -284,20 +1243,66 @@ impl Repository {
             x_ly: row.get(4),
             y_ly: row.get(5),
             z_ly: row.get(6),
+            zone_id: row.get(7),
+            faction_id: row.get(8),
+            contested_by: row.get(9),
+            border: row.get(10),
+        }
+    }
+
+    fn row_to_density_zone(&self, row: &Row) -> DensityZone {
+        let zone_type_str: String = row.get(3);
+        let axis_str: Option<String> = row.get(9);
+
+        DensityZone {
+            id: Some(row.get(0)),
+            project_id: row.get(1),
+            zone_index: row.get(2),
+            zone_type: ZoneType::from_name(&zone_type_str).unwrap_or(ZoneType::OpenCluster),
+            center_x_ly: row.get(4),
+            center_y_ly: row.get(5),
+            center_z_ly: row.get(6),
+            radius_ly: row.get(7),
+            star_share: row.get(8),
+            gradient_axis: axis_str.as_deref().and_then(Axis::from_name),
+            gradient_strength: row.get(10),
+        }
+    }
+
+    fn row_to_orbit(&self, row: &Row) -> Orbit {
+        Orbit {
+            id: Some(row.get(0)),
+            system_id: row.get(1),
+            project_id: row.get(2),
+            level: row.get(3),
+            primary_component: row.get(4),
+            secondary_component: row.get(5),
+            semi_major_axis_au: row.get(6),
+            eccentricity: row.get(7),
+            inclination_deg: row.get(8),
+            period_years: row.get(9),
+            mass_ratio: row.get(10),
         }
     }
 
     fn row_to_star(&self, row: &Row) -> Star {
         let spectral_str: String = row.get(4);
-        let spectral_class = match spectral_str.chars().next().unwrap_or('M') {
-            'O' => SpectralClass::O,
-            'B' => SpectralClass::B,
-            'A' => SpectralClass::A,
-            'F' => SpectralClass::F,
-            'G' => SpectralClass::G,
-            'K' => SpectralClass::K,
-            'M' => SpectralClass::M,
-            _ => SpectralClass::M,
+        let spectral_class = SpectralClass::from_code(spectral_str.trim()).unwrap_or(SpectralClass::M);
+        let luminosity_str: Option<String> = row.get(6);
+        let luminosity_class = luminosity_str.as_deref().and_then(LuminosityClass::from_code);
+        let stage_str: String = row.get(13);
+        let evolutionary_stage = EvolutionaryStage::from_name(&stage_str).unwrap_or(EvolutionaryStage::MainSequence);
+        let habitable_zone = match (row.get(14), row.get(15), row.get(16), row.get(17)) {
+            (Some(optimistic_inner_au), Some(conservative_inner_au), Some(conservative_outer_au), Some(optimistic_outer_au)) => {
+                Some(HabitableZone { optimistic_inner_au, conservative_inner_au, conservative_outer_au, optimistic_outer_au })
+            },
+            _ => None,
+        };
+        let photometry = match (row.get(18), row.get(19), row.get(20), row.get(21)) {
+            (Some(absolute_magnitude_v), Some(bolometric_correction), Some(color_index_bv), Some(color_hex)) => {
+                Some(Photometry { absolute_magnitude_v, bolometric_correction, color_index_bv, color_hex })
+            },
+            _ => None,
         };
 
         Star {
@@ -305,13 +1310,107 @@ impl Repository {
             system_id: row.get(1),
             project_id: row.get(2),
             name: row.get(3),
+            component: row.get(22),
             spectral_class,
             spectral_subclass: row.get(5),
-            luminosity_class: row.get(6),
+            luminosity_class,
             temperature_k: row.get(7),
             mass_solar: row.get(8),
             radius_solar: row.get(9),
             luminosity_solar: row.get(10),
+            age_gyr: row.get(11),
+            metallicity_feh: row.get(12),
+            evolutionary_stage,
+            habitable_zone,
+            photometry,
+        }
+    }
+
+    fn row_to_planet(&self, row: &Row) -> Planet {
+        let type_str: String = row.get(6);
+        let atmosphere_str: String = row.get(20);
+        let climate_str: String = row.get(25);
+
+        Planet {
+            id: Some(row.get(0)),
+            star_id: row.get(1),
+            system_id: row.get(2),
+            project_id: row.get(3),
+            name: row.get(4),
+            orbit_index: row.get(5),
+            barycentre_orbit_id: row.get(16),
+            planet_type: PlanetType::from_name(&type_str).unwrap_or(PlanetType::Rocky),
+            semi_major_axis_au: row.get(7),
+            eccentricity: row.get(8),
+            period_years: row.get(9),
+            mass_earth: row.get(10),
+            radius_earth: row.get(11),
+            ring: match (row.get(14), row.get(15)) {
+                (Some(inner_radius_km), Some(outer_radius_km)) => Some(Ring { inner_radius_km, outer_radius_km }),
+                _ => None,
+            },
+            profile: WorldProfile {
+                albedo: row.get(17),
+                equilibrium_temperature_k: row.get(18),
+                surface_temperature_k: row.get(19),
+                atmosphere: Atmosphere::from_name(&atmosphere_str).unwrap_or_default(),
+                surface_pressure_bar: row.get(21),
+                water_fraction: row.get(22),
+                tidally_locked: row.get(23),
+                day_length_hours: row.get(24),
+                climate: Climate::from_name(&climate_str).unwrap_or_default(),
+            },
+            insolation_earth: row.get(12),
+            habitability: row.get(13),
+        }
+    }
+
+    fn row_to_moon(&self, row: &Row) -> Moon {
+        Moon {
+            id: Some(row.get(0)),
+            planet_id: row.get(1),
+            star_id: row.get(2),
+            system_id: row.get(3),
+            project_id: row.get(4),
+            name: row.get(5),
+            orbit_index: row.get(6),
+            semi_major_axis_km: row.get(7),
+            eccentricity: row.get(8),
+            period_days: row.get(9),
+            mass_earth: row.get(10),
+            radius_km: row.get(11),
+        }
+    }
+
+    fn row_to_system_market(&self, row: &Row) -> SystemMarket {
+        // Arrays hold one value per resource
+        let amounts = |values: Vec<f64>| -> Amounts { values.try_into().unwrap_or_default() };
+
+        SystemMarket {
+            system_id: row.get(0),
+            project_id: row.get(1),
+            production: amounts(row.get(2)),
+            consumption: amounts(row.get(3)),
+            stock: amounts(row.get(4)),
+            imports: amounts(row.get(5)),
+            exports: amounts(row.get(6)),
+            throughput: row.get(7),
+        }
+    }
+
+    fn row_to_belt(&self, row: &Row) -> Belt {
+        let type_str: String = row.get(5);
+
+        Belt {
+            id: Some(row.get(0)),
+            star_id: row.get(1),
+            system_id: row.get(2),
+            project_id: row.get(3),
+            name: row.get(4),
+            belt_type: BeltType::from_name(&type_str).unwrap_or(BeltType::AsteroidBelt),
+            inner_radius_au: row.get(6),
+            outer_radius_au: row.get(7),
+            mass_earth: row.get(8),
         }
     }
 }
@@ -0,0 +1,118 @@
+use rand::Rng;
+use uuid::Uuid;
+use crate::models::{Belt, BeltType, EvolutionaryStage, Planet, PlanetType};
+use super::{log_uniform, snow_line_au, PlanetHost};
+
+/// Main-belt edges as fractions of the innermost giant's orbit (the Sun's
+/// belt spans 2.1-3.3 AU inside Jupiter at 5.2 AU)
+const ASTEROID_BELT_INNER: f64 = 0.4;
+const ASTEROID_BELT_OUTER: f64 = 0.63;
+
+/// Kuiper-belt edges as multiples of the outermost planet's orbit (39-55 AU
+/// beyond Neptune at 30 AU)
+const KUIPER_BELT_INNER: f64 = 1.3;
+const KUIPER_BELT_OUTER: f64 = 1.8;
+
+/// Kuiper-belt edges in snow-line radii for stars without planets to
+/// sculpt them
+const BARE_KUIPER_INNER: f64 = 11.0;
+const BARE_KUIPER_OUTER: f64 = 20.0;
+
+/// Generate the belts of one star from its planets
+/// A giant planet stirs the region inside its orbit into an asteroid belt;
+/// icy debris collects beyond the outermost planet. Belts are dropped
+/// where a planet already orbits or a companion would strip them.
+/// Neutron stars and black holes get none
+pub fn generate_belts<R: Rng + ?Sized>(rng: &mut R, host: &PlanetHost, planets: &[Planet]) -> Vec<Belt> {
+    let star = host.star;
+    if matches!(star.evolutionary_stage, EvolutionaryStage::NeutronStar | EvolutionaryStage::BlackHole) {
+        return Vec::new();
+    }
+
+    let snow_line = snow_line_au(star.mass_solar);
+    let mut belts = Vec::new();
+
+    let innermost_giant = planets
+        .iter()
+        .filter(|p| p.planet_type == PlanetType::GasGiant && p.semi_major_axis_au > 0.5 * snow_line)
+        .map(|p| p.semi_major_axis_au)
+        .reduce(f64::min);
+    let asteroid_belt = match innermost_giant {
+        Some(a) if rng.gen::<f64>() < 0.8 => Some((ASTEROID_BELT_INNER * a, ASTEROID_BELT_OUTER * a)),
+        None if rng.gen::<f64>() < 0.2 => Some((0.7 * snow_line, snow_line)),
+        _ => None,
+    };
+    if let Some((inner, outer)) = asteroid_belt {
+        belts.push((BeltType::AsteroidBelt, inner, outer, log_uniform(rng, 1e-4, 1e-3)));
+    }
+
+    let outermost = planets.iter().map(|p| p.semi_major_axis_au).reduce(f64::max);
+    let has_giants = planets.iter().any(|p| matches!(p.planet_type, PlanetType::GasGiant | PlanetType::IceGiant));
+    let kuiper_belt = match outermost {
+        Some(a) if rng.gen::<f64>() < if has_giants { 0.7 } else { 0.3 } => {
+            Some((KUIPER_BELT_INNER * a, KUIPER_BELT_OUTER * a))
+        },
+        None if rng.gen::<f64>() < 0.3 => Some((BARE_KUIPER_INNER * snow_line, BARE_KUIPER_OUTER * snow_line)),
+        _ => None,
+    };
+    if let Some((inner, outer)) = kuiper_belt {
+        belts.push((BeltType::KuiperBelt, inner, outer, log_uniform(rng, 0.01, 0.1)));
+    }
+
+    belts
+        .into_iter()
+        .filter(|&(_, inner, outer, _)| {
+            inner >= host.inner_limit_au
+                && outer <= host.outer_limit_au
+                && !planets.iter().any(|p| (inner..=outer).contains(&p.semi_major_axis_au))
+        })
+        .map(|(belt_type, inner_radius_au, outer_radius_au, mass_earth)| Belt {
+            id: Some(Uuid::new_v4()),
+            star_id: star.id.unwrap_or_default(),
+            system_id: star.system_id,
+            project_id: star.project_id,
+            name: format!("{} {}", star.name, belt_type.title()),
+            belt_type,
+            inner_radius_au,
+            outer_radius_au,
+            mass_earth,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::sun_like_star;
+    use crate::generator::{generate_planets, seeded_rng};
+    use crate::models::Star;
+
+    #[test]
+    fn test_belts_avoid_planets() {
+        let star = Star { metallicity_feh: 0.3, ..sun_like_star(1.0) };
+
+        let mut rng = seeded_rng(22);
+        let (mut asteroid_belts, mut kuiper_belts) = (0, 0);
+        for _ in 0..500 {
+            let host = PlanetHost::single(&star);
+            let planets = generate_planets(&mut rng, &host);
+            let belts = generate_belts(&mut rng, &host, &planets);
+
+            for belt in &belts {
+                assert!(belt.inner_radius_au < belt.outer_radius_au);
+                assert!(belt.mass_earth > 0.0);
+                assert!(!planets.iter().any(|p| (belt.inner_radius_au..=belt.outer_radius_au).contains(&p.semi_major_axis_au)));
+                match belt.belt_type {
+                    BeltType::AsteroidBelt => asteroid_belts += 1,
+                    BeltType::KuiperBelt => {
+                        kuiper_belts += 1;
+                        assert!(planets.iter().all(|p| p.semi_major_axis_au < belt.inner_radius_au));
+                    },
+                }
+            }
+        }
+
+        assert!(asteroid_belts > 20);
+        assert!(kuiper_belts > 100);
+    }
+}
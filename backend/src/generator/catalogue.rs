@@ -0,0 +1,824 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use uuid::Uuid;
+use crate::models::{
+    CatalogueFormat, DensityZone, EvolutionaryStage, LuminosityClass, Position3D, SpectralClass, Star,
+    StarProperties, StarSystem, SystemType, ZoneType,
+};
+use super::{
+    bolometric_correction, bv_temperature, generate_star_systems, main_sequence_lifetime, main_sequence_luminosity,
+    main_sequence_mass, main_sequence_properties, populate_system, component_letters, sample_metallicity, sample_system_age, seeded_rng,
+    star_photometry, stefan_boltzmann_luminosity, stefan_boltzmann_radius, white_dwarf_radius, GeneratedGalaxy,
+    GenerationParams, BROWN_DWARF_RADIUS_SOLAR, LY_PER_PARSEC, SUN_BOLOMETRIC_MAGNITUDE,
+};
+
+/// Most star systems one project may hold
+pub const MAX_IMPORT_SYSTEMS: usize = 10_000;
+
+/// Catalogue stars closer together than this are taken as one system
+/// (wide binaries reach about 0.2 ly; Proxima is 0.21 ly from Alpha Centauri AB)
+const COMPANION_SEPARATION_LY: f64 = 0.25;
+
+/// HYG gives stars without a usable parallax this distance in parsecs
+const HYG_UNKNOWN_DISTANCE_PC: f64 = 100_000.0;
+
+/// Below this fraction of the main-sequence luminosity at its temperature
+/// an untyped star is taken for a white dwarf
+const WHITE_DWARF_LUMINOSITY_RATIO: f64 = 0.01;
+
+/// Luminosity over the main-sequence value at the same temperature from
+/// which an untyped star counts as a subgiant, and as a giant
+const SUBGIANT_LUMINOSITY_RATIO: f64 = 2.5;
+const GIANT_LUMINOSITY_RATIO: f64 = 10.0;
+
+/// Masses for stars whose luminosity does not pin the mass down
+const WHITE_DWARF_MASS: f64 = 0.6;
+const BROWN_DWARF_MASS: f64 = 0.05;
+const GIANT_MASS: f64 = 1.5;
+const BRIGHT_GIANT_MASS: f64 = 5.0;
+const MIN_SUPERGIANT_MASS: f64 = 8.0;
+
+/// One usable catalogue row
+#[derive(Debug, Clone)]
+pub struct CatalogueEntry {
+    /// Proper name or catalogue designation, e.g. "Sirius", "Gl 244B", "HIP 8102"
+    pub name: String,
+    /// Traditional name, when the catalogue gives one
+    pub proper_name: Option<String>,
+    /// Catalogue name of the multiple system the star belongs to (HYG `base`)
+    pub system_name: Option<String>,
+    /// Rows sharing this key belong to one system (HYG `comp_primary`)
+    group: Option<String>,
+    pub position: Position3D,
+    pub properties: StarProperties,
+}
+
+/// Stars read from a catalogue file, grouped into systems
+#[derive(Debug, Clone)]
+pub struct Catalogue {
+    pub format: CatalogueFormat,
+    /// Members heaviest first; systems nearest the origin first
+    pub systems: Vec<Vec<CatalogueEntry>>,
+    /// Rows without a usable distance or spectral information, and
+    /// companions beyond the sixth in one system
+    pub skipped_stars: usize,
+}
+
+impl Catalogue {
+    /// Procedural systems needed to continue the catalogue's density from
+    /// `completeness_radius_ly` out to `radius_ly`
+    pub fn fill_count(&self, radius_ly: f64, completeness_radius_ly: f64) -> usize {
+        let shell = (radius_ly / completeness_radius_ly).powi(3) - 1.0;
+        (self.systems.len() as f64 * shell.max(0.0)).round() as usize
+    }
+}
+
+/// MK spectral type as written in catalogues, e.g. "K0III", "M4.5Ve", "DA2"
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SpectralType {
+    class: SpectralClass,
+    subclass: Option<f64>,
+    luminosity_class: Option<LuminosityClass>,
+}
+
+impl SpectralType {
+    /// Temperature at the middle of the subclass (subclass 5 if absent);
+    /// white dwarfs use the temperature index 50400 / T
+    fn temperature_k(&self) -> Option<f64> {
+        match self.class.temperature_range() {
+            Some((min_t, max_t)) => {
+                let fraction = ((self.subclass.unwrap_or(5.0) + 0.5) / 10.0).min(1.0);
+                Some(max_t * (min_t / max_t).powf(fraction))
+            },
+            None => self.subclass.filter(|s| *s > 0.0).map(|s| 50400.0 / s),
+        }
+    }
+}
+
+/// Parse the spectral type of a catalogue row
+/// Mount Wilson prefixes ("sd", "d", "g") set the luminosity class;
+/// peculiarity suffixes are ignored, and of a range ("K0III-IV", "G8/K0")
+/// the first type counts. Carbon and S stars return None
+fn parse_spectral_type(text: &str) -> Option<SpectralType> {
+    let text = text.trim();
+    let (text, prefix_class) = if let Some(rest) = text.strip_prefix("sd") {
+        (rest, Some(LuminosityClass::V))
+    } else if let Some(rest) = text.strip_prefix('d') {
+        (rest, Some(LuminosityClass::V))
+    } else if let Some(rest) = text.strip_prefix('g') {
+        (rest, Some(LuminosityClass::III))
+    } else {
+        (text, None)
+    };
+
+    let (class, rest) = if let Some(rest) = text.strip_prefix('D') {
+        let class = rest.get(..1).and_then(|c| SpectralClass::from_code(&format!("D{}", c)));
+        (class.unwrap_or(SpectralClass::DA), rest.trim_start_matches(|c: char| c.is_ascii_alphabetic()))
+    } else {
+        let class = match text.chars().next()? {
+            // Wolf-Rayet stars are evolved O stars
+            'W' => SpectralClass::O,
+            c => SpectralClass::from_code(&c.to_string()).filter(|c| c.temperature_range().is_some())?,
+        };
+        (class, &text[1..])
+    };
+
+    let digits = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+    let subclass = rest[..digits].parse::<f64>().ok().filter(|s| *s < 10.0 || class.temperature_range().is_none());
+
+    let luminosity_class = if class.temperature_range().is_none() {
+        None
+    } else {
+        let tail = rest[digits..].trim_start();
+        const NUMERALS: [(&str, LuminosityClass); 9] = [
+            ("Iab", LuminosityClass::Ia),
+            ("Ia", LuminosityClass::Ia),
+            ("Ib", LuminosityClass::Ib),
+            ("III", LuminosityClass::III),
+            ("II", LuminosityClass::II),
+            ("IV", LuminosityClass::IV),
+            ("I", LuminosityClass::Ib),
+            ("VI", LuminosityClass::V),
+            ("V", LuminosityClass::V),
+        ];
+        NUMERALS.iter().find(|(numeral, _)| tail.starts_with(numeral)).map(|&(_, lc)| lc).or(prefix_class)
+    };
+
+    Some(SpectralType { class, subclass, luminosity_class })
+}
+
+/// Main-sequence mass whose temperature is `temperature_k` (bisection)
+fn main_sequence_mass_for_temperature(temperature_k: f64) -> f64 {
+    let (mut low, mut high) = (0.08f64, 150.0f64);
+    for _ in 0..60 {
+        let mid = (low * high).sqrt();
+        if main_sequence_properties(mid).temperature_k < temperature_k {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low * high).sqrt()
+}
+
+/// Luminosity class from a star's place on the HR diagram
+fn hr_luminosity_class(temperature_k: f64, luminosity_solar: f64) -> LuminosityClass {
+    let ratio = luminosity_solar / main_sequence_luminosity(main_sequence_mass_for_temperature(temperature_k));
+    if ratio < SUBGIANT_LUMINOSITY_RATIO {
+        LuminosityClass::V
+    } else if ratio < GIANT_LUMINOSITY_RATIO {
+        LuminosityClass::IV
+    } else if luminosity_solar >= 1.0e5 {
+        LuminosityClass::Ia
+    } else if luminosity_solar >= 2.0e4 {
+        LuminosityClass::Ib
+    } else if luminosity_solar >= 1.0e3 {
+        LuminosityClass::II
+    } else {
+        LuminosityClass::III
+    }
+}
+
+/// Typical luminosity of an evolved star of known class but unknown magnitude
+fn typical_luminosity(class: LuminosityClass) -> f64 {
+    match class {
+        LuminosityClass::Ia => 2.0e5,
+        LuminosityClass::Ib => 3.0e4,
+        LuminosityClass::II => 2.0e3,
+        LuminosityClass::III => 100.0,
+        LuminosityClass::IV => 4.0,
+        LuminosityClass::V => 1.0,
+    }
+}
+
+/// Physical properties of a catalogue star
+/// Temperature comes from the spectral type, or failing that from the
+/// colour; luminosity from the absolute magnitude and bolometric
+/// correction. Untyped stars are placed by their position on the HR
+/// diagram. Masses follow the generator's relations (main-sequence
+/// mass-luminosity, supergiants 2-5 times their main-sequence luminosity)
+/// or typical values where luminosity does not fix them. None if the row
+/// gives neither a spectral type nor a colour
+fn catalogue_properties(
+    spectral_type: Option<&str>,
+    absolute_magnitude_v: Option<f64>,
+    color_temperature_k: Option<f64>,
+) -> Option<StarProperties> {
+    let parsed = spectral_type.and_then(parse_spectral_type);
+    let temperature_k = parsed.and_then(|t| t.temperature_k()).or(color_temperature_k)?;
+    let measured_luminosity = absolute_magnitude_v
+        .map(|m| 10f64.powf(-0.4 * (m + bolometric_correction(temperature_k) - SUN_BOLOMETRIC_MAGNITUDE)));
+
+    let (spectral_class, spectral_subclass) = match parsed {
+        Some(t) => (t.class, t.subclass.map(|s| (s.floor() as i32).clamp(0, 9))),
+        None => {
+            let dim = measured_luminosity.is_some_and(|l| {
+                l < WHITE_DWARF_LUMINOSITY_RATIO * main_sequence_luminosity(main_sequence_mass_for_temperature(temperature_k))
+            });
+            if dim {
+                (SpectralClass::DA, None)
+            } else {
+                let (class, subclass) = SpectralClass::from_temperature(temperature_k);
+                (class, Some(subclass))
+            }
+        },
+    };
+    let luminosity_class = parsed
+        .and_then(|t| t.luminosity_class)
+        .or_else(|| measured_luminosity.map(|l| hr_luminosity_class(temperature_k, l)));
+
+    let (stage, luminosity_class, mass_solar, luminosity_solar) = match spectral_class {
+        SpectralClass::DA | SpectralClass::DB | SpectralClass::DC | SpectralClass::DO | SpectralClass::DQ | SpectralClass::DZ => {
+            let luminosity = measured_luminosity
+                .unwrap_or_else(|| stefan_boltzmann_luminosity(white_dwarf_radius(WHITE_DWARF_MASS), temperature_k));
+            (EvolutionaryStage::WhiteDwarf, None, WHITE_DWARF_MASS, luminosity)
+        },
+        SpectralClass::L | SpectralClass::T | SpectralClass::Y => {
+            let luminosity = measured_luminosity
+                .unwrap_or_else(|| stefan_boltzmann_luminosity(BROWN_DWARF_RADIUS_SOLAR, temperature_k));
+            (EvolutionaryStage::BrownDwarf, Some(LuminosityClass::V), BROWN_DWARF_MASS, luminosity)
+        },
+        _ => match luminosity_class.unwrap_or(LuminosityClass::V) {
+            LuminosityClass::V => {
+                let mass = measured_luminosity
+                    .map(main_sequence_mass)
+                    .unwrap_or_else(|| main_sequence_mass_for_temperature(temperature_k));
+                let luminosity = measured_luminosity.unwrap_or_else(|| main_sequence_luminosity(mass));
+                (EvolutionaryStage::MainSequence, Some(LuminosityClass::V), mass, luminosity)
+            },
+            class => {
+                let luminosity = measured_luminosity.unwrap_or_else(|| typical_luminosity(class));
+                let (stage, mass) = match class {
+                    LuminosityClass::IV => (EvolutionaryStage::Subgiant, main_sequence_mass(luminosity / 1.5)),
+                    LuminosityClass::III => (EvolutionaryStage::Giant, GIANT_MASS),
+                    LuminosityClass::II => (EvolutionaryStage::BrightGiant, BRIGHT_GIANT_MASS),
+                    _ => (
+                        EvolutionaryStage::Supergiant,
+                        main_sequence_mass(luminosity / 3.5).max(MIN_SUPERGIANT_MASS),
+                    ),
+                };
+                (stage, Some(class), mass, luminosity)
+            },
+        },
+    };
+
+    // White dwarf subclasses are the temperature index 50400 / T
+    let spectral_subclass = spectral_subclass.or_else(|| match stage {
+        EvolutionaryStage::WhiteDwarf => Some(((50400.0 / temperature_k).round() as i32).clamp(0, 9)),
+        _ => Some(SpectralClass::from_temperature(temperature_k).1),
+    });
+
+    Some(StarProperties {
+        spectral_class,
+        spectral_subclass,
+        luminosity_class,
+        evolutionary_stage: stage,
+        temperature_k,
+        mass_solar,
+        radius_solar: stefan_boltzmann_radius(luminosity_solar, temperature_k),
+        luminosity_solar,
+    })
+}
+
+/// Equatorial cartesian position in light years
+fn equatorial_position(ra_deg: f64, dec_deg: f64, distance_pc: f64) -> Position3D {
+    let distance_ly = distance_pc * LY_PER_PARSEC;
+    let (sin_dec, cos_dec) = dec_deg.to_radians().sin_cos();
+    let (sin_ra, cos_ra) = ra_deg.to_radians().sin_cos();
+    Position3D { x: distance_ly * cos_dec * cos_ra, y: distance_ly * cos_dec * sin_ra, z: distance_ly * sin_dec }
+}
+
+/// Effective temperature from Gaia BP-RP colour (Mucciarelli & Bellazzini
+/// 2020, dwarfs at solar metallicity)
+fn bp_rp_temperature(bp_rp: f64) -> f64 {
+    let theta = 0.4929 + 0.5092 * bp_rp - 0.0353 * bp_rp * bp_rp;
+    (5040.0 / theta.max(0.1)).clamp(2400.0, 50000.0)
+}
+
+/// Johnson V from Gaia G and BP-RP (Evans et al. 2018)
+fn gaia_v_magnitude(g: f64, bp_rp: Option<f64>) -> f64 {
+    let c = bp_rp.unwrap_or(0.0).clamp(-0.5, 2.75);
+    g + 0.01760 + 0.006860 * c + 0.1732 * c * c
+}
+
+/// Header positions of a catalogue file, looked up case-insensitively
+struct Columns(HashMap<String, usize>);
+
+impl Columns {
+    fn new(headers: &csv::StringRecord) -> Self {
+        Columns(headers.iter().enumerate().map(|(i, h)| (h.trim().to_ascii_lowercase(), i)).collect())
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    /// Non-empty field of a row
+    fn text<'r>(&self, record: &'r csv::StringRecord, name: &str) -> Option<&'r str> {
+        self.0.get(name).and_then(|&i| record.get(i)).map(str::trim).filter(|s| !s.is_empty())
+    }
+
+    fn number(&self, record: &csv::StringRecord, name: &str) -> Option<f64> {
+        self.text(record, name).and_then(|s| s.parse::<f64>().ok()).filter(|v| v.is_finite())
+    }
+}
+
+/// Catalogue layout from the header row
+fn detect_format(columns: &Columns) -> Option<CatalogueFormat> {
+    if columns.has("parallax") {
+        Some(CatalogueFormat::Gaia)
+    } else if columns.has("dist") && columns.has("spect") {
+        Some(CatalogueFormat::Hyg)
+    } else {
+        None
+    }
+}
+
+/// HYG row: RA in hours, Dec in degrees, distance in parsecs
+fn hyg_entry(columns: &Columns, record: &csv::StringRecord) -> Option<CatalogueEntry> {
+    let ra_deg = columns.number(record, "ra")? * 15.0;
+    let dec_deg = columns.number(record, "dec")?;
+    let distance_pc = columns.number(record, "dist").filter(|d| (0.0..HYG_UNKNOWN_DISTANCE_PC).contains(d))?;
+
+    let absolute_magnitude = columns.number(record, "absmag").or_else(|| {
+        let apparent = columns.number(record, "mag")?;
+        (distance_pc > 0.0).then(|| apparent - 5.0 * distance_pc.log10() + 5.0)
+    });
+    let color_temperature = columns.number(record, "ci").map(bv_temperature);
+    let properties = catalogue_properties(columns.text(record, "spect"), absolute_magnitude, color_temperature)?;
+
+    let proper_name = columns.text(record, "proper").map(String::from);
+    let name = proper_name
+        .clone()
+        .or_else(|| columns.text(record, "gl").map(String::from))
+        .or_else(|| columns.text(record, "hip").map(|hip| format!("HIP {}", hip)))
+        .or_else(|| columns.text(record, "hd").map(|hd| format!("HD {}", hd)))
+        .or_else(|| columns.text(record, "id").map(|id| format!("HYG {}", id)))?;
+
+    Some(CatalogueEntry {
+        name,
+        proper_name,
+        system_name: columns.text(record, "base").map(String::from),
+        group: columns.text(record, "comp_primary").map(String::from),
+        position: equatorial_position(ra_deg, dec_deg, distance_pc),
+        properties,
+    })
+}
+
+/// Gaia row: RA and Dec in degrees, parallax in milliarcseconds
+fn gaia_entry(columns: &Columns, record: &csv::StringRecord) -> Option<CatalogueEntry> {
+    let ra_deg = columns.number(record, "ra")?;
+    let dec_deg = columns.number(record, "dec")?;
+    let parallax_mas = columns.number(record, "parallax").filter(|p| *p > 0.0)?;
+
+    let bp_rp = columns.number(record, "bp_rp");
+    let absolute_magnitude = columns
+        .number(record, "phot_g_mean_mag")
+        .map(|g| gaia_v_magnitude(g, bp_rp) + 5.0 * parallax_mas.log10() - 10.0);
+    let temperature = columns
+        .number(record, "teff_gspphot")
+        .filter(|t| *t > 0.0)
+        .or_else(|| bp_rp.map(bp_rp_temperature));
+    let properties = catalogue_properties(None, absolute_magnitude, temperature)?;
+
+    let name = columns
+        .text(record, "designation")
+        .map(String::from)
+        .or_else(|| columns.text(record, "source_id").map(|id| format!("Gaia DR3 {}", id)))?;
+
+    Some(CatalogueEntry {
+        name,
+        proper_name: None,
+        system_name: None,
+        group: None,
+        position: equatorial_position(ra_deg, dec_deg, 1000.0 / parallax_mas),
+        properties,
+    })
+}
+
+/// Read the stars within `radius_ly` of the origin from a catalogue file
+/// The format is detected from the header when not given. Stars sharing a
+/// HYG `comp_primary`, or closer than 0.25 ly to each other, become one
+/// system of at most six stars
+pub fn read_catalogue<Rd: Read>(reader: Rd, format: Option<CatalogueFormat>, radius_ly: f64) -> Result<Catalogue, String> {
+    let mut csv = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let headers = csv.headers().map_err(|e| format!("Could not read the catalogue header: {}", e))?;
+    let columns = Columns::new(headers);
+
+    let format = match format.or_else(|| detect_format(&columns)) {
+        Some(format) => format,
+        None => return Err("Unrecognised catalogue layout: expected HYG (ra, dec, dist, spect) or Gaia (ra, dec, parallax) columns".to_string()),
+    };
+    let required: &[&str] = match format {
+        CatalogueFormat::Hyg => &["ra", "dec", "dist"],
+        CatalogueFormat::Gaia => &["ra", "dec", "parallax"],
+    };
+    if let Some(missing) = required.iter().find(|c| !columns.has(c)) {
+        return Err(format!("The {} catalogue has no '{}' column", format, missing));
+    }
+
+    let mut entries = Vec::new();
+    let mut skipped_stars = 0;
+    for (row, record) in csv.records().enumerate() {
+        let record = record.map_err(|e| format!("Catalogue row {}: {}", row + 2, e))?;
+        let entry = match format {
+            CatalogueFormat::Hyg => hyg_entry(&columns, &record),
+            CatalogueFormat::Gaia => gaia_entry(&columns, &record),
+        };
+        match entry {
+            Some(entry) if distance(&entry.position) <= radius_ly => entries.push(entry),
+            Some(_) => {},
+            None => skipped_stars += 1,
+        }
+    }
+
+    let mut systems = group_systems(entries);
+    for members in &mut systems {
+        members.sort_by(|a, b| b.properties.mass_solar.total_cmp(&a.properties.mass_solar));
+        if members.len() > SystemType::MAX_STARS {
+            skipped_stars += members.len() - SystemType::MAX_STARS;
+            members.truncate(SystemType::MAX_STARS);
+        }
+    }
+    systems.sort_by(|a, b| distance(&a[0].position).total_cmp(&distance(&b[0].position)));
+
+    Ok(Catalogue { format, systems, skipped_stars })
+}
+
+fn distance(position: &Position3D) -> f64 {
+    (position.x.powi(2) + position.y.powi(2) + position.z.powi(2)).sqrt()
+}
+
+/// Group entries that share a catalogue group key or lie within the
+/// companion separation of each other (union-find over an x-sorted sweep)
+fn group_systems(entries: Vec<CatalogueEntry>) -> Vec<Vec<CatalogueEntry>> {
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    fn join(parent: &mut [usize], a: usize, b: usize) {
+        let (a, b) = (root(parent, a), root(parent, b));
+        parent[a.max(b)] = a.min(b);
+    }
+
+    let mut parent: Vec<usize> = (0..entries.len()).collect();
+
+    let mut keyed: HashMap<&str, usize> = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if let Some(key) = entry.group.as_deref() {
+            let first = *keyed.entry(key).or_insert(i);
+            join(&mut parent, first, i);
+        }
+    }
+
+    let mut by_x: Vec<usize> = (0..entries.len()).collect();
+    by_x.sort_by(|&a, &b| entries[a].position.x.total_cmp(&entries[b].position.x));
+    for (k, &i) in by_x.iter().enumerate() {
+        for &j in &by_x[k + 1..] {
+            let (a, b) = (&entries[i].position, &entries[j].position);
+            if b.x - a.x > COMPANION_SEPARATION_LY {
+                break;
+            }
+            let separation = ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt();
+            if separation <= COMPANION_SEPARATION_LY {
+                join(&mut parent, i, j);
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<CatalogueEntry>> = HashMap::new();
+    for (i, entry) in entries.into_iter().enumerate() {
+        let r = root(&mut parent, i);
+        groups.entry(r).or_default().push(entry);
+    }
+    groups.into_values().collect()
+}
+
+/// `name`, or `name (2)`, `name (3)`, ... if it is already taken
+fn unique_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while !taken.insert(candidate.clone()) {
+        candidate = format!("{} ({})", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+/// Turn a catalogue into a project's systems
+/// Catalogue stars keep their names, positions and observed properties and
+/// take component letters heaviest first;
+/// ages, metallicities, companion orbits, planets, moons and belts are
+/// generated as for any other system. With a completeness radius the
+/// remaining `params.num_systems` are procedural systems placed uniformly
+/// between it and the project radius
+pub fn import_catalogue(
+    project_id: Uuid,
+    catalogue: &Catalogue,
+    params: &GenerationParams,
+    completeness_radius_ly: Option<f64>,
+) -> Result<GeneratedGalaxy, String> {
+    let mut rng = seeded_rng(params.seed);
+    let mut galaxy = GeneratedGalaxy::default();
+    let mut system_names = HashSet::new();
+    let mut star_names = HashSet::new();
+
+    for members in &catalogue.systems {
+        let primary = &members[0];
+        let system_type = SystemType::from_num_stars(members.len());
+        let catalogue_name = match (&primary.proper_name, &primary.system_name) {
+            (None, Some(base)) if members.len() > 1 => base,
+            _ => &primary.name,
+        };
+
+        let system = StarSystem {
+            id: Some(Uuid::new_v4()),
+            project_id,
+            name: unique_name(catalogue_name, &mut system_names),
+            system_type,
+            x_ly: primary.position.x,
+            y_ly: primary.position.y,
+            z_ly: primary.position.z,
+            zone_id: None,
+            faction_id: None,
+            contested_by: None,
+            border: false,
+        };
+        let system_id = system.id.unwrap();
+
+        // Every member still shining must be younger than its main-sequence lifetime
+        let lifetime = members
+            .iter()
+            .filter(|m| m.properties.evolutionary_stage == EvolutionaryStage::MainSequence)
+            .map(|m| main_sequence_lifetime(m.properties.mass_solar))
+            .fold(f64::INFINITY, f64::min);
+        let age_gyr = sample_system_age(&mut rng, &params.star_formation).min(lifetime);
+        let metallicity_feh = sample_metallicity(&mut rng, age_gyr);
+
+        // Members are heaviest first, so they take the letters in order
+        let first_star = galaxy.stars.len();
+        for (member, component) in members.iter().zip(component_letters(members.len())) {
+            let star_name = if members.len() == 1 { &system.name } else { &member.name };
+            let properties = member.properties.clone();
+            galaxy.stars.push(Star {
+                id: Some(Uuid::new_v4()),
+                system_id,
+                project_id,
+                name: unique_name(star_name, &mut star_names),
+                component,
+                spectral_class: properties.spectral_class,
+                spectral_subclass: properties.spectral_subclass,
+                luminosity_class: properties.luminosity_class,
+                temperature_k: properties.temperature_k,
+                mass_solar: properties.mass_solar,
+                radius_solar: properties.radius_solar,
+                luminosity_solar: properties.luminosity_solar,
+                age_gyr,
+                metallicity_feh,
+                evolutionary_stage: properties.evolutionary_stage,
+                habitable_zone: None,
+                photometry: star_photometry(properties.temperature_k, properties.luminosity_solar),
+            });
+        }
+
+        populate_system(&mut rng, &system, first_star, &mut galaxy);
+        galaxy.systems.push(system);
+    }
+
+    if let Some(completeness_radius_ly) = completeness_radius_ly {
+        // A void over the catalogue sphere keeps the fill in the outer shell
+        let catalogue_sphere = DensityZone {
+            id: None,
+            project_id,
+            zone_index: 0,
+            zone_type: ZoneType::Void,
+            center_x_ly: 0.0,
+            center_y_ly: 0.0,
+            center_z_ly: 0.0,
+            radius_ly: completeness_radius_ly,
+            star_share: 0.0,
+            gradient_axis: None,
+            gradient_strength: None,
+        };
+        let fill = GenerationParams {
+            num_systems: params.num_systems.saturating_sub(catalogue.systems.len()),
+            zones: vec![catalogue_sphere],
+            ..params.clone()
+        };
+        galaxy.append(generate_star_systems(project_id, &fill)?);
+    }
+
+    Ok(galaxy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::sphere_params;
+    use crate::generator::component_label;
+
+    const HYG: &str = "\
+id,hip,hd,hr,gl,bf,proper,ra,dec,dist,mag,absmag,spect,ci,comp,comp_primary,base
+0,,,,,,Sol,0.000000,0.000000,0.0000,-26.700,4.850,G2V,0.656,1,0,
+70666,71681,128621,5460,Gl 559B,,,14.660,-60.8356,1.3475,1.350,5.700,K1V,0.900,2,70667,Gl 559
+70667,71683,128620,5459,Gl 559A,,Rigil Kentaurus,14.660,-60.8340,1.3475,-0.010,4.380,G2V,0.710,1,70667,Gl 559
+32263,32349,48915,2491,Gl 244A,9Alp CMa,Sirius,6.7525,-16.7161,2.6371,-1.440,1.450,A0m...,0.009,1,32263,Gl 244
+32264,,,,Gl 244B,,,6.7525,-16.7161,2.6371,8.440,11.180,DA2,-0.030,2,32263,Gl 244
+24378,24436,34085,1713,,19Bet Ori,Rigel,5.2423,-8.2016,264.5503,0.180,-6.933,B8Ia,-0.030,1,24378,
+27919,27989,39801,2061,Gl 222,58Alp Ori,Betelgeuse,5.9195,7.4071,152.6718,0.450,-5.469,,1.850,1,27919,
+99999,1,,,,,,0.0005,1.0890,100000.0000,9.100,,F5,0.482,1,99999,
+100001,5,,,,,,1.0000,1.0000,10.0000,9.000,8.000,,,1,100001,
+";
+
+    const GAIA: &str = "\
+source_id,ra,dec,parallax,phot_g_mean_mag,bp_rp
+101,10.0,20.0,100.0,7.0,1.2
+102,10.001,20.0,100.0,11.0,2.0
+103,200.0,-30.0,50.0,6.0,0.8
+104,150.0,0.0,-1.0,10.0,1.0
+";
+
+    #[test]
+    fn test_parse_spectral_types() {
+        let parse = |s| parse_spectral_type(s).unwrap();
+        assert_eq!(parse("G2V"), SpectralType { class: SpectralClass::G, subclass: Some(2.0), luminosity_class: Some(LuminosityClass::V) });
+        assert_eq!(parse("M4.5Ve").subclass, Some(4.5));
+        assert_eq!(parse("K0III-IV").luminosity_class, Some(LuminosityClass::III));
+        assert_eq!(parse("F5IV-V").luminosity_class, Some(LuminosityClass::IV));
+        assert_eq!(parse("B8Iae").luminosity_class, Some(LuminosityClass::Ia));
+        assert_eq!(parse("M2Iab").luminosity_class, Some(LuminosityClass::Ia));
+        assert_eq!(parse("sdM3").luminosity_class, Some(LuminosityClass::V));
+        assert_eq!(parse("gK5").luminosity_class, Some(LuminosityClass::III));
+        assert_eq!(parse("A0m..."), SpectralType { class: SpectralClass::A, subclass: Some(0.0), luminosity_class: None });
+        assert_eq!(parse("DA2"), SpectralType { class: SpectralClass::DA, subclass: Some(2.0), luminosity_class: None });
+        assert_eq!(parse("DZ").class, SpectralClass::DZ);
+        assert_eq!(parse("WC8").class, SpectralClass::O);
+        assert!(parse_spectral_type("C5,4").is_none());
+        assert!(parse_spectral_type("").is_none());
+
+        // Subclass temperatures sit inside their class
+        assert!((parse("G2V").temperature_k().unwrap() - 5789.0).abs() < 5.0);
+        assert_eq!(parse("DA2").temperature_k(), Some(25200.0));
+    }
+
+    #[test]
+    fn test_read_hyg() {
+        let catalogue = read_catalogue(HYG.as_bytes(), None, 50.0).unwrap();
+        assert_eq!(catalogue.format, CatalogueFormat::Hyg);
+        // Unknown distance and no spectral information; Rigel and Betelgeuse are too far
+        assert_eq!(catalogue.skipped_stars, 2);
+        assert_eq!(catalogue.systems.len(), 3);
+
+        let sol = &catalogue.systems[0];
+        assert_eq!(sol.len(), 1);
+        assert_eq!(sol[0].name, "Sol");
+        assert!(distance(&sol[0].position) < 1e-3);
+        let sun = &sol[0].properties;
+        assert_eq!((sun.spectral_class, sun.spectral_subclass), (SpectralClass::G, Some(2)));
+        assert!((sun.luminosity_solar - 1.0).abs() < 0.1, "{:?}", sun);
+        assert!((sun.mass_solar - 1.0).abs() < 0.05 && (sun.radius_solar - 1.0).abs() < 0.1);
+
+        let alpha_centauri = &catalogue.systems[1];
+        assert_eq!(alpha_centauri.len(), 2);
+        assert_eq!(alpha_centauri[0].name, "Rigil Kentaurus", "The heavier G star comes first");
+        // RA 14.66h, Dec -60.83° at 4.39 ly
+        let p = &alpha_centauri[0].position;
+        assert!((distance(p) - 4.395).abs() < 0.01);
+        assert!((p.y.atan2(p.x).to_degrees().rem_euclid(360.0) - 219.9).abs() < 0.1);
+        assert!(p.z < 0.0);
+
+        let sirius = &catalogue.systems[2];
+        assert_eq!(sirius[0].properties.evolutionary_stage, EvolutionaryStage::MainSequence);
+        assert!((sirius[0].properties.mass_solar - 2.1).abs() < 0.3, "{:?}", sirius[0].properties);
+        let sirius_b = &sirius[1].properties;
+        assert_eq!(sirius_b.evolutionary_stage, EvolutionaryStage::WhiteDwarf);
+        assert_eq!(sirius_b.spectral_subclass, Some(2));
+        assert!((sirius_b.radius_solar - 0.0084).abs() < 0.002, "{:?}", sirius_b);
+
+        // Farther out, Rigel is a supergiant and untyped Betelgeuse is placed by the HR diagram
+        let far = read_catalogue(HYG.as_bytes(), Some(CatalogueFormat::Hyg), 1000.0).unwrap();
+        let find = |name: &str| far.systems.iter().flatten().find(|e| e.name == name).unwrap().properties.clone();
+        let rigel = find("Rigel");
+        assert_eq!(rigel.evolutionary_stage, EvolutionaryStage::Supergiant);
+        assert!(rigel.luminosity_solar > 3.0e4 && rigel.mass_solar > 15.0, "{:?}", rigel);
+        let betelgeuse = find("Betelgeuse");
+        assert!(betelgeuse.luminosity_solar > 1.0e4, "{:?}", betelgeuse);
+        assert!(betelgeuse.radius_solar > 300.0 && betelgeuse.spectral_class == SpectralClass::M);
+    }
+
+    #[test]
+    fn test_read_gaia() {
+        let catalogue = read_catalogue(GAIA.as_bytes(), None, 100.0).unwrap();
+        assert_eq!(catalogue.format, CatalogueFormat::Gaia);
+        assert_eq!(catalogue.skipped_stars, 1, "Negative parallaxes give no distance");
+        assert_eq!(catalogue.systems.len(), 2);
+
+        // Two stars 0.0006 ly apart at 32.6 ly form a binary
+        let binary = &catalogue.systems[0];
+        assert_eq!(binary.len(), 2);
+        assert_eq!(binary[0].name, "Gaia DR3 101");
+        assert!((distance(&binary[0].position) - 10.0 * LY_PER_PARSEC).abs() < 1e-9);
+        // BP-RP 1.2 is a K dwarf, 2.0 an M dwarf
+        assert_eq!(binary[0].properties.spectral_class, SpectralClass::K);
+        assert_eq!(binary[1].properties.spectral_class, SpectralClass::M);
+        assert_eq!(binary[1].properties.evolutionary_stage, EvolutionaryStage::MainSequence);
+
+        assert!(read_catalogue("name,x,y\nfoo,1,2\n".as_bytes(), None, 100.0).is_err());
+        assert!(read_catalogue("ra,dec\n1,2\n".as_bytes(), Some(CatalogueFormat::Gaia), 100.0).is_err());
+    }
+
+    #[test]
+    fn test_gaia_names_fit_their_columns() {
+        // Real DR3 source ids have 19 digits; the widths are the name
+        // columns of sql/02_create_tables.sql
+        let gaia = "\
+source_id,ra,dec,parallax,phot_g_mean_mag,bp_rp
+4472832130942575872,10.0,20.0,100.0,7.0,1.2
+4472832130942575873,10.001,20.0,100.0,11.0,2.0
+5853498713190525696,200.0,-30.0,50.0,6.0,0.8
+";
+        let catalogue = read_catalogue(gaia.as_bytes(), None, 100.0).unwrap();
+        assert_eq!(catalogue.systems[0][0].name, "Gaia DR3 4472832130942575872");
+
+        let mut planets = 0;
+        for seed in 0..10 {
+            let galaxy = import_catalogue(Uuid::new_v4(), &catalogue, &sphere_params(2, 100.0, seed), None).unwrap();
+            assert!(galaxy.systems.iter().all(|s| s.name.len() <= 64));
+            assert!(galaxy.stars.iter().all(|s| s.name.len() <= 64));
+            assert!(galaxy.planets.iter().all(|p| p.name.len() <= 80));
+            assert!(galaxy.moons.iter().all(|m| m.name.len() <= 96));
+            assert!(galaxy.belts.iter().all(|b| b.name.len() <= 96));
+            planets += galaxy.planets.len();
+        }
+        assert!(planets > 0);
+    }
+
+    #[test]
+    fn test_import_with_fill() {
+        let catalogue = read_catalogue(HYG.as_bytes(), None, 20.0).unwrap();
+        let fill = catalogue.fill_count(40.0, 20.0);
+        assert_eq!(fill, 21, "Seven times the catalogue sphere's volume at the same density");
+
+        let params = sphere_params(catalogue.systems.len() + fill, 40.0, 19);
+        let project_id = Uuid::new_v4();
+        let galaxy = import_catalogue(project_id, &catalogue, &params, Some(20.0)).unwrap();
+        assert_eq!(galaxy.systems.len(), 24);
+
+        let names: Vec<&str> = galaxy.systems.iter().take(3).map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Sol", "Rigil Kentaurus", "Sirius"]);
+        assert_eq!(galaxy.systems[1].system_type, SystemType::Binary);
+        let sirius_stars: Vec<&str> = galaxy
+            .stars
+            .iter()
+            .filter(|s| s.system_id == galaxy.systems[2].id.unwrap())
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(sirius_stars, ["Sirius", "Gl 244B"]);
+        assert!(galaxy.stars.iter().all(|s| s.photometry.is_some() && s.project_id == project_id));
+
+        for system in &galaxy.systems[3..] {
+            let r = (system.x_ly.powi(2) + system.y_ly.powi(2) + system.z_ly.powi(2)).sqrt();
+            assert!((20.0..=40.0).contains(&r), "Fill systems belong in the outer shell, found one at {} ly", r);
+        }
+
+        let again = import_catalogue(project_id, &catalogue, &params, Some(20.0)).unwrap();
+        assert_eq!(again.planets.len(), galaxy.planets.len(), "The same seed gives the same planets");
+    }
+
+    #[test]
+    fn test_imported_multiples_label_components() {
+        let catalogue = read_catalogue(HYG.as_bytes(), None, 20.0).unwrap();
+        let mut circumbinary = 0;
+
+        for seed in 0..40 {
+            let params = sphere_params(catalogue.systems.len(), 20.0, seed);
+            let galaxy = import_catalogue(Uuid::new_v4(), &catalogue, &params, None).unwrap();
+
+            for (system_name, names) in [("Sirius", ["Sirius", "Gl 244B"]), ("Rigil Kentaurus", ["Rigil Kentaurus", "Gl 559B"])] {
+                let system = galaxy.systems.iter().find(|s| s.name == system_name).unwrap();
+                let stars: Vec<Star> = galaxy.stars.iter().filter(|s| s.system_id == system.id.unwrap()).cloned().collect();
+                let components: Vec<&str> = stars.iter().map(|s| s.component.as_str()).collect();
+                assert_eq!(components, ["a", "b"]);
+
+                let orbit = galaxy.orbits.iter().find(|o| o.system_id == system.id.unwrap()).unwrap();
+                assert_eq!(component_label(system_name, &stars, &orbit.primary_component), names[0]);
+                assert_eq!(component_label(system_name, &stars, &orbit.secondary_component), names[1]);
+
+                for planet in galaxy.planets.iter().filter(|p| p.system_id == system.id.unwrap()) {
+                    let host = match planet.barycentre_orbit_id {
+                        Some(_) => {
+                            circumbinary += 1;
+                            format!("{} AB", system_name)
+                        },
+                        None => stars.iter().find(|s| s.id == Some(planet.star_id)).unwrap().name.clone(),
+                    };
+                    assert_eq!(planet.host_name(), host);
+                }
+            }
+        }
+        assert!(circumbinary > 0, "Expected circumbinary planets around Sirius or Rigil Kentaurus");
+    }
+}
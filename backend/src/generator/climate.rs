@@ -0,0 +1,293 @@
+use crate::models::{Atmosphere, Climate, Planet, PlanetType, Star, WorldProfile};
+use super::{runaway_greenhouse_flux, snow_line_au, EARTH_RADIUS_KM};
+
+/// Equilibrium temperature of a black body at Earth's insolation, in K:
+/// T = 278.6 K (S (1 - A))^¼
+const EQUILIBRIUM_AT_EARTH_K: f64 = 278.6;
+
+/// Insolation at which an Earth-like escape velocity can no longer hold an
+/// atmosphere; the "cosmic shoreline" scales as v_esc⁴ (Zahnle & Catling 2017)
+const SHORELINE_INSOLATION: f64 = 25.0;
+
+/// Super-Earths above this mass keep a hydrogen envelope (mini-Neptunes)
+const MINI_NEPTUNE_MASS_EARTH: f64 = 5.0;
+
+/// Earth's greenhouse warming in K at 1 bar
+const EARTH_GREENHOUSE_K: f64 = 33.0;
+
+/// Venus-like greenhouse warming in K at 90 bar
+const DENSE_GREENHOUSE_K: f64 = 500.0;
+
+/// Surface temperatures above which water boils off and rock melts
+const HOTHOUSE_K: f64 = 350.0;
+const MOLTEN_K: f64 = 1200.0;
+
+/// Below this mean surface temperature the surface is mostly ice
+const GLACIAL_K: f64 = 260.0;
+
+/// Worlds with less water than this are deserts
+const ARID_WATER_FRACTION: f64 = 0.1;
+
+/// Tidal locking: tidal quality factor Q, Love number k2 and moment of
+/// inertia factor for rocky worlds and giants (Gladman et al. 1996)
+const ROCKY_TIDES: (f64, f64, f64) = (100.0, 0.3, 0.33);
+const GIANT_TIDES: (f64, f64, f64) = (1e5, 0.5, 0.25);
+
+const G: f64 = 6.674e-11;
+const SOLAR_MASS_KG: f64 = 1.989e30;
+const EARTH_MASS_KG: f64 = 5.972e24;
+const AU_M: f64 = 1.496e11;
+const SECONDS_PER_YEAR: f64 = 3.156e7;
+
+/// Surface, atmosphere and climate of a planet
+/// Needs the planet's insolation, so it runs after `insolation`. The host
+/// sets the runaway-greenhouse threshold, the snow line the planet's water
+/// came from and the system's age for tidal locking; the mass the planet
+/// orbits (one star or a close pair) follows from its period
+pub fn world_profile(planet: &Planet, host: &Star) -> WorldProfile {
+    let mass = planet.mass_earth;
+    let radius = planet.radius_earth;
+    let insolation = planet.insolation_earth;
+
+    // Escape velocity relative to Earth's
+    let escape_velocity = (mass / radius).sqrt();
+    let shoreline = SHORELINE_INSOLATION * escape_velocity.powi(4);
+    let atmosphere = match planet.planet_type {
+        PlanetType::GasGiant | PlanetType::IceGiant => Atmosphere::HydrogenEnvelope,
+        PlanetType::SuperEarth if mass > MINI_NEPTUNE_MASS_EARTH => Atmosphere::HydrogenEnvelope,
+        _ if insolation > shoreline => Atmosphere::Airless,
+        _ if insolation > runaway_greenhouse_flux(host.temperature_k) => Atmosphere::Dense,
+        // Small or close to the shoreline: mostly lost to space
+        _ if mass < 0.3 || insolation > shoreline / 4.0 => Atmosphere::Thin,
+        _ => Atmosphere::Moderate,
+    };
+
+    // Outgassed inventory ∝ M, spread over R² under gravity M / R²
+    let base_pressure = mass.powi(2) / radius.powi(4);
+    let surface_pressure_bar = match atmosphere {
+        Atmosphere::Airless => Some(0.0),
+        Atmosphere::Thin => Some(0.05 * base_pressure),
+        Atmosphere::Moderate => Some(base_pressure),
+        Atmosphere::Dense => Some(90.0 * base_pressure),
+        Atmosphere::HydrogenEnvelope => None,
+    };
+
+    // Water delivered from beyond the snow line: about 70% cover at
+    // Earth's distance, ocean worlds near the line itself
+    let snow_line = snow_line_au(host.mass_solar);
+    let inventory = (0.3 + 1.2 * planet.semi_major_axis_au / snow_line
+        + if planet.planet_type == PlanetType::SuperEarth { 0.1 } else { 0.0 })
+    .min(1.0);
+
+    let temperatures = |albedo: f64| {
+        let equilibrium = EQUILIBRIUM_AT_EARTH_K * (insolation * (1.0 - albedo)).powf(0.25);
+        let surface = match (atmosphere, surface_pressure_bar) {
+            (Atmosphere::HydrogenEnvelope, _) => {
+                // Internal heat warms giants above equilibrium
+                equilibrium * if planet.planet_type == PlanetType::GasGiant { 1.4 } else { 1.3 }
+            },
+            (Atmosphere::Dense, Some(p)) => equilibrium + DENSE_GREENHOUSE_K * (p / 90.0).powf(0.3),
+            (_, Some(p)) => equilibrium + EARTH_GREENHOUSE_K * p.powf(0.3),
+            (_, None) => equilibrium,
+        };
+        (equilibrium, surface)
+    };
+
+    let mut albedo = match atmosphere {
+        Atmosphere::HydrogenEnvelope if planet.planet_type == PlanetType::GasGiant => 0.34,
+        Atmosphere::HydrogenEnvelope => 0.29,
+        Atmosphere::Airless => 0.12,
+        Atmosphere::Thin => 0.25,
+        Atmosphere::Moderate => 0.3,
+        // Sulphuric-acid clouds
+        Atmosphere::Dense => 0.75,
+    };
+    let (mut equilibrium_temperature_k, mut surface_temperature_k) = temperatures(albedo);
+
+    let water_fraction = match atmosphere {
+        Atmosphere::HydrogenEnvelope => 0.0,
+        _ if surface_temperature_k > HOTHOUSE_K => 0.0,
+        // Only ice survives in cold, permanently shadowed ground
+        Atmosphere::Airless if equilibrium_temperature_k < 150.0 => 0.5 * inventory,
+        Atmosphere::Airless => 0.0,
+        Atmosphere::Thin => 0.2 * inventory,
+        _ => inventory,
+    };
+
+    // Ice-albedo feedback: cold, wet worlds freeze over and reflect more
+    if atmosphere != Atmosphere::HydrogenEnvelope
+        && atmosphere != Atmosphere::Airless
+        && surface_temperature_k < GLACIAL_K
+        && water_fraction >= ARID_WATER_FRACTION
+    {
+        albedo = 0.6;
+        (equilibrium_temperature_k, surface_temperature_k) = temperatures(albedo);
+    }
+
+    let (tidally_locked, day_length_hours) = rotation(planet, host.age_gyr);
+
+    let climate = match atmosphere {
+        Atmosphere::HydrogenEnvelope => Climate::Gaseous,
+        _ if surface_temperature_k > MOLTEN_K => Climate::Molten,
+        Atmosphere::Airless => Climate::Barren,
+        _ if surface_temperature_k > HOTHOUSE_K => Climate::Hothouse,
+        _ if surface_temperature_k < GLACIAL_K => Climate::Glacial,
+        _ if water_fraction < ARID_WATER_FRACTION => Climate::Arid,
+        _ if surface_temperature_k > 300.0 => Climate::Tropical,
+        _ if surface_temperature_k >= 280.0 => Climate::Temperate,
+        _ => Climate::Boreal,
+    };
+
+    WorldProfile {
+        albedo,
+        equilibrium_temperature_k,
+        surface_temperature_k,
+        atmosphere,
+        surface_pressure_bar,
+        water_fraction,
+        tidally_locked,
+        day_length_hours,
+        climate,
+    }
+}
+
+/// Whether a planet is tidally locked, and its rotation period in hours
+/// Tides spin a planet down from its primordial rotation on the timescale
+/// t = ω a⁶ I Q / (3 G M² k₂ R⁵) (Gladman et al. 1996). Planets older than
+/// that rotate with their orbit, or 3:2 like Mercury when eccentric
+fn rotation(planet: &Planet, age_gyr: f64) -> (bool, f64) {
+    let giant = matches!(planet.planet_type, PlanetType::GasGiant | PlanetType::IceGiant);
+    let (q, k2, inertia_factor) = if giant { GIANT_TIDES } else { ROCKY_TIDES };
+    let primordial_hours = if giant { 10.0 } else { 20.0 * planet.mass_earth.powf(-0.25) };
+    let orbital_hours = planet.period_years * 365.25 * 24.0;
+
+    // Mass the planet orbits, from Kepler's third law
+    let host_mass_kg = planet.semi_major_axis_au.powi(3) / planet.period_years.powi(2) * SOLAR_MASS_KG;
+    let mass_kg = planet.mass_earth * EARTH_MASS_KG;
+    let radius_m = planet.radius_earth * EARTH_RADIUS_KM * 1000.0;
+    let spin = 2.0 * std::f64::consts::PI / (primordial_hours * 3600.0);
+
+    let lock_seconds = spin * (planet.semi_major_axis_au * AU_M).powi(6) * inertia_factor * mass_kg * radius_m.powi(2) * q
+        / (3.0 * G * host_mass_kg.powi(2) * k2 * radius_m.powi(5));
+    let lock_gyr = lock_seconds / SECONDS_PER_YEAR / 1e9;
+
+    if lock_gyr < age_gyr {
+        if planet.eccentricity < 0.1 {
+            (true, orbital_hours)
+        } else {
+            (false, orbital_hours * 2.0 / 3.0)
+        }
+    } else {
+        // Partly spun down on the way to locking
+        (false, (primordial_hours * (1.0 + age_gyr / lock_gyr)).min(orbital_hours))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::{sphere_params, sun_like_star};
+    use crate::generator::{generate_star_systems, kepler_period_years};
+    use std::collections::HashSet;
+    use uuid::Uuid;
+
+    fn sun() -> Star {
+        sun_like_star(1.0)
+    }
+
+    fn planet(planet_type: PlanetType, mass_earth: f64, radius_earth: f64, a: f64) -> Planet {
+        Planet {
+            id: Some(Uuid::new_v4()),
+            star_id: Uuid::nil(),
+            system_id: Uuid::nil(),
+            project_id: Uuid::nil(),
+            name: "S0001 I".to_string(),
+            orbit_index: 1,
+            barycentre_orbit_id: None,
+            planet_type,
+            semi_major_axis_au: a,
+            eccentricity: 0.0,
+            period_years: a.powf(1.5),
+            mass_earth,
+            radius_earth,
+            ring: None,
+            profile: WorldProfile::default(),
+            insolation_earth: 1.0 / (a * a),
+            habitability: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_solar_system_climates() {
+        let sun = sun();
+
+        let earth = world_profile(&planet(PlanetType::Rocky, 1.0, 1.0, 1.0), &sun);
+        assert_eq!(earth.atmosphere, Atmosphere::Moderate);
+        assert_eq!(earth.climate, Climate::Temperate);
+        assert!((earth.equilibrium_temperature_k - 255.0).abs() < 2.0);
+        assert!((earth.surface_temperature_k - 288.0).abs() < 2.0);
+        assert!((earth.water_fraction - 0.71).abs() < 0.1);
+        assert!(!earth.tidally_locked);
+
+        let venus = world_profile(&planet(PlanetType::Rocky, 0.815, 0.95, 0.723), &sun);
+        assert_eq!(venus.atmosphere, Atmosphere::Dense);
+        assert_eq!(venus.climate, Climate::Hothouse);
+        assert!((venus.surface_temperature_k - 737.0).abs() < 60.0);
+        assert_eq!(venus.water_fraction, 0.0);
+
+        let mercury = world_profile(&planet(PlanetType::Rocky, 0.055, 0.383, 0.387), &sun);
+        assert_eq!(mercury.atmosphere, Atmosphere::Airless);
+        assert_eq!(mercury.climate, Climate::Barren);
+
+        let mars = world_profile(&planet(PlanetType::Rocky, 0.107, 0.532, 1.524), &sun);
+        assert_eq!(mars.atmosphere, Atmosphere::Thin);
+        assert_eq!(mars.climate, Climate::Glacial);
+
+        let jupiter = world_profile(&planet(PlanetType::GasGiant, 317.8, 11.2, 5.2), &sun);
+        assert_eq!(jupiter.climate, Climate::Gaseous);
+        assert_eq!(jupiter.surface_pressure_bar, None);
+        assert!((jupiter.equilibrium_temperature_k - 110.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_close_planets_lock() {
+        let mut m_dwarf = sun();
+        m_dwarf.mass_solar = 0.3;
+
+        // A temperate orbit around a 0.3 M☉ star locks within the star's age
+        let mut close = planet(PlanetType::Rocky, 1.0, 1.0, 0.1);
+        close.period_years = kepler_period_years(0.1, 0.3);
+        let profile = world_profile(&close, &m_dwarf);
+        assert!(profile.tidally_locked);
+        assert!((profile.day_length_hours - close.period_years * 365.25 * 24.0).abs() < 1e-6);
+
+        // Eccentric orbits settle into a 3:2 resonance instead
+        close.eccentricity = 0.2;
+        let profile = world_profile(&close, &m_dwarf);
+        assert!(!profile.tidally_locked);
+        assert!((profile.day_length_hours - close.period_years * 365.25 * 16.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_generated_worlds() {
+        let galaxy = generate_star_systems(
+            Uuid::new_v4(),
+            &sphere_params(1000, 300.0, 14),
+        )
+        .unwrap();
+
+        let mut climates = HashSet::new();
+        for planet in &galaxy.planets {
+            let profile = planet.profile;
+            assert!((0.0..1.0).contains(&profile.albedo));
+            assert!(profile.surface_temperature_k >= profile.equilibrium_temperature_k);
+            assert!((0.0..=1.0).contains(&profile.water_fraction));
+            assert!(profile.day_length_hours > 0.0);
+            assert_eq!(profile.surface_pressure_bar.is_none(), profile.climate == Climate::Gaseous);
+            climates.insert(profile.climate);
+        }
+
+        assert!(climates.contains(&Climate::Temperate), "Expected some temperate worlds");
+        assert!(climates.len() >= 6, "Expected a spread of climates, got {:?}", climates);
+    }
+}
@@ -0,0 +1,137 @@
+use rand::Rng;
+use uuid::Uuid;
+use crate::models::{Constellation, ConstellationStar, SkyStar, StarSystem};
+use super::{angular_separation_deg, bayer_designation, generate_constellation_name, seeded_rng};
+
+/// Brightest stars considered, about as many as form the classical
+/// constellations; the rest of the sky stays unnamed
+const MAX_FIGURE_STARS: usize = 300;
+
+/// Farthest a star may lie from the figure it joins, in degrees
+const LINK_ANGLE_DEG: f64 = 20.0;
+
+/// Stars per constellation
+const MIN_FIGURE_STARS: usize = 3;
+const MAX_FIGURE_STARS_EACH: usize = 10;
+
+/// Group the bright stars of an observer's sky into constellations
+/// Each figure starts from the brightest star not yet used and grows by the
+/// nearest remaining star within 20° of any member, joined to that member
+/// by a line, so the lines form a tree. Figures that stay below three stars
+/// are dropped. Companions count once, as their system's brightest star.
+/// `sky` must be brightest first, as `sky_view` returns it; the same seed
+/// and sky give the same constellations
+pub fn generate_constellations(observer: &StarSystem, sky: &[SkyStar], seed: u64) -> Vec<Constellation> {
+    let mut rng = seeded_rng(seed);
+    let mut candidates: Vec<&SkyStar> = Vec::new();
+    for star in sky {
+        if candidates.len() == MAX_FIGURE_STARS {
+            break;
+        }
+        if !candidates.iter().any(|c| c.system_name == star.system_name) {
+            candidates.push(star);
+        }
+    }
+
+    let mut used = vec![false; candidates.len()];
+    let mut constellations: Vec<Constellation> = Vec::new();
+
+    while let Some(first) = used.iter().position(|u| !u) {
+        used[first] = true;
+        let target = rng.gen_range(MIN_FIGURE_STARS..=MAX_FIGURE_STARS_EACH);
+        // (candidate index, member it is drawn to)
+        let mut members: Vec<(usize, Option<usize>)> = vec![(first, None)];
+
+        while members.len() < target {
+            let nearest = (0..candidates.len())
+                .filter(|&i| !used[i])
+                .flat_map(|i| members.iter().map(move |&(m, _)| (i, m)))
+                .map(|(i, m)| (i, m, angular_separation_deg(candidates[i], candidates[m])))
+                .filter(|&(_, _, angle)| angle <= LINK_ANGLE_DEG)
+                .min_by(|a, b| a.2.total_cmp(&b.2));
+            let Some((i, m, _)) = nearest else {
+                break;
+            };
+            used[i] = true;
+            members.push((i, Some(m)));
+        }
+
+        if members.len() < MIN_FIGURE_STARS {
+            continue;
+        }
+
+        let mut name = generate_constellation_name(&mut rng);
+        while constellations.iter().any(|c| c.name == name) {
+            name = generate_constellation_name(&mut rng);
+        }
+
+        // Candidates are brightest first, so sorting by index ranks by brightness
+        let mut ranked: Vec<usize> = members.iter().map(|&(i, _)| i).collect();
+        ranked.sort_unstable();
+        let stars = ranked
+            .iter()
+            .enumerate()
+            .map(|(rank, &i)| {
+                let line_to = members.iter().find(|&&(m, _)| m == i).and_then(|&(_, to)| to);
+                ConstellationStar {
+                    star_id: candidates[i].star_id,
+                    bayer_index: rank as i32 + 1,
+                    designation: bayer_designation(&name, rank),
+                    line_to_star_id: line_to.map(|to| candidates[to].star_id),
+                }
+            })
+            .collect();
+
+        constellations.push(Constellation {
+            id: Some(Uuid::new_v4()),
+            project_id: observer.project_id,
+            observer_system_id: observer.id.unwrap_or_default(),
+            name,
+            seed,
+            stars,
+        });
+    }
+
+    constellations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::sphere_params;
+    use crate::generator::{generate_star_systems, sky_view};
+    use crate::models::SkyFrame;
+
+    #[test]
+    fn test_constellations() {
+        let galaxy = generate_star_systems(
+            Uuid::new_v4(),
+            &sphere_params(3000, 200.0, 17),
+        )
+        .unwrap();
+        let home = &galaxy.systems[0];
+        let sky = sky_view(home, &galaxy.systems, &galaxy.stars, SkyFrame::Equatorial, f64::INFINITY);
+        let constellations = generate_constellations(home, &sky, 1);
+        assert!(constellations.len() >= 20, "Expected several constellations, got {}", constellations.len());
+
+        let mut seen = Vec::new();
+        for constellation in &constellations {
+            assert!((MIN_FIGURE_STARS..=MAX_FIGURE_STARS_EACH).contains(&constellation.stars.len()));
+            assert_eq!(constellation.stars[0].designation, format!("Alpha {}", constellation.name));
+
+            let magnitude = |id: Uuid| sky.iter().find(|s| s.star_id == id).unwrap().apparent_magnitude;
+            for pair in constellation.stars.windows(2) {
+                assert!(magnitude(pair[0].star_id) <= magnitude(pair[1].star_id));
+            }
+            // One line per star after the first, each to a fellow member
+            let lines: Vec<Uuid> = constellation.stars.iter().filter_map(|s| s.line_to_star_id).collect();
+            assert_eq!(lines.len(), constellation.stars.len() - 1);
+            assert!(lines.iter().all(|id| constellation.stars.iter().any(|s| s.star_id == *id)));
+
+            for star in &constellation.stars {
+                assert!(!seen.contains(&star.star_id), "A star belongs to one constellation");
+                seen.push(star.star_id);
+            }
+        }
+    }
+}
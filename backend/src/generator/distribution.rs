@@ -1,11 +1,47 @@
 use rand::Rng;
-use crate::models::star_system::Position3D;
+use crate::models::{DistributionType, Position3D};
 use std::f64::consts::PI;
 
-/// Generate a random position within a cube
-pub fn generate_cube_position(size_x: f64, size_y: f64, size_z: f64) -> Position3D {
-    let mut rng = rand::thread_rng();
+/// Radius where logarithmic spiral arms start, as a fraction of the galaxy radius
+const SPIRAL_ARM_START: f64 = 0.05;
+
+/// Gaussian offsets drawn for a spiral star before the last one is pulled
+/// back onto the edge of the disc
+const MAX_SCATTER_ATTEMPTS: usize = 100;
+
+/// Ratio of de Vaucouleurs effective radius to the Hernquist scale radius
+/// (the Hernquist sphere projects to an r^1/4 profile with R_e = 1.8153 a)
+const HERNQUIST_RE_RATIO: f64 = 1.8153;
+
+/// Generate a random position for any distribution shape
+pub fn generate_position<R: Rng + ?Sized>(rng: &mut R, distribution: &DistributionType) -> Position3D {
+    match *distribution {
+        DistributionType::Cube { size_x, size_y, size_z } => {
+            generate_cube_position(rng, size_x, size_y, size_z)
+        },
+        DistributionType::Sphere { radius } => generate_sphere_position(rng, radius),
+        DistributionType::Spiral { radius, arm_count, pitch_angle_deg, arm_spread, scale_height } => {
+            generate_spiral_position(rng, radius, arm_count, pitch_angle_deg, arm_spread, scale_height)
+        },
+        DistributionType::Disk { radius, scale_length, scale_height } => {
+            generate_disk_position(rng, radius, scale_length, scale_height)
+        },
+        DistributionType::Bulge { radius, scale_length, axis_ratio } => {
+            generate_bulge_position(rng, radius, scale_length, axis_ratio)
+        },
+        DistributionType::Elliptical { radius, effective_radius, axis_ratio } => {
+            generate_elliptical_position(rng, radius, effective_radius, axis_ratio)
+        },
+    }
+}
 
+/// Generate a random position within a cube
+pub fn generate_cube_position<R: Rng + ?Sized>(
+    rng: &mut R,
+    size_x: f64,
+    size_y: f64,
+    size_z: f64,
+) -> Position3D {
     Position3D {
         x: rng.gen_range(-size_x/2.0..size_x/2.0),
         y: rng.gen_range(-size_y/2.0..size_y/2.0),
@@ -15,9 +51,7 @@ pub fn generate_cube_position(size_x: f64, size_y: f64, size_z: f64) -> Position
 
 /// Generate a random position within a sphere using polar coordinates
 /// This ensures uniform distribution within the sphere volume
-pub fn generate_sphere_position(radius: f64) -> Position3D {
-    let mut rng = rand::thread_rng();
-
+pub fn generate_sphere_position<R: Rng + ?Sized>(rng: &mut R, radius: f64) -> Position3D {
     // Random distance from center (cube root for uniform volume distribution)
     let r = rng.gen::<f64>().powf(1.0/3.0) * radius;
 
@@ -36,13 +70,150 @@ pub fn generate_sphere_position(radius: f64) -> Position3D {
     }
 }
 
+/// Generate a random position in a logarithmic spiral galaxy
+/// Radius follows an exponential disk (scale length radius/4); each star is
+/// placed on one of `arm_count` arms r = r0 * e^(tan(pitch) * θ) and
+/// scattered by a Gaussian of width `arm_spread` around it, resampling
+/// offsets that would leave the disc
+/// A star still outside after `MAX_SCATTER_ATTEMPTS` is moved to the rim
+pub fn generate_spiral_position<R: Rng + ?Sized>(
+    rng: &mut R,
+    radius: f64,
+    arm_count: i32,
+    pitch_angle_deg: f64,
+    arm_spread: f64,
+    scale_height: f64,
+) -> Position3D {
+    let r = sample_disk_radius(rng, radius, radius / 4.0);
+    let r0 = radius * SPIRAL_ARM_START;
+
+    let arm = rng.gen_range(0..arm_count.max(1));
+    let winding = pitch_angle_deg.to_radians().tan();
+    let theta = (r / r0).ln() / winding + 2.0 * PI * arm as f64 / arm_count.max(1) as f64;
+
+    let (mut x, mut y) = (0.0, 0.0);
+    for _ in 0..MAX_SCATTER_ATTEMPTS {
+        let (dx, dy) = gaussian_pair(rng);
+        x = r * theta.cos() + dx * arm_spread;
+        y = r * theta.sin() + dy * arm_spread;
+        if x * x + y * y <= radius * radius {
+            break;
+        }
+    }
+    let scale = (radius / x.hypot(y)).min(1.0);
+
+    Position3D {
+        x: x * scale,
+        y: y * scale,
+        z: sample_laplace(rng, scale_height),
+    }
+}
+
+/// Generate a random position in an exponential disk
+/// Surface density falls off as e^(-r/scale_length), vertical density as
+/// e^(-|z|/scale_height); the disk is truncated at `radius`
+pub fn generate_disk_position<R: Rng + ?Sized>(
+    rng: &mut R,
+    radius: f64,
+    scale_length: f64,
+    scale_height: f64,
+) -> Position3D {
+    let r = sample_disk_radius(rng, radius, scale_length);
+    let theta = rng.gen::<f64>() * 2.0 * PI;
+
+    Position3D {
+        x: r * theta.cos(),
+        y: r * theta.sin(),
+        z: sample_laplace(rng, scale_height),
+    }
+}
+
+/// Generate a random position in a central bulge
+/// Volume density falls off as e^(-r/scale_length), truncated at `radius`
+/// and flattened along z by `axis_ratio`
+pub fn generate_bulge_position<R: Rng + ?Sized>(
+    rng: &mut R,
+    radius: f64,
+    scale_length: f64,
+    axis_ratio: f64,
+) -> Position3D {
+    // r^2 e^(-r/h) is a Gamma(3, h) distribution: sum of three exponentials
+    let r = loop {
+        let r = -scale_length * (rng.gen::<f64>() * rng.gen::<f64>() * rng.gen::<f64>()).max(f64::MIN_POSITIVE).ln();
+        if r <= radius {
+            break r;
+        }
+    };
+
+    spheroid_point(rng, r, axis_ratio)
+}
+
+/// Generate a random position in an elliptical galaxy
+/// Uses a Hernquist sphere, whose projection follows de Vaucouleurs' r^1/4
+/// law, truncated at `radius` and flattened along z by `axis_ratio`
+pub fn generate_elliptical_position<R: Rng + ?Sized>(
+    rng: &mut R,
+    radius: f64,
+    effective_radius: f64,
+    axis_ratio: f64,
+) -> Position3D {
+    let a = effective_radius / HERNQUIST_RE_RATIO;
+
+    // Hernquist cumulative mass M(<r) = r^2 / (r + a)^2; sampling below
+    // M(radius) gives the truncated profile without rejection
+    let m_max = (radius / (radius + a)).powi(2);
+    let s = (rng.gen::<f64>() * m_max).sqrt();
+    let r = a * s / (1.0 - s);
+
+    spheroid_point(rng, r, axis_ratio)
+}
+
+/// Sample a cylindrical radius from an exponential disk truncated at `radius`
+fn sample_disk_radius<R: Rng + ?Sized>(rng: &mut R, radius: f64, scale_length: f64) -> f64 {
+    // r e^(-r/h) is a Gamma(2, h) distribution: sum of two exponentials
+    loop {
+        let r = -scale_length * (rng.gen::<f64>() * rng.gen::<f64>()).max(f64::MIN_POSITIVE).ln();
+        if r <= radius {
+            return r;
+        }
+    }
+}
+
+/// Sample a signed height from a two-sided exponential with the given scale
+fn sample_laplace<R: Rng + ?Sized>(rng: &mut R, scale: f64) -> f64 {
+    let magnitude = -scale * (1.0 - rng.gen::<f64>()).ln();
+    if rng.gen::<bool>() { magnitude } else { -magnitude }
+}
+
+/// Two independent standard normal values (Box-Muller)
+fn gaussian_pair<R: Rng + ?Sized>(rng: &mut R) -> (f64, f64) {
+    let u1 = (1.0 - rng.gen::<f64>()).max(f64::MIN_POSITIVE);
+    let u2 = rng.gen::<f64>();
+    let mag = (-2.0 * u1.ln()).sqrt();
+    (mag * (2.0 * PI * u2).cos(), mag * (2.0 * PI * u2).sin())
+}
+
+/// Point at distance `r` in a random direction, flattened along z
+fn spheroid_point<R: Rng + ?Sized>(rng: &mut R, r: f64, axis_ratio: f64) -> Position3D {
+    let theta = rng.gen::<f64>() * 2.0 * PI;
+    let phi = (rng.gen::<f64>() * 2.0 - 1.0).acos();
+
+    Position3D {
+        x: r * phi.sin() * theta.cos(),
+        y: r * phi.sin() * theta.sin(),
+        z: r * phi.cos() * axis_ratio,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::seeded_rng;
 
     #[test]
     fn test_cube_position() {
-        let pos = generate_cube_position(100.0, 100.0, 100.0);
+        let mut rng = seeded_rng(1);
+        let pos = generate_cube_position(&mut rng, 100.0, 100.0, 100.0);
         assert!(pos.x >= -50.0 && pos.x <= 50.0);
         assert!(pos.y >= -50.0 && pos.y <= 50.0);
         assert!(pos.z >= -50.0 && pos.z <= 50.0);
@@ -50,8 +221,9 @@ mod tests {
 
     #[test]
     fn test_sphere_position() {
+        let mut rng = seeded_rng(1);
         let radius = 100.0;
-        let pos = generate_sphere_position(radius);
+        let pos = generate_sphere_position(&mut rng, radius);
         let distance = (pos.x.powi(2) + pos.y.powi(2) + pos.z.powi(2)).sqrt();
         assert!(distance <= radius, "Point should be within sphere radius");
     }
@@ -59,12 +231,13 @@ mod tests {
     #[test]
     fn test_sphere_distribution() {
         // Test that points are distributed throughout the volume
+        let mut rng = seeded_rng(2);
         let radius = 100.0;
         let mut min_dist = f64::MAX;
         let mut max_dist: f64 = 0.0;
 
         for _ in 0..1000 {
-            let pos = generate_sphere_position(radius);
+            let pos = generate_sphere_position(&mut rng, radius);
             let dist = (pos.x.powi(2) + pos.y.powi(2) + pos.z.powi(2)).sqrt();
             min_dist = min_dist.min(dist);
             max_dist = max_dist.max(dist);
@@ -74,4 +247,102 @@ mod tests {
         assert!(min_dist < radius * 0.2, "Should have points near center");
         assert!(max_dist > radius * 0.8, "Should have points near edge");
     }
+
+    #[test]
+    fn test_same_seed_same_positions() {
+        let mut a = seeded_rng(42);
+        let mut b = seeded_rng(42);
+
+        for _ in 0..100 {
+            let pa = generate_sphere_position(&mut a, 50.0);
+            let pb = generate_sphere_position(&mut b, 50.0);
+            assert_eq!(pa.x.to_bits(), pb.x.to_bits());
+            assert_eq!(pa.y.to_bits(), pb.y.to_bits());
+            assert_eq!(pa.z.to_bits(), pb.z.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_disk_is_thin_and_truncated() {
+        let mut rng = seeded_rng(3);
+        let mut sum_abs_z = 0.0;
+
+        for _ in 0..2000 {
+            let pos = generate_disk_position(&mut rng, 500.0, 150.0, 10.0);
+            assert!((pos.x.powi(2) + pos.y.powi(2)).sqrt() <= 500.0);
+            sum_abs_z += pos.z.abs();
+        }
+
+        // Mean |z| of a two-sided exponential equals its scale height
+        let mean_abs_z = sum_abs_z / 2000.0;
+        assert!((8.0..=12.0).contains(&mean_abs_z), "Mean |z| was {}", mean_abs_z);
+    }
+
+    #[test]
+    fn test_spiral_arms_concentrate_stars() {
+        // With tight arms and little spread, stars cluster in azimuth at a
+        // fixed radius band instead of spreading uniformly
+        let mut rng = seeded_rng(4);
+        let mut bins = [0usize; 36];
+
+        for _ in 0..5000 {
+            let pos = generate_spiral_position(&mut rng, 1000.0, 2, 15.0, 10.0, 5.0);
+            let r = (pos.x.powi(2) + pos.y.powi(2)).sqrt();
+            if (300.0..400.0).contains(&r) {
+                let angle = pos.y.atan2(pos.x) + PI;
+                bins[((angle / (2.0 * PI) * 36.0) as usize).min(35)] += 1;
+            }
+        }
+
+        let empty = bins.iter().filter(|&&c| c == 0).count();
+        assert!(empty >= 18, "Expected most azimuth bins empty, got {} empty", empty);
+    }
+
+    #[test]
+    fn test_spiral_scatter_stays_in_disc() {
+        // A spread comparable to the radius would push many stars past the
+        // edge without resampling
+        let mut rng = seeded_rng(7);
+
+        for _ in 0..2000 {
+            let pos = generate_spiral_position(&mut rng, 100.0, 3, 20.0, 40.0, 2.0);
+            assert!((pos.x.powi(2) + pos.y.powi(2)).sqrt() <= 100.0 + 1e-9);
+        }
+
+        // A spread far beyond the radius still returns, on the rim
+        let pos = generate_spiral_position(&mut rng, 100.0, 3, 20.0, 1.0e9, 2.0);
+        assert!((pos.x.powi(2) + pos.y.powi(2)).sqrt() <= 100.0 + 1e-9);
+    }
+
+    #[test]
+    fn test_bulge_and_elliptical_truncated_and_flattened() {
+        let mut rng = seeded_rng(5);
+
+        for _ in 0..2000 {
+            let bulge = generate_bulge_position(&mut rng, 200.0, 50.0, 0.5);
+            let r = (bulge.x.powi(2) + bulge.y.powi(2) + (bulge.z / 0.5).powi(2)).sqrt();
+            assert!(r <= 200.0 + 1e-9);
+            assert!(bulge.z.abs() <= 100.0 + 1e-9);
+
+            let ell = generate_elliptical_position(&mut rng, 300.0, 60.0, 1.0);
+            let r = (ell.x.powi(2) + ell.y.powi(2) + ell.z.powi(2)).sqrt();
+            assert!(r <= 300.0 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_elliptical_half_mass_near_effective_radius() {
+        // For an r^1/4 profile the 3D half-mass radius is about 1.33 R_e
+        let mut rng = seeded_rng(6);
+        let mut radii: Vec<f64> = (0..4001)
+            .map(|_| {
+                let p = generate_elliptical_position(&mut rng, 1.0e6, 100.0, 1.0);
+                (p.x.powi(2) + p.y.powi(2) + p.z.powi(2)).sqrt()
+            })
+            .collect();
+        radii.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let median = radii[2000];
+        assert!((110.0..=160.0).contains(&median), "Median radius was {}", median);
+    }
 }
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::models::{Amounts, Belt, BeltType, Planet, PlanetType, Position3D, Resource, Star, StarSystem, SystemMarket};
+use crate::spatial::{shortest_paths, KdTree, TravelGraph};
+
+/// Population of an unclaimed outpost, a faction's colony and its capital,
+/// before habitable worlds multiply it
+const OUTPOST_POPULATION: f64 = 0.1;
+const COLONY_POPULATION: f64 = 1.0;
+const CAPITAL_POPULATION: f64 = 3.0;
+
+/// Stock a system keeps back from trade, in ticks of its own consumption
+const RESERVE_TICKS: f64 = 2.0;
+
+/// Share of every stockpile lost each tick
+const SPOILAGE: f64 = 0.05;
+
+/// Extra cost of a jump between systems of different owners, as a fraction
+/// of its length
+const BORDER_TARIFF: f64 = 0.5;
+
+/// Times sellers and buyers of each resource are matched per tick
+const TRADE_ROUNDS: usize = 4;
+
+/// Nearest sellers each buyer weighs in straight-line trade
+const STRAIGHT_LINE_SELLERS: usize = 8;
+
+/// Amounts below this count as nothing
+const EPSILON: f64 = 1e-9;
+
+/// How goods can move between a project's systems
+pub enum TradeRoutes<'a> {
+    /// Along lanes, passing through the systems in between
+    Lanes(TravelGraph<'a>),
+    /// Directly between any two systems
+    StraightLine(KdTree),
+}
+
+/// Goods moved over one leg during a tick: one lane, or seller to buyer in
+/// straight-line trade
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flow {
+    pub resource: Resource,
+    pub from: usize,
+    pub to: usize,
+    pub volume: f64,
+}
+
+/// Flows and project-wide totals of one tick
+#[derive(Debug, Clone)]
+pub struct TickResult {
+    /// Largest first
+    pub flows: Vec<Flow>,
+    pub produced: f64,
+    pub consumed: f64,
+    pub traded: f64,
+    pub shortage: f64,
+}
+
+/// Production and consumption of a system per tick
+/// Population grows with ownership (outpost, colony or capital) and the best
+/// habitable world. Food comes from habitable worlds with water, ores from
+/// belts and rocky planets scaled by metallicity, energy from starlight and
+/// giant planets, and goods from the population, which consumes all four
+pub fn market_rates(system: &StarSystem, stars: &[&Star], planets: &[&Planet], belts: &[&Belt], capital: bool) -> (Amounts, Amounts) {
+    let habitability = planets.iter().map(|p| p.habitability).fold(0.0, f64::max);
+    let population = match (system.faction_id, capital) {
+        (None, _) => OUTPOST_POPULATION,
+        (Some(_), false) => COLONY_POPULATION,
+        (Some(_), true) => CAPITAL_POPULATION,
+    } * (1.0 + 4.0 * habitability);
+
+    let food: f64 = planets.iter().map(|p| 4.0 * p.habitability * (0.5 + p.profile.water_fraction)).sum::<f64>()
+        + 0.2 * population;
+
+    // Stars of a system share one metallicity
+    let metals = stars.first().map_or(1.0, |s| 10f64.powf(s.metallicity_feh).clamp(0.1, 5.0));
+    let bodies: f64 = belts
+        .iter()
+        .map(|b| match b.belt_type {
+            BeltType::AsteroidBelt => 1.0,
+            BeltType::KuiperBelt => 0.3,
+        })
+        .sum::<f64>()
+        + 0.4 * planets.iter().filter(|p| matches!(p.planet_type, PlanetType::Rocky | PlanetType::SuperEarth)).count() as f64;
+    let ores = metals * (0.2 + bodies);
+
+    let energy: f64 = stars.iter().map(|s| s.luminosity_solar.max(0.0).sqrt().min(5.0)).sum::<f64>()
+        + 0.5 * planets.iter().filter(|p| matches!(p.planet_type, PlanetType::IceGiant | PlanetType::GasGiant)).count() as f64;
+
+    let mut production = [0.0; Resource::COUNT];
+    production[Resource::Food.index()] = food;
+    production[Resource::Ores.index()] = ores;
+    production[Resource::Energy.index()] = energy;
+    production[Resource::Goods.index()] = 0.8 * population;
+
+    let mut consumption = [0.0; Resource::COUNT];
+    consumption[Resource::Food.index()] = population;
+    consumption[Resource::Ores.index()] = 0.5 * population;
+    consumption[Resource::Energy.index()] = 0.5 * population;
+    consumption[Resource::Goods.index()] = 0.6 * population;
+
+    (production, consumption)
+}
+
+/// Advance every market by one tick
+/// Each system adds its production to its stock and meets what it can of its
+/// own consumption. Stock beyond a reserve is then sold to the systems still
+/// short, cheapest route first, where a route costs its length plus a tariff
+/// on every jump between different owners and may not cost more than
+/// `max_route_ly`. Lane trade is carried through the systems on the way,
+/// which all count it as throughput. Leftover stock partly spoils
+pub fn simulate_tick(
+    routes: &TradeRoutes,
+    positions: &[Position3D],
+    owners: &[Option<Uuid>],
+    markets: &mut [SystemMarket],
+    max_route_ly: Option<f64>,
+) -> TickResult {
+    let n = markets.len();
+    let mut need = vec![[0.0; Resource::COUNT]; n];
+    let mut spare = vec![[0.0; Resource::COUNT]; n];
+    for (i, market) in markets.iter_mut().enumerate() {
+        market.imports = [0.0; Resource::COUNT];
+        market.exports = [0.0; Resource::COUNT];
+        market.throughput = 0.0;
+        for r in 0..Resource::COUNT {
+            market.stock[r] += market.production[r];
+            let used = market.stock[r].min(market.consumption[r]);
+            market.stock[r] -= used;
+            need[i][r] = market.consumption[r] - used;
+            spare[i][r] = (market.stock[r] - RESERVE_TICKS * market.consumption[r]).max(0.0);
+        }
+    }
+
+    let jump_cost = |a: usize, b: usize, length: f64| {
+        if owners[a] == owners[b] { length } else { length * (1.0 + BORDER_TARIFF) }
+    };
+    let mut volumes: HashMap<(usize, usize, Resource), f64> = HashMap::new();
+    let mut traded = 0.0;
+
+    for resource in Resource::ALL {
+        let r = resource.index();
+        for _ in 0..TRADE_ROUNDS {
+            let sellers: Vec<usize> = (0..n).filter(|&i| spare[i][r] > EPSILON).collect();
+            let buyers: Vec<usize> = (0..n).filter(|&i| need[i][r] > EPSILON).collect();
+            if sellers.is_empty() || buyers.is_empty() {
+                break;
+            }
+
+            // Each buyer's cheapest seller, and for lanes the path there
+            let paths = match routes {
+                TradeRoutes::Lanes(graph) => Some(shortest_paths(graph, positions, &sellers, max_route_ly, jump_cost)),
+                TradeRoutes::StraightLine(_) => None,
+            };
+            let mut deals: Vec<(f64, usize, usize)> = buyers
+                .iter()
+                .filter_map(|&buyer| match (routes, &paths) {
+                    (_, Some(paths)) => paths[buyer].map(|step| (step.cost, step.source, buyer)),
+                    (TradeRoutes::StraightLine(tree), None) => tree
+                        .nearest_where(&positions[buyer], STRAIGHT_LINE_SELLERS, |j| spare[j][r] > EPSILON)
+                        .into_iter()
+                        .map(|(seller, length)| (jump_cost(seller, buyer, length), seller, buyer))
+                        .filter(|&(cost, _, _)| max_route_ly.is_none_or(|max| cost <= max))
+                        .min_by(|a, b| a.0.total_cmp(&b.0)),
+                    _ => None,
+                })
+                .collect();
+            deals.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.2.cmp(&b.2)));
+
+            let mut moved = false;
+            for (_, seller, buyer) in deals {
+                let volume = need[buyer][r].min(spare[seller][r]);
+                if volume <= EPSILON {
+                    continue;
+                }
+                moved = true;
+                need[buyer][r] -= volume;
+                spare[seller][r] -= volume;
+                markets[seller].stock[r] -= volume;
+                markets[seller].exports[r] += volume;
+                markets[buyer].imports[r] += volume;
+                traded += volume;
+
+                markets[buyer].throughput += volume;
+                match &paths {
+                    Some(paths) => {
+                        let mut system = buyer;
+                        while let Some(previous) = paths[system].and_then(|step| step.previous) {
+                            *volumes.entry((previous, system, resource)).or_insert(0.0) += volume;
+                            markets[previous].throughput += volume;
+                            system = previous;
+                        }
+                    }
+                    None => {
+                        *volumes.entry((seller, buyer, resource)).or_insert(0.0) += volume;
+                        markets[seller].throughput += volume;
+                    }
+                }
+            }
+            if !moved {
+                break;
+            }
+        }
+    }
+
+    for market in markets.iter_mut() {
+        for stock in market.stock.iter_mut() {
+            *stock *= 1.0 - SPOILAGE;
+        }
+    }
+
+    let mut flows: Vec<Flow> = volumes
+        .into_iter()
+        .map(|((from, to, resource), volume)| Flow { resource, from, to, volume })
+        .collect();
+    flows.sort_by(|a, b| {
+        b.volume
+            .total_cmp(&a.volume)
+            .then(a.from.cmp(&b.from))
+            .then(a.to.cmp(&b.to))
+            .then(a.resource.index().cmp(&b.resource.index()))
+    });
+
+    let produced: f64 = markets.iter().flat_map(|m| m.production).sum();
+    let shortage: f64 = need.iter().flatten().sum();
+    TickResult {
+        flows,
+        produced,
+        consumed: markets.iter().flat_map(|m| m.consumption).sum::<f64>() - shortage,
+        traded,
+        shortage,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::sun_like_star;
+    use crate::models::SystemType;
+
+    fn system(x_ly: f64, faction_id: Option<Uuid>) -> StarSystem {
+        StarSystem {
+            id: Some(Uuid::new_v4()),
+            project_id: Uuid::nil(),
+            name: format!("S{:04}", x_ly as i32),
+            system_type: SystemType::Solo,
+            x_ly,
+            y_ly: 0.0,
+            z_ly: 0.0,
+            zone_id: None,
+            faction_id,
+            contested_by: None,
+            border: false,
+        }
+    }
+
+    fn star(luminosity_solar: f64) -> Star {
+        Star { luminosity_solar, ..sun_like_star(1.0) }
+    }
+
+    fn market(production: f64, consumption: f64, resource: Resource) -> SystemMarket {
+        let mut amounts = ([0.0; Resource::COUNT], [0.0; Resource::COUNT]);
+        amounts.0[resource.index()] = production;
+        amounts.1[resource.index()] = consumption;
+        SystemMarket {
+            system_id: Uuid::new_v4(),
+            project_id: Uuid::nil(),
+            production: amounts.0,
+            consumption: amounts.1,
+            stock: [0.0; Resource::COUNT],
+            imports: [0.0; Resource::COUNT],
+            exports: [0.0; Resource::COUNT],
+            throughput: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_rates_follow_stars_and_ownership() {
+        let faction = Some(Uuid::new_v4());
+        let (sun, bright) = (star(1.0), star(16.0));
+
+        let (outpost_made, outpost_used) = market_rates(&system(0.0, None), &[&sun], &[], &[], false);
+        let (_, colony_used) = market_rates(&system(0.0, faction), &[&sun], &[], &[], false);
+        let (_, capital_used) = market_rates(&system(0.0, faction), &[&sun], &[], &[], true);
+        let food = Resource::Food.index();
+        assert!(outpost_used[food] < colony_used[food] && colony_used[food] < capital_used[food]);
+
+        let (bright_made, _) = market_rates(&system(0.0, None), &[&bright], &[], &[], false);
+        let energy = Resource::Energy.index();
+        assert_eq!(outpost_made[energy], 1.0);
+        assert_eq!(bright_made[energy], 4.0);
+    }
+
+    #[test]
+    fn test_trade_flows_to_buyers_through_hubs() {
+        let positions: Vec<Position3D> = (0..4).map(|i| Position3D { x: i as f64 * 2.0, y: 0.0, z: 0.0 }).collect();
+        let owners = vec![None; 4];
+        let food = Resource::Food;
+        let markets = || {
+            vec![market(10.0, 1.0, food), market(0.0, 0.0, food), market(0.0, 0.0, food), market(0.0, 3.0, food)]
+        };
+
+        let lanes = TradeRoutes::Lanes(TravelGraph::from_lanes(4, [(0, 1, 2.0), (1, 2, 2.0), (2, 3, 2.0)]));
+        let mut by_lane = markets();
+        let result = simulate_tick(&lanes, &positions, &owners, &mut by_lane, None);
+        assert_eq!(result.traded, 3.0);
+        assert_eq!(result.shortage, 0.0);
+        assert_eq!(result.flows.len(), 3);
+        assert!(result.flows.iter().all(|f| f.volume == 3.0 && f.to == f.from + 1));
+        assert_eq!(by_lane[1].throughput, 3.0);
+        assert_eq!(by_lane[3].imports[food.index()], 3.0);
+        assert!((by_lane[0].stock[food.index()] - 6.0 * (1.0 - SPOILAGE)).abs() < 1e-9);
+
+        let direct = TradeRoutes::StraightLine(KdTree::new(&positions));
+        let mut by_line = markets();
+        let result = simulate_tick(&direct, &positions, &owners, &mut by_line, None);
+        assert_eq!(result.flows, vec![Flow { resource: food, from: 0, to: 3, volume: 3.0 }]);
+        assert_eq!(by_line[1].throughput, 0.0);
+
+        // Too far to trade at all
+        let mut stranded = markets();
+        let result = simulate_tick(&lanes, &positions, &owners, &mut stranded, Some(5.0));
+        assert_eq!(result.traded, 0.0);
+        assert_eq!(result.shortage, 3.0);
+    }
+}
@@ -0,0 +1,178 @@
+use rand::Rng;
+use crate::models::{StarFormationHistory, StarProperties};
+use super::{
+    black_hole_properties, giant_properties, main_sequence_properties, neutron_star_properties,
+    standard_normal, subgiant_properties, supergiant_properties, white_dwarf_properties,
+};
+
+/// Age of the oldest stars in Gyr; every star formation history starts here
+pub const GALAXY_AGE_GYR: f64 = 13.0;
+
+/// Lightest initial mass that ends as a core-collapse remnant
+const SUPERNOVA_MASS: f64 = 8.0;
+
+/// Lightest initial mass that collapses to a black hole rather than a
+/// neutron star
+const BLACK_HOLE_MASS: f64 = 25.0;
+
+/// Subgiant phase length as a fraction of the main-sequence lifetime
+const SUBGIANT_PHASE: f64 = 0.05;
+
+/// Giant-branch phase length (red giant branch through AGB) as a fraction
+/// of the main-sequence lifetime
+const GIANT_PHASE: f64 = 0.10;
+
+/// Supergiant phase length for massive stars as a fraction of the
+/// main-sequence lifetime
+const SUPERGIANT_PHASE: f64 = 0.10;
+
+/// Main-sequence lifetime in Gyr, t ≈ 10 Gyr (M / M☉)^-2.5
+pub fn main_sequence_lifetime(mass: f64) -> f64 {
+    10.0 * mass.powf(-2.5)
+}
+
+/// White dwarf mass left by a star of the given initial mass
+/// (linear initial-final mass relation, Kalirai et al. 2008)
+pub fn initial_final_mass(initial_mass: f64) -> f64 {
+    (0.109 * initial_mass + 0.394).min(1.35)
+}
+
+/// Properties of a star of `initial_mass` at `age_gyr`
+/// Stars still inside their main-sequence lifetime stay on it; older ones
+/// pass through the subgiant and giant phases (supergiant for massive
+/// stars) and end as white dwarfs, neutron stars or black holes
+pub fn evolve_star<R: Rng + ?Sized>(rng: &mut R, initial_mass: f64, age_gyr: f64) -> StarProperties {
+    let lifetime = main_sequence_lifetime(initial_mass);
+    if age_gyr < lifetime {
+        return main_sequence_properties(initial_mass);
+    }
+
+    let post_ms = (age_gyr - lifetime) / lifetime;
+
+    if initial_mass >= SUPERNOVA_MASS {
+        if post_ms < SUPERGIANT_PHASE {
+            return supergiant_properties(initial_mass, post_ms / SUPERGIANT_PHASE);
+        }
+        if initial_mass >= BLACK_HOLE_MASS {
+            // Most of the envelope is lost before collapse
+            return black_hole_properties((0.3 * initial_mass).max(5.0));
+        }
+        return neutron_star_properties(rng);
+    }
+
+    if post_ms < SUBGIANT_PHASE {
+        return subgiant_properties(initial_mass, post_ms / SUBGIANT_PHASE);
+    }
+    if post_ms < SUBGIANT_PHASE + GIANT_PHASE {
+        return giant_properties(initial_mass, (post_ms - SUBGIANT_PHASE) / GIANT_PHASE);
+    }
+
+    let cooling_age = age_gyr - lifetime * (1.0 + SUBGIANT_PHASE + GIANT_PHASE);
+    white_dwarf_properties(rng, initial_final_mass(initial_mass), cooling_age)
+}
+
+/// Draw a system age in Gyr from a star formation history
+pub fn sample_system_age<R: Rng + ?Sized>(rng: &mut R, history: &StarFormationHistory) -> f64 {
+    match *history {
+        StarFormationHistory::Constant => rng.gen_range(0.0..GALAXY_AGE_GYR),
+        StarFormationHistory::Burst { age_gyr, spread_gyr } => {
+            (age_gyr + spread_gyr * standard_normal(rng)).clamp(0.0, GALAXY_AGE_GYR)
+        },
+        StarFormationHistory::Declining { timescale_gyr } => {
+            // Time since the galaxy formed, from a truncated exponential
+            let u: f64 = rng.gen();
+            let tail = (-GALAXY_AGE_GYR / timescale_gyr).exp();
+            let t = -timescale_gyr * (1.0 - u * (1.0 - tail)).ln();
+            (GALAXY_AGE_GYR - t).clamp(0.0, GALAXY_AGE_GYR)
+        },
+    }
+}
+
+/// Draw [Fe/H] for a system of the given age
+/// Age-metallicity relation of the solar neighbourhood: young stars are
+/// slightly metal-rich, old ones metal-poor, with 0.2 dex of scatter
+pub fn sample_metallicity<R: Rng + ?Sized>(rng: &mut R, age_gyr: f64) -> f64 {
+    (0.1 - 0.05 * age_gyr + 0.2 * standard_normal(rng)).clamp(-3.0, 0.6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{sample_mass, seeded_rng, GalaxyRng};
+    use crate::models::{EvolutionaryStage, Imf};
+
+    #[test]
+    fn test_sun_lifecycle() {
+        let mut rng = seeded_rng(1);
+        assert_eq!(evolve_star(&mut rng, 1.0, 4.6).evolutionary_stage, EvolutionaryStage::MainSequence);
+        assert_eq!(evolve_star(&mut rng, 1.0, 10.2).evolutionary_stage, EvolutionaryStage::Subgiant);
+        assert_eq!(evolve_star(&mut rng, 1.0, 11.0).evolutionary_stage, EvolutionaryStage::Giant);
+
+        let remnant = evolve_star(&mut rng, 1.0, 12.5);
+        assert_eq!(remnant.evolutionary_stage, EvolutionaryStage::WhiteDwarf);
+        assert!((remnant.mass_solar - 0.503).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_massive_star_remnants() {
+        let mut rng = seeded_rng(2);
+        assert_eq!(evolve_star(&mut rng, 15.0, 0.0125).evolutionary_stage, EvolutionaryStage::Supergiant);
+        assert_eq!(evolve_star(&mut rng, 15.0, 1.0).evolutionary_stage, EvolutionaryStage::NeutronStar);
+        assert_eq!(evolve_star(&mut rng, 40.0, 1.0).evolutionary_stage, EvolutionaryStage::BlackHole);
+    }
+
+    fn is_remnant(p: &StarProperties) -> bool {
+        matches!(
+            p.evolutionary_stage,
+            EvolutionaryStage::WhiteDwarf | EvolutionaryStage::NeutronStar | EvolutionaryStage::BlackHole
+        )
+    }
+
+    #[test]
+    fn test_old_population_has_remnants() {
+        let mut rng = seeded_rng(3);
+        let n = 20000;
+        let (mut young_remnants, mut old_remnants) = (0, 0);
+        for _ in 0..n {
+            let mass = sample_mass(&mut rng, Imf::Kroupa);
+            if is_remnant(&evolve_star(&mut rng, mass, 0.05)) {
+                young_remnants += 1;
+            }
+            if is_remnant(&evolve_star(&mut rng, mass, 12.0)) {
+                old_remnants += 1;
+            }
+        }
+        assert!(old_remnants > young_remnants * 5, "{} vs {}", old_remnants, young_remnants);
+        assert!(old_remnants > n / 50);
+    }
+
+    #[test]
+    fn test_star_formation_histories() {
+        let mut rng = seeded_rng(4);
+        let mean = |rng: &mut GalaxyRng, history: StarFormationHistory| {
+            (0..5000).map(|_| sample_system_age(rng, &history)).sum::<f64>() / 5000.0
+        };
+
+        let constant = mean(&mut rng, StarFormationHistory::Constant);
+        assert!((constant - GALAXY_AGE_GYR / 2.0).abs() < 0.3);
+
+        let burst = mean(&mut rng, StarFormationHistory::Burst { age_gyr: 0.05, spread_gyr: 0.01 });
+        assert!(burst < 0.1);
+
+        let declining = mean(&mut rng, StarFormationHistory::Declining { timescale_gyr: 3.0 });
+        assert!(declining > constant + 2.0, "declining mean age {}", declining);
+
+        for _ in 0..1000 {
+            let age = sample_system_age(&mut rng, &StarFormationHistory::Declining { timescale_gyr: 1.0 });
+            assert!((0.0..=GALAXY_AGE_GYR).contains(&age));
+        }
+    }
+
+    #[test]
+    fn test_metallicity_falls_with_age() {
+        let mut rng = seeded_rng(5);
+        let young: f64 = (0..2000).map(|_| sample_metallicity(&mut rng, 1.0)).sum::<f64>() / 2000.0;
+        let old: f64 = (0..2000).map(|_| sample_metallicity(&mut rng, 12.0)).sum::<f64>() / 2000.0;
+        assert!(young > old + 0.3);
+    }
+}
@@ -1,54 +1,156 @@
 use uuid::Uuid;
 use rand::Rng;
-use crate::models::{StarSystem, Star, SystemType};
+use crate::models::{StarSystem, Star, Orbit, Planet, Moon, Belt, Lane, SystemType, DistributionType, DensityZone, Imf, Position3D, StarFormationHistory};
 use super::{
-    generate_cube_position, generate_sphere_position,
-    generate_system_name, generate_star_names,
-    generate_star_properties,
+    generate_position, generate_system_name, generate_star_names, component_letters, star_photometry,
+    sample_initial_mass, star_from_initial_mass, seeded_rng, sample_system_age, sample_metallicity,
+    generate_orbits, sample_mass_ratio, MIN_BROWN_DWARF_MASS, generate_planets, assess_habitability,
+    generate_moons, generate_ring, generate_belts, planet_hosts,
+    generate_zone_position, in_void, zone_system_counts,
+    SeparationGrid,
 };
 
-/// Determine system type based on weighted random
-/// Solo: 74%, Binary: 25%, Trinary: 1%
-pub fn determine_system_type() -> SystemType {
-    let mut rng = rand::thread_rng();
-    let roll = rng.gen_range(0..100);
+/// Attempts at placing a system inside the galaxy and outside every void
+/// before giving up
+const MAX_PLACEMENT_ATTEMPTS: usize = 10_000;
+
+/// Attempts at placing one system at least the minimum separation from the
+/// others before the requested count is declared not to fit
+const MAX_SEPARATION_ATTEMPTS: usize = 2_000;
+
+/// Parameters for one generation run
+#[derive(Debug, Clone)]
+pub struct GenerationParams {
+    pub num_systems: usize,
+    pub distribution: DistributionType,
+    pub zones: Vec<DensityZone>,
+    /// Poisson-disk minimum distance between systems (None = independent placement)
+    pub min_separation_ly: Option<f64>,
+    /// Initial mass function stellar masses are drawn from
+    pub imf: Imf,
+    /// Star formation history system ages are drawn from
+    pub star_formation: StarFormationHistory,
+    pub seed: u64,
+}
+
+/// Everything one generation run produces
+#[derive(Debug, Clone, Default)]
+pub struct GeneratedGalaxy {
+    pub systems: Vec<StarSystem>,
+    pub stars: Vec<Star>,
+    /// Component orbits of binary and higher-order systems
+    pub orbits: Vec<Orbit>,
+    pub planets: Vec<Planet>,
+    pub moons: Vec<Moon>,
+    /// Asteroid and Kuiper belts
+    pub belts: Vec<Belt>,
+    /// Travel lanes, filled in by the lane stage when one is asked for
+    pub lanes: Vec<Lane>,
+}
+
+impl GeneratedGalaxy {
+    /// Move everything from `other` into this galaxy
+    pub fn append(&mut self, mut other: GeneratedGalaxy) {
+        self.systems.append(&mut other.systems);
+        self.stars.append(&mut other.stars);
+        self.orbits.append(&mut other.orbits);
+        self.planets.append(&mut other.planets);
+        self.moons.append(&mut other.moons);
+        self.belts.append(&mut other.belts);
+        self.lanes.append(&mut other.lanes);
+    }
+}
+
+/// Multiplicity by primary mass (Duchêne & Kraus 2013; Moe & Di Stefano 2017)
+/// Each row: upper primary mass in M☉, fraction of systems with at least one
+/// companion, and chance that a multiple gains each further companion
+const MULTIPLICITY: [(f64, f64, f64); 6] = [
+    (0.1, 0.22, 0.05),          // brown dwarfs and very-low-mass stars
+    (0.5, 0.26, 0.10),          // M dwarfs
+    (1.5, 0.44, 0.25),          // solar-type stars
+    (5.0, 0.50, 0.30),          // A and late B stars
+    (16.0, 0.60, 0.40),         // early B stars
+    (f64::INFINITY, 0.80, 0.50), // O stars
+];
+
+/// Determine system type from the primary's initial mass
+/// Massive primaries are far more often multiple, and their multiples more
+/// often hierarchical triples or higher
+pub fn determine_system_type<R: Rng + ?Sized>(rng: &mut R, primary_mass: f64) -> SystemType {
+    let &(_, multiple_fraction, higher_order) = MULTIPLICITY
+        .iter()
+        .find(|(max_mass, _, _)| primary_mass < *max_mass)
+        .unwrap_or(&MULTIPLICITY[MULTIPLICITY.len() - 1]);
+
+    if rng.gen::<f64>() >= multiple_fraction {
+        return SystemType::Solo;
+    }
 
-    if roll < 74 {
-        SystemType::Solo
-    } else if roll < 99 {
-        SystemType::Binary
-    } else {
-        SystemType::Trinary
+    let mut num_stars = 2;
+    while num_stars < SystemType::MAX_STARS && rng.gen::<f64>() < higher_order {
+        num_stars += 1;
     }
+    SystemType::from_num_stars(num_stars)
 }
 
 /// Generate all star systems for a project
+/// Background systems come first, then each density zone's share in zone order.
+/// The same seed and parameters always produce the same systems, stars and names
 pub fn generate_star_systems(
     project_id: Uuid,
-    num_systems: usize,
-    distribution_type: &str,
-    cube_params: Option<(f64, f64, f64)>,
-    sphere_params: Option<f64>,
-) -> Result<(Vec<StarSystem>, Vec<Star>), String> {
-    let mut systems = Vec::new();
-    let mut stars = Vec::new();
-
-    for i in 0..num_systems {
-        // Generate position
-        let position = match distribution_type {
-            "cube" => {
-                let (x, y, z) = cube_params.ok_or("Cube parameters required")?;
-                generate_cube_position(x, y, z)
-            },
-            "sphere" => {
-                let radius = sphere_params.ok_or("Sphere parameters required")?;
-                generate_sphere_position(radius)
-            },
-            _ => return Err("Invalid distribution type".to_string()),
+    params: &GenerationParams,
+) -> Result<GeneratedGalaxy, String> {
+    let num_systems = params.num_systems;
+    let zones = &params.zones;
+    let mut rng = seeded_rng(params.seed);
+    let mut galaxy = GeneratedGalaxy::default();
+    let mut grid = params.min_separation_ly.map(SeparationGrid::new);
+
+    // Which zone (if any) places each system
+    let zone_counts = zone_system_counts(zones, num_systems);
+    let background = num_systems - zone_counts.iter().sum::<usize>().min(num_systems);
+    let placement: Vec<Option<&DensityZone>> = std::iter::repeat_n(None, background)
+        .chain(zones.iter().zip(&zone_counts).flat_map(|(zone, &count)| {
+            std::iter::repeat_n(Some(zone), count)
+        }))
+        .collect();
+
+    for (i, zone) in placement.into_iter().enumerate() {
+        // Generate position, redrawing until it clears the minimum separation
+        let mut attempts = 0;
+        let position = loop {
+            let position = match zone {
+                Some(zone) => generate_zone_system_position(&mut rng, zone, &params.distribution, zones)?,
+                None => generate_background_position(&mut rng, &params.distribution, zones)?,
+            };
+
+            match grid.as_mut() {
+                None => break position,
+                Some(grid) if grid.fits(&position) => {
+                    grid.insert(position);
+                    break position;
+                }
+                Some(_) => {}
+            }
+
+            attempts += 1;
+            if attempts >= MAX_SEPARATION_ATTEMPTS {
+                return Err(format!(
+                    "Could not fit {} star systems {} ly apart: only {} placed. Lower min_separation_ly or num_stars",
+                    num_systems,
+                    params.min_separation_ly.unwrap_or_default(),
+                    i,
+                ));
+            }
         };
 
-        // Determine system type
-        let system_type = determine_system_type();
+        // Companions form together, so they share an age and composition
+        let age_gyr = sample_system_age(&mut rng, &params.star_formation);
+        let metallicity_feh = sample_metallicity(&mut rng, age_gyr);
+
+        // Multiplicity depends on the primary, so it is drawn first
+        let primary_mass = sample_initial_mass(&mut rng, params.imf);
+        let system_type = determine_system_type(&mut rng, primary_mass);
 
         // Generate system name
         let system_name = generate_system_name(i);
@@ -62,6 +164,10 @@ pub fn generate_star_systems(
             x_ly: position.x,
             y_ly: position.y,
             z_ly: position.z,
+            zone_id: zone.and_then(|z| z.id),
+            faction_id: None,
+            contested_by: None,
+            border: false,
         };
 
         let system_id = system.id.unwrap();
@@ -69,87 +175,198 @@ pub fn generate_star_systems(
         // Generate star names for this system
         let star_names = generate_star_names(&system_name, &system_type);
 
-        // Create stars
-        for star_name in star_names {
-            let properties = generate_star_properties();
+        // Companions take a fraction q of the primary's initial mass
+        let mut members: Vec<_> = (0..system_type.num_stars())
+            .map(|index| {
+                let initial_mass = if index == 0 {
+                    primary_mass
+                } else {
+                    (sample_mass_ratio(&mut rng) * primary_mass).max(MIN_BROWN_DWARF_MASS)
+                };
+                star_from_initial_mass(&mut rng, initial_mass, age_gyr)
+            })
+            .collect();
+
+        // Suffixes follow current mass, so "a" is the heaviest star even
+        // when the primary has already shed mass as a remnant
+        members.sort_by(|a, b| b.mass_solar.total_cmp(&a.mass_solar));
+        let first_star = galaxy.stars.len();
 
+        // Create stars
+        let components = component_letters(system_type.num_stars());
+        for ((properties, star_name), component) in members.into_iter().zip(star_names).zip(components) {
             let star = Star {
                 id: Some(Uuid::new_v4()),
                 system_id,
                 project_id,
                 name: star_name,
+                component,
                 spectral_class: properties.spectral_class,
-                spectral_subclass: Some(properties.spectral_subclass),
-                luminosity_class: "V".to_string(), // Main sequence
+                spectral_subclass: properties.spectral_subclass,
+                luminosity_class: properties.luminosity_class,
                 temperature_k: properties.temperature_k,
                 mass_solar: properties.mass_solar,
                 radius_solar: properties.radius_solar,
                 luminosity_solar: properties.luminosity_solar,
+                age_gyr,
+                metallicity_feh,
+                evolutionary_stage: properties.evolutionary_stage,
+                habitable_zone: None,
+                photometry: star_photometry(properties.temperature_k, properties.luminosity_solar),
             };
 
-            stars.push(star);
+            galaxy.stars.push(star);
+        }
+
+        populate_system(&mut rng, &system, first_star, &mut galaxy);
+        galaxy.systems.push(system);
+    }
+
+    Ok(galaxy)
+}
+
+/// Generate everything around a system's stars: companion orbits, planets
+/// in each stable region with their rings and moons, belts, and finally
+/// the habitability of the whole system
+/// The system's stars are `galaxy.stars[first_star..]`
+pub fn populate_system<R: Rng + ?Sized>(rng: &mut R, system: &StarSystem, first_star: usize, galaxy: &mut GeneratedGalaxy) {
+    let first_orbit = galaxy.orbits.len();
+    galaxy.orbits.extend(generate_orbits(rng, system, &galaxy.stars[first_star..]));
+
+    let first_planet = galaxy.planets.len();
+    for host in planet_hosts(system, &galaxy.stars[first_star..], &galaxy.orbits[first_orbit..]) {
+        let mut host_planets = generate_planets(rng, &host);
+        for planet in &mut host_planets {
+            planet.ring = generate_ring(rng, planet);
+            galaxy.moons.extend(generate_moons(rng, planet, host.mass_solar));
+        }
+        if host.barycentre_orbit_id.is_none() {
+            galaxy.belts.extend(generate_belts(rng, &host, &host_planets));
+        }
+        galaxy.planets.extend(host_planets);
+    }
+
+    assess_habitability(
+        &mut galaxy.stars[first_star..],
+        &galaxy.orbits[first_orbit..],
+        &mut galaxy.planets[first_planet..],
+    );
+}
+
+/// Position from the project distribution, kept out of every void zone
+fn generate_background_position<R: Rng + ?Sized>(
+    rng: &mut R,
+    distribution: &DistributionType,
+    zones: &[DensityZone],
+) -> Result<Position3D, String> {
+    for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+        let position = generate_position(rng, distribution);
+        if !in_void(zones, &position) {
+            return Ok(position);
         }
+    }
+
+    Err("Could not place star systems outside the void zones; the voids cover almost the whole volume".to_string())
+}
 
-        systems.push(system);
+/// Position inside a density zone, redrawn while it falls outside the
+/// galaxy or inside a void
+fn generate_zone_system_position<R: Rng + ?Sized>(
+    rng: &mut R,
+    zone: &DensityZone,
+    distribution: &DistributionType,
+    zones: &[DensityZone],
+) -> Result<Position3D, String> {
+    for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+        let position = generate_zone_position(rng, zone);
+        if distribution.contains(&position) && !in_void(zones, &position) {
+            return Ok(position);
+        }
     }
 
-    Ok((systems, stars))
+    Err(format!(
+        "Could not place star systems in zone {}; it lies almost entirely outside the galaxy or inside voids",
+        zone.zone_index
+    ))
 }
 
 /// Get system type counts from generated systems
-pub fn count_system_types(systems: &[StarSystem]) -> (i32, i32, i32) {
+/// Returns (solo, binary, trinary, quadruple and higher)
+pub fn count_system_types(systems: &[StarSystem]) -> (i32, i32, i32, i32) {
     let solo = systems.iter().filter(|s| s.system_type == SystemType::Solo).count() as i32;
     let binary = systems.iter().filter(|s| s.system_type == SystemType::Binary).count() as i32;
     let trinary = systems.iter().filter(|s| s.system_type == SystemType::Trinary).count() as i32;
+    let higher_order = systems.len() as i32 - solo - binary - trinary;
 
-    (solo, binary, trinary)
+    (solo, binary, trinary, higher_order)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::test_support::sphere_params;
+    use crate::models::ZoneType;
+    use crate::generator::minimum_separation;
 
     #[test]
     fn test_system_type_distribution() {
-        let mut solo_count = 0;
-        let mut binary_count = 0;
-        let mut trinary_count = 0;
-
-        // Generate 10,000 systems
-        for _ in 0..10000 {
-            match determine_system_type() {
-                SystemType::Solo => solo_count += 1,
-                SystemType::Binary => binary_count += 1,
-                SystemType::Trinary => trinary_count += 1,
+        let mut rng = seeded_rng(3);
+
+        // Percentages of (multiple, triple or higher, quadruple or higher)
+        let mut fractions = |primary_mass: f64| {
+            let (mut multiple, mut triple, mut quadruple) = (0, 0, 0);
+            for _ in 0..10000 {
+                let num_stars = determine_system_type(&mut rng, primary_mass).num_stars();
+                multiple += (num_stars >= 2) as i32;
+                triple += (num_stars >= 3) as i32;
+                quadruple += (num_stars >= 4) as i32;
             }
-        }
+            (multiple as f64 / 100.0, triple as f64 / 100.0, quadruple as f64 / 100.0)
+        };
+
+        let (m_dwarf, m_dwarf_triple, _) = fractions(0.3);
+        let (solar, solar_triple, solar_quadruple) = fractions(1.0);
+        let (o_star, o_star_triple, o_star_quadruple) = fractions(30.0);
 
-        let total = 10000.0;
-        let solo_pct = (solo_count as f64 / total) * 100.0;
-        let binary_pct = (binary_count as f64 / total) * 100.0;
-        let trinary_pct = (trinary_count as f64 / total) * 100.0;
+        println!("Multiple: M {:.1}%, G {:.1}%, O {:.1}%", m_dwarf, solar, o_star);
 
-        println!("Solo: {:.1}%, Binary: {:.1}%, Trinary: {:.1}%", solo_pct, binary_pct, trinary_pct);
+        assert!((23.0..=29.0).contains(&m_dwarf), "M dwarfs should be around 26% multiple");
+        assert!((41.0..=47.0).contains(&solar), "Solar-type stars should be around 44% multiple");
+        assert!((77.0..=83.0).contains(&o_star), "O stars should be around 80% multiple");
 
-        // Allow ±5% variance
-        assert!(solo_pct >= 69.0 && solo_pct <= 79.0, "Solo systems should be around 74%");
-        assert!(binary_pct >= 20.0 && binary_pct <= 30.0, "Binary systems should be around 25%");
-        assert!(trinary_pct >= 0.0 && trinary_pct <= 6.0, "Trinary systems should be around 1%");
+        // Higher-order multiples rise with mass too
+        assert!(m_dwarf_triple < solar_triple && solar_triple < o_star_triple);
+        assert!((8.0..=14.0).contains(&solar_triple), "Solar-type triples should be around 11%");
+        assert!(solar_quadruple < solar_triple / 2.0);
+        assert!(o_star_quadruple > 10.0);
+    }
+
+    #[test]
+    fn test_companions_ordered_by_mass() {
+        let GeneratedGalaxy { systems, stars, .. } = generate_star_systems(
+            Uuid::new_v4(),
+            &sphere_params(3000, 500.0, 9),
+        ).unwrap();
+
+        assert!(systems.iter().any(|s| s.system_type.num_stars() >= 4), "Expected some quadruple or higher systems");
+
+        for system in &systems {
+            let members: Vec<&Star> = stars.iter().filter(|s| Some(s.system_id) == system.id).collect();
+            assert_eq!(members.len(), system.system_type.num_stars());
+            assert!(members.windows(2).all(|pair| pair[0].mass_solar >= pair[1].mass_solar));
+        }
     }
 
     #[test]
     fn test_generate_star_systems_sphere() {
         let project_id = Uuid::new_v4();
-        let (systems, stars) = generate_star_systems(
+        let GeneratedGalaxy { systems, stars, .. } = generate_star_systems(
             project_id,
-            100,
-            "sphere",
-            None,
-            Some(100.0),
+            &sphere_params(100, 100.0, 1),
         ).unwrap();
 
         assert_eq!(systems.len(), 100);
-        assert!(stars.len() >= 100 && stars.len() <= 300); // Between 1-3 stars per system
+        assert!(stars.len() >= 100 && stars.len() <= 600); // Between 1-6 stars per system
 
         // Check all systems have correct project_id
         for system in &systems {
@@ -165,16 +382,16 @@ mod tests {
     #[test]
     fn test_generate_star_systems_cube() {
         let project_id = Uuid::new_v4();
-        let (systems, stars) = generate_star_systems(
+        let GeneratedGalaxy { systems, stars, .. } = generate_star_systems(
             project_id,
-            50,
-            "cube",
-            Some((100.0, 100.0, 100.0)),
-            None,
+            &GenerationParams {
+                distribution: DistributionType::Cube { size_x: 100.0, size_y: 100.0, size_z: 100.0 },
+                ..sphere_params(50, 100.0, 1)
+            },
         ).unwrap();
 
         assert_eq!(systems.len(), 50);
-        assert!(stars.len() >= 50 && stars.len() <= 150);
+        assert!(stars.len() >= systems.len() && stars.len() <= SystemType::MAX_STARS * systems.len());
 
         // Check positions are within cube bounds
         for system in &systems {
@@ -184,22 +401,225 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_star_systems_disk() {
+        let GeneratedGalaxy { systems, .. } = generate_star_systems(
+            Uuid::new_v4(),
+            &GenerationParams {
+                distribution: DistributionType::Disk { radius: 1000.0, scale_length: 300.0, scale_height: 20.0 },
+                ..sphere_params(200, 100.0, 1)
+            },
+        ).unwrap();
+
+        assert_eq!(systems.len(), 200);
+        for system in &systems {
+            assert!((system.x_ly.powi(2) + system.y_ly.powi(2)).sqrt() <= 1000.0);
+        }
+    }
+
+    #[test]
+    fn test_zones_place_their_share_and_voids_stay_empty() {
+        let project_id = Uuid::new_v4();
+        let zone = |zone_type, x: f64, share| DensityZone {
+            id: Some(Uuid::new_v4()),
+            project_id,
+            zone_index: 0,
+            zone_type,
+            center_x_ly: x,
+            center_y_ly: 0.0,
+            center_z_ly: 0.0,
+            radius_ly: 20.0,
+            star_share: share,
+            gradient_axis: None,
+            gradient_strength: None,
+        };
+        let zones = vec![
+            zone(ZoneType::OpenCluster, 50.0, 0.25),
+            zone(ZoneType::Void, -50.0, 0.0),
+        ];
+
+        let GeneratedGalaxy { systems, .. } = generate_star_systems(
+            project_id,
+            &GenerationParams {
+                zones: zones.clone(),
+                ..sphere_params(400, 100.0, 9)
+            },
+        ).unwrap();
+
+        assert_eq!(systems.len(), 400);
+        let cluster: Vec<_> = systems.iter().filter(|s| s.zone_id == zones[0].id).collect();
+        assert_eq!(cluster.len(), 100);
+        for system in &systems {
+            let to_void = ((system.x_ly + 50.0).powi(2) + system.y_ly.powi(2) + system.z_ly.powi(2)).sqrt();
+            assert!(to_void > 20.0, "{} is inside the void", system.name);
+            assert_ne!(system.zone_id, zones[1].id);
+        }
+    }
+
+    #[test]
+    fn test_zone_systems_stay_in_galaxy_and_out_of_voids() {
+        // A cluster straddling the sphere's edge, half covered by a void
+        let project_id = Uuid::new_v4();
+        let zone = |zone_type, x: f64, share| DensityZone {
+            id: Some(Uuid::new_v4()),
+            project_id,
+            zone_index: 0,
+            zone_type,
+            center_x_ly: x,
+            center_y_ly: 0.0,
+            center_z_ly: 0.0,
+            radius_ly: 30.0,
+            star_share: share,
+            gradient_axis: None,
+            gradient_strength: None,
+        };
+        let zones = vec![
+            zone(ZoneType::OpenCluster, 95.0, 0.5),
+            zone(ZoneType::Void, 75.0, 0.0),
+        ];
+
+        let GeneratedGalaxy { systems, .. } = generate_star_systems(
+            project_id,
+            &GenerationParams {
+                zones: zones.clone(),
+                ..sphere_params(200, 100.0, 10)
+            },
+        ).unwrap();
+
+        assert_eq!(systems.iter().filter(|s| s.zone_id == zones[0].id).count(), 100);
+        for system in &systems {
+            assert!((system.x_ly.powi(2) + system.y_ly.powi(2) + system.z_ly.powi(2)).sqrt() <= 100.0);
+            let to_void = ((system.x_ly - 75.0).powi(2) + system.y_ly.powi(2) + system.z_ly.powi(2)).sqrt();
+            assert!(to_void > 30.0, "{} is inside the void", system.name);
+        }
+    }
+
+    #[test]
+    fn test_min_separation_is_respected() {
+        let GeneratedGalaxy { systems, .. } = generate_star_systems(
+            Uuid::new_v4(),
+            &GenerationParams {
+                distribution: DistributionType::Disk { radius: 300.0, scale_length: 100.0, scale_height: 10.0 },
+                min_separation_ly: Some(5.0),
+                ..sphere_params(500, 100.0, 11)
+            },
+        ).unwrap();
+
+        let positions: Vec<Position3D> = systems
+            .iter()
+            .map(|s| Position3D { x: s.x_ly, y: s.y_ly, z: s.z_ly })
+            .collect();
+        assert!(minimum_separation(&positions).unwrap() >= 5.0);
+    }
+
+    #[test]
+    fn test_min_separation_reports_overfull_volume() {
+        let result = generate_star_systems(
+            Uuid::new_v4(),
+            &GenerationParams {
+                min_separation_ly: Some(5.0),
+                ..sphere_params(200, 10.0, 11)
+            },
+        );
+
+        let err = result.unwrap_err();
+        assert!(err.contains("Could not fit 200 star systems"), "{}", err);
+    }
+
     #[test]
     fn test_count_system_types() {
         let project_id = Uuid::new_v4();
-        let (systems, _) = generate_star_systems(
+        let GeneratedGalaxy { systems, .. } = generate_star_systems(
             project_id,
-            100,
-            "sphere",
-            None,
-            Some(100.0),
+            &sphere_params(100, 100.0, 1),
         ).unwrap();
 
-        let (solo, binary, trinary) = count_system_types(&systems);
+        let (solo, binary, trinary, higher_order) = count_system_types(&systems);
+
+        assert_eq!(solo + binary + trinary + higher_order, 100);
+        assert!((55..=85).contains(&solo)); // Most primaries are single M dwarfs
+        assert!((10..=40).contains(&binary));
+        assert!((0..=15).contains(&trinary));
+        assert!((0..=5).contains(&higher_order));
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_galaxy() {
+        let params = sphere_params(200, 250.0, 1234);
+        let a = generate_star_systems(Uuid::new_v4(), &params).unwrap();
+        let b = generate_star_systems(Uuid::new_v4(), &params).unwrap();
+
+        assert_eq!(a.systems.len(), b.systems.len());
+        for (a, b) in a.systems.iter().zip(&b.systems) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.system_type, b.system_type);
+            assert_eq!(a.x_ly.to_bits(), b.x_ly.to_bits());
+            assert_eq!(a.y_ly.to_bits(), b.y_ly.to_bits());
+            assert_eq!(a.z_ly.to_bits(), b.z_ly.to_bits());
+        }
+
+        assert_eq!(a.stars.len(), b.stars.len());
+        for (a, b) in a.stars.iter().zip(&b.stars) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.spectral_class, b.spectral_class);
+            assert_eq!(a.spectral_subclass, b.spectral_subclass);
+            assert_eq!(a.luminosity_class, b.luminosity_class);
+            assert_eq!(a.evolutionary_stage, b.evolutionary_stage);
+            assert_eq!(a.age_gyr.to_bits(), b.age_gyr.to_bits());
+            assert_eq!(a.temperature_k.to_bits(), b.temperature_k.to_bits());
+            assert_eq!(a.mass_solar.to_bits(), b.mass_solar.to_bits());
+            assert_eq!(a.radius_solar.to_bits(), b.radius_solar.to_bits());
+            assert_eq!(a.luminosity_solar.to_bits(), b.luminosity_solar.to_bits());
+        }
+
+        assert_eq!(a.planets.len(), b.planets.len());
+        for (a, b) in a.planets.iter().zip(&b.planets) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.semi_major_axis_au, b.semi_major_axis_au);
+            assert_eq!(a.ring, b.ring);
+        }
+        assert_eq!(a.moons.len(), b.moons.len());
+        for (a, b) in a.moons.iter().zip(&b.moons) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.semi_major_axis_km.to_bits(), b.semi_major_axis_km.to_bits());
+        }
+        assert_eq!(a.belts.len(), b.belts.len());
+        for (a, b) in a.belts.iter().zip(&b.belts) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.inner_radius_au.to_bits(), b.inner_radius_au.to_bits());
+        }
+        assert_eq!(a.orbits.len(), b.orbits.len());
+        for (a, b) in a.orbits.iter().zip(&b.orbits) {
+            assert_eq!(a.semi_major_axis_au.to_bits(), b.semi_major_axis_au.to_bits());
+            assert_eq!(a.eccentricity.to_bits(), b.eccentricity.to_bits());
+            assert_eq!(a.inclination_deg.to_bits(), b.inclination_deg.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_companions_share_age_and_metallicity() {
+        let params = GenerationParams {
+            star_formation: StarFormationHistory::Burst { age_gyr: 2.0, spread_gyr: 0.5 },
+            ..sphere_params(300, 250.0, 77)
+        };
+        let GeneratedGalaxy { systems, stars, .. } = generate_star_systems(Uuid::new_v4(), &params).unwrap();
+
+        for system in &systems {
+            let members: Vec<&Star> = stars.iter().filter(|s| Some(s.system_id) == system.id).collect();
+            assert!(members.iter().all(|s| s.age_gyr == members[0].age_gyr));
+            assert!(members.iter().all(|s| s.metallicity_feh == members[0].metallicity_feh));
+        }
+        let mean_age = stars.iter().map(|s| s.age_gyr).sum::<f64>() / stars.len() as f64;
+        assert!((mean_age - 2.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_different_seeds_differ() {
+        let mut params = sphere_params(50, 250.0, 1);
+        let systems_a = generate_star_systems(Uuid::new_v4(), &params).unwrap().systems;
+        params.seed = 2;
+        let systems_b = generate_star_systems(Uuid::new_v4(), &params).unwrap().systems;
 
-        assert_eq!(solo + binary + trinary, 100);
-        assert!(solo >= 60 && solo <= 85); // Roughly 74%
-        assert!(binary >= 15 && binary <= 35); // Roughly 25%
-        assert!(trinary >= 0 && trinary <= 10); // Roughly 1%
+        assert!(systems_a.iter().zip(&systems_b).any(|(a, b)| a.x_ly != b.x_ly));
     }
 }
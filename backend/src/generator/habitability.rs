@@ -0,0 +1,353 @@
+use super::world_profile;
+use crate::models::{EvolutionaryStage, HabitableZone, Orbit, Planet, PlanetType, SpectralClass, Star};
+
+/// Effective-flux polynomials of Kopparapu et al. (2014) for a 1 M⊕
+/// planet: Seff = S☉ + aT + bT² + cT³ + dT⁴ with T = Teff - 5780 K
+const RECENT_VENUS: [f64; 5] = [1.776, 2.136e-4, 2.533e-8, -1.332e-11, -3.097e-15];
+const RUNAWAY_GREENHOUSE: [f64; 5] = [1.107, 1.332e-4, 1.580e-8, -8.308e-12, -1.931e-15];
+const MAXIMUM_GREENHOUSE: [f64; 5] = [0.356, 6.171e-5, 1.698e-9, -3.198e-12, -5.575e-16];
+const EARLY_MARS: [f64; 5] = [0.320, 5.547e-5, 1.526e-9, -2.874e-12, -5.011e-16];
+
+/// Temperature range the polynomials were fitted over
+const MIN_FIT_TEMPERATURE_K: f64 = 2600.0;
+const MAX_FIT_TEMPERATURE_K: f64 = 7200.0;
+
+/// Hosts younger than this are still bombarding and outgassing their planets
+const YOUNG_SYSTEM_GYR: f64 = 0.5;
+
+/// Flux, relative to Earth's, at which a habitable-zone boundary lies for
+/// a star of this temperature
+fn effective_flux(coefficients: &[f64; 5], temperature_k: f64) -> f64 {
+    let t = temperature_k.clamp(MIN_FIT_TEMPERATURE_K, MAX_FIT_TEMPERATURE_K) - 5780.0;
+    coefficients[0] + t * (coefficients[1] + t * (coefficients[2] + t * (coefficients[3] + t * coefficients[4])))
+}
+
+/// Flux, relative to Earth's, above which a planet around a star of this
+/// temperature falls into a runaway greenhouse
+pub fn runaway_greenhouse_flux(temperature_k: f64) -> f64 {
+    effective_flux(&RUNAWAY_GREENHOUSE, temperature_k)
+}
+
+/// Habitable zone of a star, including light from its companions
+/// `companions` pairs each other star with its distance in AU. A planet
+/// sits on a boundary where Σ L / (Seff d²) = 1 over every star, so the
+/// companions' share shifts the host's boundaries outwards. None for dark
+/// remnants, or when a companion alone is bright enough to dominate
+pub fn habitable_zone(star: &Star, companions: &[(&Star, f64)]) -> Option<HabitableZone> {
+    circumbinary_habitable_zone(&[star], companions)
+}
+
+/// Habitable zone around the barycentre of a close pair or group
+/// The enclosed stars sit at the planet's distance, so their light adds
+/// directly; stars outside the group count as companions
+pub fn circumbinary_habitable_zone(members: &[&Star], companions: &[(&Star, f64)]) -> Option<HabitableZone> {
+    if members.iter().all(|s| s.luminosity_solar <= 0.0) {
+        return None;
+    }
+
+    let boundary = |coefficients: &[f64; 5]| {
+        let companion_share: f64 = companions
+            .iter()
+            .filter(|(c, _)| c.luminosity_solar > 0.0)
+            .map(|(c, d)| c.luminosity_solar / (effective_flux(coefficients, c.temperature_k) * d * d))
+            .sum();
+        let member_flux: f64 = members
+            .iter()
+            .filter(|s| s.luminosity_solar > 0.0)
+            .map(|s| s.luminosity_solar / effective_flux(coefficients, s.temperature_k))
+            .sum();
+        (companion_share < 1.0).then(|| (member_flux / (1.0 - companion_share)).sqrt())
+    };
+
+    Some(HabitableZone {
+        optimistic_inner_au: boundary(&RECENT_VENUS)?,
+        conservative_inner_au: boundary(&RUNAWAY_GREENHOUSE)?,
+        conservative_outer_au: boundary(&MAXIMUM_GREENHOUSE)?,
+        optimistic_outer_au: boundary(&EARLY_MARS)?,
+    })
+}
+
+/// Every other star of a system with its separation from `star` in AU,
+/// taken from the orbit that links the two
+pub fn companion_separations<'a>(star: &Star, stars: &'a [Star], orbits: &[Orbit]) -> Vec<(&'a Star, f64)> {
+    let own = star.component.as_str();
+
+    stars
+        .iter()
+        .filter(|other| other.id != star.id)
+        .filter_map(|other| {
+            let theirs = other.component.as_str();
+            orbits
+                .iter()
+                .find(|o| {
+                    (o.primary_component.contains(own) && o.secondary_component == theirs)
+                        || (o.primary_component.contains(theirs) && o.secondary_component == own)
+                })
+                .map(|o| (other, o.semi_major_axis_au))
+        })
+        .collect()
+}
+
+/// Orbit-averaged flux on a planet relative to Earth's
+/// The hosts' flux averages to L / (a² √(1 - e²)) over an eccentric orbit;
+/// companions are treated as sitting at their orbital separation
+pub fn insolation(hosts: &[&Star], companions: &[(&Star, f64)], semi_major_axis_au: f64, eccentricity: f64) -> f64 {
+    let luminosity: f64 = hosts.iter().map(|s| s.luminosity_solar).sum();
+    let host_flux = luminosity / (semi_major_axis_au.powi(2) * (1.0 - eccentricity.powi(2)).sqrt());
+    let companion_flux: f64 = companions.iter().map(|(c, d)| c.luminosity_solar / (d * d)).sum();
+    host_flux + companion_flux
+}
+
+/// Habitability score of a planet, 0-1
+/// Product of where the planet sits in the habitable zone around its host
+/// star or barycentre (1 at the centre, 0.5 at the conservative edges, 0
+/// beyond the optimistic ones), whether it is a terrestrial world, and how
+/// stable and long-lived the host is
+pub fn habitability_score(planet: &Planet, host: &Star, zone: Option<HabitableZone>) -> f64 {
+    let Some(zone) = zone else {
+        return 0.0;
+    };
+
+    // Distance with the same mean flux as the eccentric orbit
+    let a = planet.semi_major_axis_au * (1.0 - planet.eccentricity.powi(2)).powf(0.25);
+    let log_fraction = |x: f64, lo: f64, hi: f64| (x / lo).ln() / (hi / lo).ln();
+    let zone_score = if a < zone.optimistic_inner_au || a > zone.optimistic_outer_au {
+        0.0
+    } else if a < zone.conservative_inner_au {
+        0.5 * log_fraction(a, zone.optimistic_inner_au, zone.conservative_inner_au)
+    } else if a > zone.conservative_outer_au {
+        0.5 * (1.0 - log_fraction(a, zone.conservative_outer_au, zone.optimistic_outer_au))
+    } else {
+        let t = log_fraction(a, zone.conservative_inner_au, zone.conservative_outer_au);
+        1.0 - 0.5 * (2.0 * t - 1.0).abs()
+    };
+
+    let planet_score = match planet.planet_type {
+        // Mars-sized and smaller worlds lose their atmospheres
+        PlanetType::Rocky if planet.mass_earth < 0.1 => 0.3,
+        PlanetType::Rocky => 1.0,
+        // Heavier super-Earths are likely volatile-rich mini-Neptunes
+        PlanetType::SuperEarth if planet.mass_earth <= 5.0 => 0.7,
+        PlanetType::SuperEarth => 0.3,
+        PlanetType::IceGiant | PlanetType::GasGiant => 0.0,
+    };
+
+    let host_score = match host.evolutionary_stage {
+        EvolutionaryStage::MainSequence => match host.spectral_class {
+            SpectralClass::G | SpectralClass::K => 1.0,
+            SpectralClass::F => 0.8,
+            // Flares and tidal locking
+            SpectralClass::M => 0.7,
+            SpectralClass::A => 0.4,
+            _ => 0.1,
+        },
+        EvolutionaryStage::Subgiant => 0.5,
+        EvolutionaryStage::BrownDwarf | EvolutionaryStage::WhiteDwarf => 0.3,
+        _ => 0.2,
+    };
+    let age_score = if host.age_gyr < YOUNG_SYSTEM_GYR { 0.5 } else { 1.0 };
+
+    zone_score * planet_score * host_score * age_score
+}
+
+/// Fill in the habitable zones of one system's stars and the insolation,
+/// habitability and world profile of its planets
+pub fn assess_habitability(stars: &mut [Star], orbits: &[Orbit], planets: &mut [Planet]) {
+    let zones: Vec<Option<HabitableZone>> = stars
+        .iter()
+        .map(|star| habitable_zone(star, &companion_separations(star, stars, orbits)))
+        .collect();
+    for (star, zone) in stars.iter_mut().zip(zones) {
+        star.habitable_zone = zone;
+    }
+
+    for planet in planets {
+        let Some(light) = host_light(planet, stars, orbits) else {
+            continue;
+        };
+
+        planet.insolation_earth = insolation(&light.members, &light.companions, planet.semi_major_axis_au, planet.eccentricity);
+        planet.habitability = habitability_score(planet, light.host, light.zone);
+        planet.profile = world_profile(planet, light.host);
+    }
+}
+
+/// Habitable zone a planet sits in: its host star's, or for a circumbinary
+/// planet the zone around the barycentre from the enclosed stars' combined
+/// light. `stars` and `orbits` belong to the planet's system; None if the
+/// host is not among them
+pub fn host_habitable_zone(planet: &Planet, stars: &[Star], orbits: &[Orbit]) -> Option<HabitableZone> {
+    host_light(planet, stars, orbits)?.zone
+}
+
+/// Light reaching a planet: the stars it orbits, the system's other stars
+/// with their distances, and the habitable zone around its host
+struct HostLight<'a> {
+    host: &'a Star,
+    members: Vec<&'a Star>,
+    companions: Vec<(&'a Star, f64)>,
+    zone: Option<HabitableZone>,
+}
+
+fn host_light<'a>(planet: &Planet, stars: &'a [Star], orbits: &[Orbit]) -> Option<HostLight<'a>> {
+    let host = stars.iter().find(|s| s.id == Some(planet.star_id))?;
+    let barycentre = planet.barycentre_orbit_id.and_then(|id| orbits.iter().find(|o| o.id == Some(id)));
+    let light = match barycentre {
+        Some(orbit) => {
+            let enclosed = format!("{}{}", orbit.primary_component, orbit.secondary_component);
+            let members: Vec<&Star> = stars
+                .iter()
+                .filter(|s| enclosed.contains(s.component.as_str()))
+                .collect();
+            let companions: Vec<(&Star, f64)> = companion_separations(host, stars, orbits)
+                .into_iter()
+                .filter(|(c, _)| !members.iter().any(|m| m.id == c.id))
+                .collect();
+            let zone = circumbinary_habitable_zone(&members, &companions);
+            HostLight { host, members, companions, zone }
+        },
+        None => HostLight {
+            host,
+            members: vec![host],
+            companions: companion_separations(host, stars, orbits),
+            zone: host.habitable_zone,
+        },
+    };
+    Some(light)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::{sphere_params, sun_like_star};
+    use crate::generator::{generate_star_systems, GeneratedGalaxy};
+    use crate::models::WorldProfile;
+    use uuid::Uuid;
+
+    fn star(name: &str, mass: f64) -> Star {
+        Star { name: name.to_string(), ..sun_like_star(mass) }
+    }
+
+    fn planet(host: &Star, planet_type: PlanetType, mass_earth: f64, semi_major_axis_au: f64) -> Planet {
+        Planet {
+            id: Some(Uuid::new_v4()),
+            star_id: host.id.unwrap(),
+            system_id: Uuid::nil(),
+            project_id: Uuid::nil(),
+            name: String::new(),
+            orbit_index: 1,
+            barycentre_orbit_id: None,
+            planet_type,
+            semi_major_axis_au,
+            eccentricity: 0.0,
+            period_years: 1.0,
+            mass_earth,
+            radius_earth: 1.0,
+            ring: None,
+            profile: WorldProfile::default(),
+            insolation_earth: 0.0,
+            habitability: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_solar_habitable_zone() {
+        let mut sun = star("S0001", 1.0);
+        sun.luminosity_solar = 1.0;
+        sun.temperature_k = 5780.0;
+
+        let zone = habitable_zone(&sun, &[]).unwrap();
+        assert!((zone.conservative_inner_au - 0.95).abs() < 0.01);
+        assert!((zone.conservative_outer_au - 1.68).abs() < 0.01);
+        assert!((zone.optimistic_inner_au - 0.75).abs() < 0.01);
+        assert!((zone.optimistic_outer_au - 1.77).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_cool_stars_have_closer_zones() {
+        let sun = habitable_zone(&star("S0001", 1.0), &[]).unwrap();
+        let m_dwarf = habitable_zone(&star("S0002", 0.3), &[]).unwrap();
+        assert!(m_dwarf.conservative_outer_au < sun.conservative_inner_au / 3.0);
+    }
+
+    #[test]
+    fn test_companion_pushes_zone_outwards() {
+        let a = star("S0001a", 1.0);
+        let b = star("S0001b", 1.0);
+        let alone = habitable_zone(&a, &[]).unwrap();
+        let with_b = habitable_zone(&a, &[(&b, 5.0)]).unwrap();
+        assert!(with_b.conservative_inner_au > alone.conservative_inner_au);
+        assert!(with_b.conservative_outer_au > alone.conservative_outer_au);
+
+        // A companion closer than its own habitable zone leaves none
+        assert!(habitable_zone(&a, &[(&b, 0.5)]).is_none());
+    }
+
+    #[test]
+    fn test_circumbinary_zone() {
+        // Twin suns at the centre double the light: boundaries move out by √2
+        let a = star("S0001a", 1.0);
+        let b = star("S0001b", 1.0);
+        let single = habitable_zone(&a, &[]).unwrap();
+        let twin = circumbinary_habitable_zone(&[&a, &b], &[]).unwrap();
+        assert!((twin.conservative_inner_au / single.conservative_inner_au - 2f64.sqrt()).abs() < 1e-9);
+        assert!((twin.optimistic_outer_au / single.optimistic_outer_au - 2f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_habitability_score() {
+        let mut sun = star("S0001", 1.0);
+        sun.habitable_zone = habitable_zone(&sun, &[]);
+        let zone = sun.habitable_zone.unwrap();
+        let centre = (zone.conservative_inner_au * zone.conservative_outer_au).sqrt();
+
+        let score = |planet_type, mass_earth, a| habitability_score(&planet(&sun, planet_type, mass_earth, a), &sun, sun.habitable_zone);
+
+        assert!((score(PlanetType::Rocky, 1.0, centre) - 1.0).abs() < 1e-9);
+        assert!((score(PlanetType::Rocky, 1.0, zone.conservative_outer_au) - 0.5).abs() < 1e-9);
+        assert_eq!(score(PlanetType::GasGiant, 300.0, centre), 0.0);
+        assert_eq!(score(PlanetType::Rocky, 1.0, 0.1), 0.0);
+        assert_eq!(score(PlanetType::Rocky, 1.0, 5.0), 0.0);
+    }
+
+    #[test]
+    fn test_generated_galaxy_has_habitable_worlds() {
+        let params = sphere_params(2000, 500.0, 11);
+        let GeneratedGalaxy { stars, planets, .. } = generate_star_systems(Uuid::new_v4(), &params).unwrap();
+
+        for star in &stars {
+            if star.luminosity_solar == 0.0 {
+                assert!(star.habitable_zone.is_none());
+            }
+        }
+
+        let habitable: Vec<&Planet> = planets.iter().filter(|p| p.habitability > 0.0).collect();
+        assert!(!habitable.is_empty());
+        for planet in &habitable {
+            assert!(matches!(planet.planet_type, PlanetType::Rocky | PlanetType::SuperEarth));
+            assert!(planet.habitability <= 1.0);
+            // Habitable-zone insolation lies between early Mars and recent Venus
+            assert!((0.1..3.0).contains(&planet.insolation_earth), "insolation {}", planet.insolation_earth);
+        }
+    }
+
+    #[test]
+    fn test_circumbinary_planets_report_barycentre_zone() {
+        let GeneratedGalaxy { stars, orbits, planets, .. } =
+            generate_star_systems(Uuid::new_v4(), &sphere_params(2000, 500.0, 12)).unwrap();
+
+        let mut checked = 0;
+        for planet in planets.iter().filter(|p| p.barycentre_orbit_id.is_some() && p.habitability > 0.0) {
+            let members: Vec<Star> = stars.iter().filter(|s| s.system_id == planet.system_id).cloned().collect();
+            let links: Vec<Orbit> = orbits.iter().filter(|o| o.system_id == planet.system_id).cloned().collect();
+            let host = stars.iter().find(|s| s.id == Some(planet.star_id)).unwrap();
+            let zone = host_habitable_zone(planet, &members, &links);
+
+            // The score was computed from the barycentre's zone, not the star's
+            assert_eq!(habitability_score(planet, host, zone), planet.habitability);
+            assert_ne!(zone, host.habitable_zone);
+            checked += 1;
+        }
+        assert!(checked > 0, "Expected habitable circumbinary planets");
+    }
+}
@@ -0,0 +1,90 @@
+use rand::Rng;
+use crate::models::Imf;
+
+/// Hydrogen-burning limit; lighter objects are not main-sequence stars
+pub const MIN_STELLAR_MASS: f64 = 0.08;
+
+/// Most massive star the generator will produce
+pub const MAX_STELLAR_MASS: f64 = 100.0;
+
+impl Imf {
+    /// Relative number of stars per unit log10(mass), dN/dlog m
+    pub fn log_density(&self, mass: f64) -> f64 {
+        match self {
+            Imf::Salpeter => mass.powf(-1.35),
+            Imf::Kroupa => {
+                if mass < 0.5 {
+                    mass.powf(-0.3)
+                } else {
+                    // Continuous with the low-mass segment at 0.5 M☉
+                    0.5 * mass.powf(-1.3)
+                }
+            },
+            Imf::Chabrier => {
+                if mass <= 1.0 {
+                    let x = mass.log10() - 0.079f64.log10();
+                    0.158 * (-(x * x) / (2.0 * 0.69 * 0.69)).exp()
+                } else {
+                    0.0443 * mass.powf(-1.3)
+                }
+            },
+        }
+    }
+}
+
+/// Sample a stellar mass in solar masses from an initial mass function
+/// Rejection sampling in log-mass space; every supported IMF falls
+/// monotonically across the stellar range, so the lower mass limit bounds
+/// the density
+pub fn sample_mass<R: Rng + ?Sized>(rng: &mut R, imf: Imf) -> f64 {
+    let log_min = MIN_STELLAR_MASS.log10();
+    let log_max = MAX_STELLAR_MASS.log10();
+    let peak = imf.log_density(MIN_STELLAR_MASS);
+
+    loop {
+        let mass = 10f64.powf(rng.gen_range(log_min..log_max));
+        if rng.gen::<f64>() * peak <= imf.log_density(mass) {
+            return mass;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::seeded_rng;
+
+    fn fraction_above(imf: Imf, mass: f64) -> f64 {
+        let mut rng = seeded_rng(5);
+        let n = 20000;
+        let above = (0..n).filter(|_| sample_mass(&mut rng, imf) > mass).count();
+        above as f64 / n as f64
+    }
+
+    #[test]
+    fn test_masses_within_stellar_range() {
+        let mut rng = seeded_rng(1);
+        for imf in [Imf::Salpeter, Imf::Kroupa, Imf::Chabrier] {
+            for _ in 0..2000 {
+                let m = sample_mass(&mut rng, imf);
+                assert!((MIN_STELLAR_MASS..=MAX_STELLAR_MASS).contains(&m));
+            }
+        }
+    }
+
+    #[test]
+    fn test_salpeter_high_mass_fraction() {
+        // ∫1..100 m^-2.35 / ∫0.08..100 m^-2.35 ≈ 3.3%
+        let f = fraction_above(Imf::Salpeter, 1.0);
+        assert!((0.025..=0.045).contains(&f), "Salpeter fraction above 1 M☉ was {}", f);
+    }
+
+    #[test]
+    fn test_flatter_imfs_have_more_massive_stars() {
+        // Kroupa and Chabrier turn over at low mass, so a larger share of
+        // their stars sit above a solar mass than for Salpeter
+        let salpeter = fraction_above(Imf::Salpeter, 1.0);
+        assert!(fraction_above(Imf::Kroupa, 1.0) > salpeter);
+        assert!(fraction_above(Imf::Chabrier, 1.0) > salpeter);
+    }
+}
@@ -0,0 +1,303 @@
+use rand::Rng;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use uuid::Uuid;
+use crate::models::{Lane, LaneRule, Position3D, StarSystem};
+use crate::spatial::KdTree;
+use super::seeded_rng;
+
+/// Nearest neighbours checked as Gabriel or relative-neighbourhood partners;
+/// their edges join systems well inside this in practice
+const CANDIDATE_NEIGHBOURS: usize = 24;
+
+/// Most lanes per system a max-jump range may produce on average
+const MAX_MEAN_LANES: usize = 50;
+
+/// How a project's systems are joined by lanes
+#[derive(Debug, Clone, Copy)]
+pub struct LaneParams {
+    pub rule: LaneRule,
+    /// Split the map into this many regions joined only by chokepoint lanes
+    pub chokepoint_regions: Option<usize>,
+}
+
+/// Link systems into a lane network
+/// Lanes follow `params.rule`. With chokepoint regions the systems are split
+/// around far-apart seed systems and only the shortest lane between each
+/// pair of touching regions is kept. Whatever is still disconnected is then
+/// joined by the shortest lane out of the smallest component, so every
+/// system can reach every other. The same seed gives the same network
+pub fn generate_lanes(project_id: Uuid, systems: &[StarSystem], params: &LaneParams, seed: u64) -> Result<Vec<Lane>, String> {
+    let positions: Vec<Position3D> = systems.iter().map(|s| Position3D { x: s.x_ly, y: s.y_ly, z: s.z_ly }).collect();
+    let tree = KdTree::new(&positions);
+    let n = positions.len();
+
+    let mut pairs: BTreeSet<(usize, usize)> = BTreeSet::new();
+    match params.rule {
+        LaneRule::MaxJump { range_ly } => {
+            for (i, position) in positions.iter().enumerate() {
+                pairs.extend(tree.within_radius(position, range_ly).into_iter().filter(|&(j, _)| j > i).map(|(j, _)| (i, j)));
+                if pairs.len() > n * MAX_MEAN_LANES {
+                    return Err(format!(
+                        "range_ly {} links more than {} lanes per system; use a shorter range",
+                        range_ly, MAX_MEAN_LANES
+                    ));
+                }
+            }
+        }
+        LaneRule::KNearest { k } => {
+            pairs = neighbour_pairs(&tree, &positions, k);
+        }
+        LaneRule::Gabriel => {
+            pairs = neighbour_pairs(&tree, &positions, CANDIDATE_NEIGHBOURS);
+            pairs.retain(|&(a, b)| is_gabriel(&tree, &positions, a, b));
+        }
+        LaneRule::RelativeNeighbourhood => {
+            pairs = neighbour_pairs(&tree, &positions, CANDIDATE_NEIGHBOURS);
+            pairs.retain(|&(a, b)| is_relative_neighbour(&tree, &positions, a, b));
+        }
+    }
+
+    let mut rng = seeded_rng(seed);
+    let regions = params.chokepoint_regions.map(|count| assign_regions(&mut rng, &positions, count));
+    if let Some(region) = &regions {
+        // Shortest lane between each pair of regions
+        let mut crossings: BTreeMap<(usize, usize), (f64, (usize, usize))> = BTreeMap::new();
+        for &(a, b) in pairs.iter().filter(|&&(a, b)| region[a] != region[b]) {
+            let key = (region[a].min(region[b]), region[a].max(region[b]));
+            let length = distance(&positions[a], &positions[b]);
+            let best = crossings.entry(key).or_insert((length, (a, b)));
+            if length < best.0 {
+                *best = (length, (a, b));
+            }
+        }
+        pairs.retain(|&(a, b)| region[a] == region[b]);
+        pairs.extend(crossings.values().map(|&(_, pair)| pair));
+    }
+
+    connect_components(&tree, &positions, &mut pairs);
+
+    Ok(pairs
+        .into_iter()
+        .map(|(a, b)| Lane {
+            id: None,
+            project_id,
+            system_a_id: systems[a].id.unwrap(),
+            system_b_id: systems[b].id.unwrap(),
+            length_ly: distance(&positions[a], &positions[b]),
+            chokepoint: regions.as_ref().is_some_and(|region| region[a] != region[b]),
+        })
+        .collect())
+}
+
+/// Each system paired with its `k` nearest neighbours, smaller index first
+pub(super) fn neighbour_pairs(tree: &KdTree, positions: &[Position3D], k: usize) -> BTreeSet<(usize, usize)> {
+    let mut pairs = BTreeSet::new();
+    for (i, position) in positions.iter().enumerate() {
+        for (j, _) in tree.nearest(position, k + 1) {
+            if j != i {
+                pairs.insert((i.min(j), i.max(j)));
+            }
+        }
+    }
+    pairs
+}
+
+/// No other system lies inside the sphere with `a`-`b` as its diameter
+fn is_gabriel(tree: &KdTree, positions: &[Position3D], a: usize, b: usize) -> bool {
+    let (p, q) = (&positions[a], &positions[b]);
+    let middle = Position3D { x: (p.x + q.x) / 2.0, y: (p.y + q.y) / 2.0, z: (p.z + q.z) / 2.0 };
+    let radius = distance(p, q) / 2.0;
+    tree.within_radius(&middle, radius)
+        .iter()
+        .all(|&(i, d)| i == a || i == b || d >= radius * (1.0 - 1e-9))
+}
+
+/// No other system is closer to both `a` and `b` than they are to each other
+fn is_relative_neighbour(tree: &KdTree, positions: &[Position3D], a: usize, b: usize) -> bool {
+    let length = distance(&positions[a], &positions[b]);
+    tree.within_radius(&positions[a], length)
+        .iter()
+        .all(|&(i, d)| i == a || i == b || d >= length || distance(&positions[i], &positions[b]) >= length)
+}
+
+/// Region of every system: the nearest of `count` seeds spread by
+/// farthest-point sampling from a random first system
+fn assign_regions<R: Rng + ?Sized>(rng: &mut R, positions: &[Position3D], count: usize) -> Vec<usize> {
+    if positions.is_empty() {
+        return Vec::new();
+    }
+    let mut region = vec![0; positions.len()];
+    let first = rng.gen_range(0..positions.len());
+    let mut nearest_seed: Vec<f64> = positions.iter().map(|p| distance(p, &positions[first])).collect();
+
+    for r in 1..count.min(positions.len()) {
+        let Some(seed) = (0..positions.len()).max_by(|&a, &b| nearest_seed[a].total_cmp(&nearest_seed[b])) else {
+            break;
+        };
+        for (i, position) in positions.iter().enumerate() {
+            let d = distance(position, &positions[seed]);
+            if d < nearest_seed[i] {
+                nearest_seed[i] = d;
+                region[i] = r;
+            }
+        }
+    }
+    region
+}
+
+/// Join the components of the network until one is left, each time by the
+/// shortest lane out of the smallest component
+fn connect_components(tree: &KdTree, positions: &[Position3D], pairs: &mut BTreeSet<(usize, usize)>) {
+    let n = positions.len();
+    let mut parent: Vec<usize> = (0..n).collect();
+    for &(a, b) in pairs.iter() {
+        let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+        parent[ra] = rb;
+    }
+
+    let mut label = vec![0; n];
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for i in 0..n {
+        label[i] = find(&mut parent, i);
+        members[label[i]].push(i);
+    }
+
+    let mut components = members.iter().filter(|m| !m.is_empty()).count();
+    let mut smallest: BinaryHeap<Reverse<(usize, usize)>> =
+        (0..n).filter(|&c| !members[c].is_empty()).map(|c| Reverse((members[c].len(), c))).collect();
+
+    while components > 1 {
+        let Some(Reverse((size, c))) = smallest.pop() else {
+            break;
+        };
+        if members[c].len() != size {
+            continue;
+        }
+
+        let bridge = members[c]
+            .iter()
+            .filter_map(|&p| {
+                tree.nearest_where(&positions[p], 1, |j| label[j] != c)
+                    .first()
+                    .map(|&(q, d)| (d, p, q))
+            })
+            .min_by(|x, y| x.0.total_cmp(&y.0));
+        let Some((_, p, q)) = bridge else {
+            break;
+        };
+        pairs.insert((p.min(q), p.max(q)));
+
+        // The smallest component joins the other, so relabelling stays cheap
+        let other = label[q];
+        let moved = std::mem::take(&mut members[c]);
+        for &i in &moved {
+            label[i] = other;
+        }
+        members[other].extend(moved);
+        smallest.push(Reverse((members[other].len(), other)));
+        components -= 1;
+    }
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut node = i;
+    while parent[node] != root {
+        let next = parent[node];
+        parent[node] = root;
+        node = next;
+    }
+    root
+}
+
+fn distance(a: &Position3D, b: &Position3D) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SystemType;
+
+    fn random_systems(n: usize, seed: u64) -> Vec<StarSystem> {
+        let mut rng = seeded_rng(seed);
+        (0..n)
+            .map(|i| StarSystem {
+                id: Some(Uuid::new_v4()),
+                project_id: Uuid::nil(),
+                name: format!("S{:04}", i + 1),
+                system_type: SystemType::Solo,
+                x_ly: rng.gen_range(-50.0..50.0),
+                y_ly: rng.gen_range(-50.0..50.0),
+                z_ly: rng.gen_range(-10.0..10.0),
+                zone_id: None,
+                faction_id: None,
+                contested_by: None,
+                border: false,
+            })
+            .collect()
+    }
+
+    /// Number of connected components, ignoring lanes `skip` rejects
+    fn component_count(systems: &[StarSystem], lanes: &[Lane], skip: impl Fn(&Lane) -> bool) -> usize {
+        let index: std::collections::HashMap<Uuid, usize> = systems.iter().enumerate().map(|(i, s)| (s.id.unwrap(), i)).collect();
+        let mut parent: Vec<usize> = (0..systems.len()).collect();
+        for lane in lanes.iter().filter(|l| !skip(l)) {
+            let a = find(&mut parent, index[&lane.system_a_id]);
+            let b = find(&mut parent, index[&lane.system_b_id]);
+            parent[a] = b;
+        }
+        (0..systems.len()).filter(|&i| find(&mut parent, i) == i).count()
+    }
+
+    fn lanes(systems: &[StarSystem], rule: LaneRule, chokepoint_regions: Option<usize>) -> Vec<Lane> {
+        generate_lanes(Uuid::nil(), systems, &LaneParams { rule, chokepoint_regions }, 5).unwrap()
+    }
+
+    #[test]
+    fn test_every_rule_gives_a_connected_network() {
+        let systems = random_systems(400, 3);
+        let pair = |l: &Lane| (l.system_a_id, l.system_b_id);
+
+        // A short range strands many systems; the bridges reconnect them
+        let jump = lanes(&systems, LaneRule::MaxJump { range_ly: 4.0 }, None);
+        assert_eq!(component_count(&systems, &jump, |_| false), 1);
+        assert!(jump.iter().filter(|l| l.length_ly > 4.0).count() > 0);
+
+        let nearest = lanes(&systems, LaneRule::KNearest { k: 3 }, None);
+        assert_eq!(component_count(&systems, &nearest, |_| false), 1);
+        assert!(nearest.len() >= 400 * 3 / 2);
+
+        let gabriel = lanes(&systems, LaneRule::Gabriel, None);
+        let relative = lanes(&systems, LaneRule::RelativeNeighbourhood, None);
+        assert_eq!(component_count(&systems, &gabriel, |_| false), 1);
+        assert_eq!(component_count(&systems, &relative, |_| false), 1);
+        // The relative neighbourhood graph is a subgraph of the Gabriel graph
+        let gabriel_pairs: BTreeSet<(Uuid, Uuid)> = gabriel.iter().map(pair).collect();
+        assert!(relative.len() < gabriel.len());
+        assert!(relative.iter().all(|l| gabriel_pairs.contains(&pair(l))));
+        assert!(gabriel.iter().all(|l| !l.chokepoint && l.system_a_id != l.system_b_id));
+
+        assert!(lanes(&systems[..1], LaneRule::Gabriel, None).is_empty());
+        assert!(generate_lanes(Uuid::nil(), &systems, &LaneParams { rule: LaneRule::MaxJump { range_ly: 1000.0 }, chokepoint_regions: None }, 5).is_err());
+    }
+
+    #[test]
+    fn test_chokepoints_separate_regions() {
+        let systems = random_systems(400, 4);
+        let network = lanes(&systems, LaneRule::Gabriel, Some(4));
+        let chokepoints = network.iter().filter(|l| l.chokepoint).count();
+
+        assert_eq!(component_count(&systems, &network, |_| false), 1);
+        assert!((3..=10).contains(&chokepoints), "{} chokepoints", chokepoints);
+        // Without its chokepoint lanes the map falls apart into the regions
+        assert!(component_count(&systems, &network, |l| l.chokepoint) >= 4);
+
+        let again = lanes(&systems, LaneRule::Gabriel, Some(4));
+        assert_eq!(again.len(), network.len());
+    }
+}
@@ -1,9 +1,99 @@
 pub mod distribution;
 pub mod star_properties;
+pub mod photometry;
 pub mod naming;
 pub mod generation;
+pub mod zones;
+pub mod separation;
+pub mod imf;
+pub mod evolution;
+pub mod orbits;
+pub mod planets;
+pub mod stability;
+pub mod habitability;
+pub mod climate;
+pub mod moons;
+pub mod belts;
+pub mod sky;
+pub mod constellations;
+pub mod skybox;
+pub mod catalogue;
+pub mod lanes;
+pub mod territories;
+pub mod economy;
+#[cfg(test)]
+mod test_support;
 
 pub use distribution::*;
 pub use star_properties::*;
+pub use photometry::*;
 pub use naming::*;
 pub use generation::*;
+pub use zones::*;
+pub use separation::*;
+pub use imf::*;
+pub use evolution::*;
+pub use orbits::*;
+pub use planets::*;
+pub use stability::*;
+pub use habitability::*;
+pub use climate::*;
+pub use moons::*;
+pub use belts::*;
+pub use sky::*;
+pub use constellations::*;
+pub use skybox::*;
+pub use catalogue::*;
+pub use lanes::*;
+pub use territories::*;
+pub use economy::*;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// RNG used for every generator stage.
+/// ChaCha8 output is stable across platforms and crate versions, so a seed
+/// always reproduces the same galaxy.
+pub type GalaxyRng = ChaCha8Rng;
+
+/// Create the generator RNG for a project seed
+pub fn seeded_rng(seed: u64) -> GalaxyRng {
+    GalaxyRng::seed_from_u64(seed)
+}
+
+/// Pick a fresh seed for requests that did not supply one
+/// Kept within 0..=i64::MAX so clients holding seeds as signed 64-bit
+/// integers (the web frontend, JavaScript-adjacent tooling) can send it back
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen_range(0..=i64::MAX as u64)
+}
+
+/// Standard normal deviate (Box-Muller)
+pub(crate) fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let u1 = (1.0 - rng.gen::<f64>()).max(f64::MIN_POSITIVE);
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Log-uniform draw between `min` and `max`
+pub(crate) fn log_uniform<R: Rng + ?Sized>(rng: &mut R, min: f64, max: f64) -> f64 {
+    (rng.gen_range(min.ln()..=max.ln())).exp()
+}
+
+/// Rayleigh-distributed draw with scale `sigma`
+pub(crate) fn rayleigh<R: Rng + ?Sized>(rng: &mut R, sigma: f64) -> f64 {
+    let u: f64 = rng.gen();
+    sigma * (-2.0 * (1.0 - u).ln()).sqrt()
+}
+
+/// Poisson-distributed count with mean `lambda` (Knuth's method)
+pub(crate) fn poisson<R: Rng + ?Sized>(rng: &mut R, lambda: f64) -> usize {
+    let limit = (-lambda).exp();
+    let mut product: f64 = rng.gen();
+    let mut count = 0;
+    while product > limit {
+        product *= rng.gen::<f64>();
+        count += 1;
+    }
+    count
+}
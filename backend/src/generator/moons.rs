@@ -0,0 +1,224 @@
+use rand::Rng;
+use uuid::Uuid;
+use crate::models::{Moon, Planet, PlanetType, Ring};
+use super::{generate_moon_name, kepler_period_years, log_uniform, poisson, rayleigh};
+
+/// Kilometres per Earth radius
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Kilometres per astronomical unit
+pub const AU_KM: f64 = 1.496e8;
+
+/// Earth masses per solar mass
+const EARTH_MASSES_PER_SOLAR: f64 = 332_946.0;
+
+/// Earth mass in kg
+const EARTH_MASS_KG: f64 = 5.972e24;
+
+/// Fluid Roche limit in planet radii; moons inside it are torn apart and
+/// rings live inside it
+const ROCHE_LIMIT_RADII: f64 = 2.44;
+
+/// Prograde moons stay bound inside about this fraction of the Hill radius
+/// (Domingos et al. 2006)
+const HILL_STABLE_FRACTION: f64 = 0.4;
+
+/// Regular satellites form in the circumplanetary disk, within about this
+/// many planet radii
+const REGULAR_MOON_MAX_RADII: f64 = 60.0;
+
+/// Smallest ratio between neighbouring moon orbits
+const MOON_SPACING_RATIO: f64 = 1.3;
+
+/// Bulk density of moons in kg/m³ (between icy and rocky)
+const MOON_DENSITY_KG_M3: f64 = 3000.0;
+
+/// Hill radius of a planet in km at periastron, around a host (star or
+/// inner binary) of `host_mass_solar`
+pub fn hill_radius_km(planet: &Planet, host_mass_solar: f64) -> f64 {
+    let periastron_km = planet.semi_major_axis_au * (1.0 - planet.eccentricity) * AU_KM;
+    periastron_km * (planet.mass_earth / (3.0 * host_mass_solar * EARTH_MASSES_PER_SOLAR)).cbrt()
+}
+
+/// Generate the moons of one planet
+/// Giants get several regular satellites with about 10⁻⁴ of their mass in
+/// total (Canup & Ward 2006); terrestrial planets sometimes get one large
+/// giant-impact moon plus captured moonlets. Moons orbit between the Roche
+/// limit and the stable part of the Hill sphere, so hot planets keep few
+pub fn generate_moons<R: Rng + ?Sized>(rng: &mut R, planet: &Planet, host_mass_solar: f64) -> Vec<Moon> {
+    let planet_radius_km = planet.radius_earth * EARTH_RADIUS_KM;
+    let inner_km = ROCHE_LIMIT_RADII * planet_radius_km;
+    let stable_km = HILL_STABLE_FRACTION * hill_radius_km(planet, host_mass_solar);
+    if stable_km <= inner_km * MOON_SPACING_RATIO {
+        return Vec::new();
+    }
+    let regular_km = stable_km.min(REGULAR_MOON_MAX_RADII * planet_radius_km);
+
+    // (mass fraction of the planet, outer orbit limit) per candidate
+    let mut candidates = Vec::new();
+    match planet.planet_type {
+        PlanetType::GasGiant | PlanetType::IceGiant => {
+            let (count, max_fraction) = if planet.planet_type == PlanetType::GasGiant { (4.0, 1e-4) } else { (3.0, 5e-5) };
+            for _ in 0..poisson(rng, count) {
+                candidates.push((log_uniform(rng, 1e-7, max_fraction), regular_km));
+            }
+        },
+        PlanetType::Rocky | PlanetType::SuperEarth => {
+            if rng.gen::<f64>() < 0.25 {
+                // Giant impact, like Earth's Moon (0.012 Earth masses)
+                candidates.push((log_uniform(rng, 1e-3, 0.02), stable_km));
+            }
+            for _ in 0..poisson(rng, 0.3) {
+                candidates.push((log_uniform(rng, 1e-9, 1e-7), stable_km));
+            }
+        },
+    }
+
+    let mut moons: Vec<Moon> = Vec::new();
+    for (mass_fraction, outer_km) in candidates {
+        if outer_km <= inner_km {
+            continue;
+        }
+        let semi_major_axis_km = log_uniform(rng, inner_km, outer_km);
+        let crowded = moons.iter().any(|m| {
+            let ratio = semi_major_axis_km / m.semi_major_axis_km;
+            ratio < MOON_SPACING_RATIO && ratio > 1.0 / MOON_SPACING_RATIO
+        });
+        if crowded {
+            continue;
+        }
+
+        let mass_earth = mass_fraction * planet.mass_earth;
+        let planet_mass_solar = planet.mass_earth / EARTH_MASSES_PER_SOLAR;
+        moons.push(Moon {
+            id: Some(Uuid::new_v4()),
+            planet_id: planet.id.unwrap_or_default(),
+            star_id: planet.star_id,
+            system_id: planet.system_id,
+            project_id: planet.project_id,
+            name: String::new(),
+            orbit_index: 0,
+            semi_major_axis_km,
+            eccentricity: rayleigh(rng, 0.01).min(0.5),
+            period_days: kepler_period_years(semi_major_axis_km / AU_KM, planet_mass_solar) * 365.25,
+            mass_earth,
+            radius_km: moon_radius_km(mass_earth),
+        });
+    }
+
+    moons.sort_by(|a, b| a.semi_major_axis_km.total_cmp(&b.semi_major_axis_km));
+    for (index, moon) in moons.iter_mut().enumerate() {
+        moon.orbit_index = index as i32 + 1;
+        moon.name = generate_moon_name(&planet.name, index);
+    }
+
+    moons
+}
+
+/// Radius of a moon in km from its mass in Earth masses
+pub fn moon_radius_km(mass_earth: f64) -> f64 {
+    let volume_m3 = mass_earth * EARTH_MASS_KG / MOON_DENSITY_KG_M3;
+    (3.0 * volume_m3 / (4.0 * std::f64::consts::PI)).cbrt() / 1000.0
+}
+
+/// Ring system for a planet, if it has one
+/// Half of gas giants and 40% of ice giants carry rings, which lie inside
+/// the Roche limit where no moon can form
+pub fn generate_ring<R: Rng + ?Sized>(rng: &mut R, planet: &Planet) -> Option<Ring> {
+    let probability = match planet.planet_type {
+        PlanetType::GasGiant => 0.5,
+        PlanetType::IceGiant => 0.4,
+        PlanetType::Rocky | PlanetType::SuperEarth => return None,
+    };
+    if rng.gen::<f64>() >= probability {
+        return None;
+    }
+
+    let planet_radius_km = planet.radius_earth * EARTH_RADIUS_KM;
+    Some(Ring {
+        inner_radius_km: planet_radius_km * rng.gen_range(1.1..1.5),
+        outer_radius_km: planet_radius_km * rng.gen_range(1.8..ROCHE_LIMIT_RADII),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::sun_like_star;
+    use crate::generator::{generate_planets, seeded_rng, PlanetHost};
+    use crate::models::{Star, WorldProfile};
+
+    fn sun() -> Star {
+        Star { metallicity_feh: 0.3, ..sun_like_star(1.0) }
+    }
+
+    #[test]
+    fn test_moon_radius() {
+        // Earth's Moon: 0.0123 Earth masses, 1737 km
+        assert!((moon_radius_km(0.0123) - 1737.0).abs() < 150.0);
+    }
+
+    #[test]
+    fn test_moons_stay_between_roche_and_hill() {
+        let mut rng = seeded_rng(20);
+        let host = sun();
+        let mut total = 0;
+
+        for _ in 0..300 {
+            for planet in generate_planets(&mut rng, &PlanetHost::single(&host)) {
+                let planet_radius_km = planet.radius_earth * EARTH_RADIUS_KM;
+                let hill = hill_radius_km(&planet, host.mass_solar);
+                let moons = generate_moons(&mut rng, &planet, host.mass_solar);
+                total += moons.len();
+
+                for (index, moon) in moons.iter().enumerate() {
+                    assert!(moon.semi_major_axis_km >= ROCHE_LIMIT_RADII * planet_radius_km);
+                    assert!(moon.semi_major_axis_km <= HILL_STABLE_FRACTION * hill);
+                    assert!(moon.mass_earth < planet.mass_earth);
+                    assert!(moon.period_days > 0.0);
+                    assert_eq!(moon.orbit_index, index as i32 + 1);
+                    assert!(moon.name.starts_with(&planet.name));
+                }
+
+                if let Some(ring) = generate_ring(&mut rng, &planet) {
+                    assert!(matches!(planet.planet_type, PlanetType::GasGiant | PlanetType::IceGiant));
+                    assert!(ring.inner_radius_km > planet_radius_km);
+                    assert!(ring.outer_radius_km < ROCHE_LIMIT_RADII * planet_radius_km);
+                }
+            }
+        }
+
+        assert!(total > 100, "Expected moons around a metal-rich Sun, got {}", total);
+    }
+
+    #[test]
+    fn test_jupiter_moons() {
+        // Jupiter's Io orbits at 421,700 km, well inside its stable region
+        let host = sun();
+        let mut rng = seeded_rng(21);
+        let jupiter = Planet {
+            id: Some(Uuid::new_v4()),
+            star_id: host.id.unwrap(),
+            system_id: host.system_id,
+            project_id: host.project_id,
+            name: "S0001 V".to_string(),
+            orbit_index: 5,
+            barycentre_orbit_id: None,
+            planet_type: PlanetType::GasGiant,
+            semi_major_axis_au: 5.2,
+            eccentricity: 0.05,
+            period_years: 11.86,
+            mass_earth: 317.8,
+            radius_earth: 11.2,
+            ring: None,
+            profile: WorldProfile::default(),
+            insolation_earth: 0.037,
+            habitability: 0.0,
+        };
+        assert!(HILL_STABLE_FRACTION * hill_radius_km(&jupiter, host.mass_solar) > 421_700.0);
+
+        let moon_counts: usize = (0..200).map(|_| generate_moons(&mut rng, &jupiter, host.mass_solar).len()).sum();
+        // Four drawn on average, a few lost to the spacing rule
+        assert!((400..900).contains(&moon_counts), "about 3 moons each, got {}", moon_counts);
+    }
+}
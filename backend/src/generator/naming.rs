@@ -1,4 +1,18 @@
-use crate::models::star_system::SystemType;
+use rand::Rng;
+use crate::models::{Star, SystemType};
+
+/// Greek letters for Bayer designations, brightest first
+const GREEK_LETTERS: [&str; 24] = [
+    "Alpha", "Beta", "Gamma", "Delta", "Epsilon", "Zeta", "Eta", "Theta", "Iota", "Kappa", "Lambda", "Mu",
+    "Nu", "Xi", "Omicron", "Pi", "Rho", "Sigma", "Tau", "Upsilon", "Phi", "Chi", "Psi", "Omega",
+];
+
+/// Syllable parts for constellation names
+const ONSETS: [&str; 20] = [
+    "b", "c", "d", "f", "g", "k", "l", "m", "n", "p", "r", "s", "t", "v", "z", "th", "ch", "dr", "st", "qu",
+];
+const VOWELS: [&str; 8] = ["a", "e", "i", "o", "u", "ae", "ei", "y"];
+const CODAS: [&str; 10] = ["", "", "", "n", "r", "s", "l", "th", "x", "m"];
 
 /// Generate star system name
 /// Format: S0001, S0002, S0003, etc.
@@ -6,23 +20,100 @@ pub fn generate_system_name(index: usize) -> String {
     format!("S{:04}", index + 1)
 }
 
+/// Component letters of a system's stars, heaviest first
+/// Solo: "" (the star is the system). Multiples: a, b, c, ...
+pub fn component_letters(num_stars: usize) -> Vec<String> {
+    match num_stars {
+        1 => vec![String::new()],
+        _ => (0..num_stars).map(|i| ((b'a' + i as u8) as char).to_string()).collect(),
+    }
+}
+
 /// Generate star names for a system
 /// Solo: S0001
 /// Binary: S0001a, S0001b
-/// Trinary: S0001a, S0001b, S0001c
+/// Trinary and larger: S0001a, S0001b, S0001c, ... (a = heaviest)
 pub fn generate_star_names(system_name: &str, system_type: &SystemType) -> Vec<String> {
-    match system_type {
-        SystemType::Solo => vec![system_name.to_string()],
-        SystemType::Binary => vec![
-            format!("{}a", system_name),
-            format!("{}b", system_name),
-        ],
-        SystemType::Trinary => vec![
-            format!("{}a", system_name),
-            format!("{}b", system_name),
-            format!("{}c", system_name),
-        ],
+    component_letters(system_type.num_stars())
+        .into_iter()
+        .map(|letter| format!("{}{}", system_name, letter))
+        .collect()
+}
+
+/// Name of one or more components of a system, e.g. an orbit's primary
+/// or a barycentre: a single component is its star's name; a group is the
+/// system name with the letters, "S0001ab" for generated stars and
+/// "Sirius AB" for stars keeping catalogue names
+pub fn component_label(system_name: &str, stars: &[Star], components: &str) -> String {
+    if let Some(star) = stars.iter().find(|s| s.component == components) {
+        return star.name.clone();
+    }
+    if stars.iter().all(|s| s.name == format!("{}{}", system_name, s.component)) {
+        format!("{}{}", system_name, components)
+    } else {
+        format!("{} {}", system_name, components.to_uppercase())
+    }
+}
+
+/// Generate a planet name: host star name plus a Roman numeral counted
+/// outwards from the star (index 0 = innermost)
+/// e.g. S0001 I, S0001a III
+pub fn generate_planet_name(star_name: &str, index: usize) -> String {
+    format!("{} {}", star_name, roman_numeral(index + 1))
+}
+
+/// Moon name: planet name plus a lowercase letter counted outwards, e.g.
+/// "S0001a III b"; moons past the 26th get their number instead
+pub fn generate_moon_name(planet_name: &str, index: usize) -> String {
+    match (b'a'..=b'z').nth(index) {
+        Some(letter) => format!("{} {}", planet_name, letter as char),
+        None => format!("{} {}", planet_name, index + 1),
+    }
+}
+
+/// Constellation name of two or three syllables, e.g. "Serrath"
+pub fn generate_constellation_name<R: Rng + ?Sized>(rng: &mut R) -> String {
+    let syllables = rng.gen_range(2..=3);
+    let mut name = String::new();
+    for i in 0..syllables {
+        name.push_str(ONSETS[rng.gen_range(0..ONSETS.len())]);
+        name.push_str(VOWELS[rng.gen_range(0..VOWELS.len())]);
+        if i + 1 == syllables {
+            name.push_str(CODAS[rng.gen_range(0..CODAS.len())]);
+        }
+    }
+
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => name,
+    }
+}
+
+/// Bayer-style designation by brightness within a constellation (index 0 =
+/// brightest), e.g. "Gamma Serrath"; past Omega the stars are numbered
+pub fn bayer_designation(constellation_name: &str, index: usize) -> String {
+    match GREEK_LETTERS.get(index) {
+        Some(letter) => format!("{} {}", letter, constellation_name),
+        None => format!("{} {}", index + 1, constellation_name),
+    }
+}
+
+/// Roman numeral for a positive number
+fn roman_numeral(mut n: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "M"), (900, "CM"), (500, "D"), (400, "CD"), (100, "C"), (90, "XC"),
+        (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+    ];
+
+    let mut s = String::new();
+    for &(value, numeral) in &NUMERALS {
+        while n >= value {
+            s.push_str(numeral);
+            n -= value;
+        }
     }
+    s
 }
 
 #[cfg(test)]
@@ -60,4 +151,39 @@ mod tests {
         assert_eq!(names[1], "S0001b");
         assert_eq!(names[2], "S0001c");
     }
+
+    #[test]
+    fn test_sextuple_star_names() {
+        let names = generate_star_names("S0042", &SystemType::Sextuple);
+        assert_eq!(names, ["S0042a", "S0042b", "S0042c", "S0042d", "S0042e", "S0042f"]);
+    }
+
+    #[test]
+    fn test_planet_names() {
+        assert_eq!(generate_planet_name("S0001", 0), "S0001 I");
+        assert_eq!(generate_planet_name("S0001a", 2), "S0001a III");
+        assert_eq!(generate_planet_name("S0001", 8), "S0001 IX");
+        assert_eq!(generate_planet_name("S0001", 13), "S0001 XIV");
+    }
+
+    #[test]
+    fn test_moon_names() {
+        assert_eq!(generate_moon_name("S0001 V", 0), "S0001 V a");
+        assert_eq!(generate_moon_name("S0001a III", 3), "S0001a III d");
+        assert_eq!(generate_moon_name("S0001 V", 26), "S0001 V 27");
+    }
+
+    #[test]
+    fn test_constellation_names() {
+        let mut rng = crate::generator::seeded_rng(17);
+        for _ in 0..100 {
+            let name = generate_constellation_name(&mut rng);
+            assert!(name.len() >= 4 && name.len() <= 14, "{}", name);
+            assert!(name.chars().next().unwrap().is_uppercase());
+        }
+
+        assert_eq!(bayer_designation("Serrath", 0), "Alpha Serrath");
+        assert_eq!(bayer_designation("Serrath", 2), "Gamma Serrath");
+        assert_eq!(bayer_designation("Serrath", 24), "25 Serrath");
+    }
 }
@@ -0,0 +1,216 @@
+use rand::Rng;
+use uuid::Uuid;
+use crate::models::{Orbit, Star, StarSystem};
+use super::standard_normal;
+
+/// Astronomical units per solar radius
+pub const AU_PER_SOLAR_RADIUS: f64 = 0.00465;
+
+/// Log-normal period distribution of solar-type binaries (Raghavan et al. 2010):
+/// mean and sigma of log10(P / days)
+const LOG_PERIOD_MEAN: f64 = 5.03;
+const LOG_PERIOD_SIGMA: f64 = 2.28;
+
+/// Period range kept from the log-normal, log10(P / days)
+const LOG_PERIOD_MIN: f64 = -0.5;
+const LOG_PERIOD_MAX: f64 = 8.5;
+
+/// Binaries closer than this are tidally circularised
+const CIRCULARIZATION_PERIOD_DAYS: f64 = 12.0;
+
+/// Highest eccentricity drawn for wide orbits
+const MAX_ECCENTRICITY: f64 = 0.8;
+
+/// Smallest companion-to-primary mass ratio
+pub const MIN_MASS_RATIO: f64 = 0.1;
+
+/// Attempts at drawing an orbit that keeps both components detached (and,
+/// for outer orbits, the hierarchy stable) before falling back to the
+/// tightest allowed circular orbit
+const MAX_ORBIT_ATTEMPTS: usize = 100;
+
+/// Companion-to-primary mass ratio q
+/// Observed ratios for solar-type binaries are roughly flat between 0.1 and 1
+pub fn sample_mass_ratio<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    rng.gen_range(MIN_MASS_RATIO..=1.0)
+}
+
+/// Orbital period in years from Kepler's third law
+/// (semi-major axis in AU, total mass in solar masses)
+pub fn kepler_period_years(semi_major_axis_au: f64, total_mass_solar: f64) -> f64 {
+    (semi_major_axis_au.powi(3) / total_mass_solar).sqrt()
+}
+
+/// Semi-major axis in AU from Kepler's third law
+pub fn kepler_semi_major_axis_au(period_years: f64, total_mass_solar: f64) -> f64 {
+    (total_mass_solar * period_years.powi(2)).cbrt()
+}
+
+/// Orbits of a multiple system's components
+/// Systems are hierarchical: "a" and "b" form the innermost pair, then each
+/// further star orbits everything inside it ("c" around "ab", "d" around
+/// "abc", ...). Stars must be ordered by component letter
+pub fn generate_orbits<R: Rng + ?Sized>(rng: &mut R, system: &StarSystem, stars: &[Star]) -> Vec<Orbit> {
+    let system_id = system.id.unwrap_or_default();
+    let suffix = |star: &Star| star.component.clone();
+    let mut orbits: Vec<Orbit> = Vec::new();
+
+    if stars.len() < 2 {
+        return orbits;
+    }
+
+    let (a, b) = (&stars[0], &stars[1]);
+    let inner_contact = AU_PER_SOLAR_RADIUS * 2.0 * (a.radius_solar + b.radius_solar);
+    let inner = sample_orbit(rng, a.mass_solar, b.mass_solar, |_| inner_contact);
+    orbits.push(Orbit {
+        id: Some(Uuid::new_v4()),
+        system_id,
+        project_id: system.project_id,
+        level: 0,
+        primary_component: suffix(a),
+        secondary_component: suffix(b),
+        ..inner
+    });
+
+    for (level, outer_star) in stars.iter().enumerate().skip(2) {
+        let inner_stars = &stars[..level];
+        let inner_mass: f64 = inner_stars.iter().map(|s| s.mass_solar).sum();
+        let q_out = outer_star.mass_solar / inner_mass;
+        let previous = &orbits[level - 2];
+        let (a_in, e_in) = (previous.semi_major_axis_au, previous.eccentricity);
+
+        // Mardling & Aarseth (2001) stability limit on the outer periastron;
+        // the outer star must also clear the inner orbit's apastron
+        let min_periastron = |e_out: f64| {
+            let stability = 2.8 * (1.0 + q_out).powf(0.4) * (1.0 + e_out).powf(0.4) * (1.0 - e_out).powf(-0.2);
+            (a_in * stability).max(a_in * (1.0 + e_in) + AU_PER_SOLAR_RADIUS * 2.0 * outer_star.radius_solar)
+        };
+        let outer = sample_orbit(rng, inner_mass, outer_star.mass_solar, min_periastron);
+        orbits.push(Orbit {
+            id: Some(Uuid::new_v4()),
+            system_id,
+            project_id: system.project_id,
+            level: level as i32 - 1,
+            primary_component: inner_stars.iter().map(suffix).collect(),
+            secondary_component: suffix(outer_star),
+            ..outer
+        });
+    }
+
+    orbits
+}
+
+/// Draw one orbit of a secondary around a primary
+/// Period is log-normal and circularised below 12 days, eccentricity flat
+/// above it, inclination isotropic; the semi-major axis follows from
+/// Kepler's third law. `min_periastron(e)` gives the closest approach
+/// allowed in AU for an eccentricity
+fn sample_orbit<R, F>(rng: &mut R, primary_mass: f64, secondary_mass: f64, min_periastron: F) -> Orbit
+where
+    R: Rng + ?Sized,
+    F: Fn(f64) -> f64,
+{
+    let total_mass = primary_mass + secondary_mass;
+    let inclination_deg = rng.gen_range(-1.0f64..=1.0).acos().to_degrees();
+
+    let mut orbit = Orbit {
+        id: None,
+        system_id: Uuid::nil(),
+        project_id: Uuid::nil(),
+        level: 0,
+        primary_component: String::new(),
+        secondary_component: String::new(),
+        semi_major_axis_au: 0.0,
+        eccentricity: 0.0,
+        inclination_deg,
+        period_years: 0.0,
+        mass_ratio: secondary_mass / primary_mass,
+    };
+
+    for _ in 0..MAX_ORBIT_ATTEMPTS {
+        let log_period = (LOG_PERIOD_MEAN + LOG_PERIOD_SIGMA * standard_normal(rng)).clamp(LOG_PERIOD_MIN, LOG_PERIOD_MAX);
+        let period_days = 10f64.powf(log_period);
+        let eccentricity = if period_days < CIRCULARIZATION_PERIOD_DAYS {
+            0.0
+        } else {
+            rng.gen_range(0.0..MAX_ECCENTRICITY)
+        };
+        let period_years = period_days / 365.25;
+        let semi_major_axis_au = kepler_semi_major_axis_au(period_years, total_mass);
+
+        if semi_major_axis_au * (1.0 - eccentricity) >= min_periastron(eccentricity) {
+            orbit.semi_major_axis_au = semi_major_axis_au;
+            orbit.eccentricity = eccentricity;
+            orbit.period_years = period_years;
+            return orbit;
+        }
+    }
+
+    // Tightest circular orbit that satisfies the constraint
+    orbit.semi_major_axis_au = min_periastron(0.0);
+    orbit.period_years = kepler_period_years(orbit.semi_major_axis_au, total_mass);
+    orbit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::sphere_params;
+    use crate::generator::{generate_star_systems, GeneratedGalaxy};
+
+    #[test]
+    fn test_kepler_third_law() {
+        // Earth around the Sun
+        assert!((kepler_period_years(1.0, 1.0) - 1.0).abs() < 1e-12);
+        // Jupiter's distance around the Sun: 11.86 years
+        assert!((kepler_period_years(5.2, 1.0) - 11.86).abs() < 0.01);
+        assert!((kepler_semi_major_axis_au(11.86, 1.0) - 5.2).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_generated_orbits() {
+        let params = sphere_params(3000, 500.0, 8);
+        let GeneratedGalaxy { systems, stars, orbits, .. } = generate_star_systems(Uuid::new_v4(), &params).unwrap();
+
+        for system in &systems {
+            let system_orbits: Vec<&Orbit> = orbits.iter().filter(|o| Some(o.system_id) == system.id).collect();
+            assert_eq!(system_orbits.len(), system.system_type.num_stars() - 1);
+
+            for orbit in &system_orbits {
+                assert!(orbit.semi_major_axis_au > 0.0);
+                assert!((0.0..1.0).contains(&orbit.eccentricity));
+                assert!((0.0..=180.0).contains(&orbit.inclination_deg));
+
+                // Kepler's third law holds for the stored masses
+                let members = stars.iter().filter(|s| Some(s.system_id) == system.id);
+                let total: f64 = members
+                    .filter(|s| {
+                        orbit.primary_component.contains(s.component.as_str())
+                            || orbit.secondary_component.contains(s.component.as_str())
+                    })
+                    .map(|s| s.mass_solar)
+                    .sum();
+                let period = kepler_period_years(orbit.semi_major_axis_au, total);
+                assert!((period / orbit.period_years - 1.0).abs() < 1e-9);
+            }
+
+            // Hierarchies: each outer orbit is much wider than the one inside it
+            for (level, pair) in system_orbits.windows(2).enumerate() {
+                let (inner, outer) = (pair[0], pair[1]);
+                assert_eq!((inner.level, outer.level), (level as i32, level as i32 + 1));
+                assert_eq!(outer.primary_component, format!("{}{}", inner.primary_component, inner.secondary_component));
+                assert!(outer.semi_major_axis_au * (1.0 - outer.eccentricity) > 2.8 * inner.semi_major_axis_au);
+            }
+        }
+
+        // Median period near the observed 10^5 days
+        let mut log_periods: Vec<f64> = orbits
+            .iter()
+            .filter(|o| o.level == 0)
+            .map(|o| (o.period_years * 365.25).log10())
+            .collect();
+        log_periods.sort_by(f64::total_cmp);
+        let median = log_periods[log_periods.len() / 2];
+        assert!((4.0..6.0).contains(&median), "median log P {}", median);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::models::Photometry;
+
+/// Absolute bolometric magnitude of the Sun (IAU 2015 B2)
+pub const SUN_BOLOMETRIC_MAGNITUDE: f64 = 4.74;
+
+/// Second radiation constant hc/k in nm·K
+const RADIATION_C2: f64 = 1.4388e7;
+
+/// Effective wavelength of the Johnson V band in nm
+const V_BAND_NM: f64 = 545.0;
+
+/// Temperatures covered by the Flower (1996) bolometric corrections
+const FLOWER_MIN_K: f64 = 3000.0;
+const FLOWER_MAX_K: f64 = 50000.0;
+
+/// Bluest and reddest B-V the colour relation is allowed to give; real O
+/// stars saturate near -0.33 where both bands sit on the Rayleigh-Jeans tail
+const MIN_B_V: f64 = -0.33;
+const MAX_B_V: f64 = 3.0;
+
+/// What a star looks like through a telescope and on screen
+/// None for dark remnants (neutron stars and black holes)
+pub fn star_photometry(temperature_k: f64, luminosity_solar: f64) -> Option<Photometry> {
+    if temperature_k <= 0.0 || luminosity_solar <= 0.0 {
+        return None;
+    }
+
+    let bolometric_magnitude = SUN_BOLOMETRIC_MAGNITUDE - 2.5 * luminosity_solar.log10();
+    let bolometric_correction = bolometric_correction(temperature_k);
+    let [r, g, b] = blackbody_rgb(temperature_k);
+
+    Some(Photometry {
+        absolute_magnitude_v: bolometric_magnitude - bolometric_correction,
+        bolometric_correction,
+        color_index_bv: color_index_bv(temperature_k),
+        color_hex: format!("#{:02x}{:02x}{:02x}", r, g, b),
+    })
+}
+
+/// V-band bolometric correction BC_V = M_bol - M_V
+/// Flower (1996) polynomials with the coefficients corrected by Torres
+/// (2010). Outside their range the correction follows the change in a
+/// blackbody's V-band share of its total flux
+pub fn bolometric_correction(temperature_k: f64) -> f64 {
+    let edge = temperature_k.clamp(FLOWER_MIN_K, FLOWER_MAX_K);
+    flower_correction(edge) + blackbody_v_correction(temperature_k) - blackbody_v_correction(edge)
+}
+
+fn flower_correction(temperature_k: f64) -> f64 {
+    let coefficients: &[f64] = match temperature_k.log10() {
+        lt if lt < 3.70 => &[-0.190537291496456e5, 0.155144866764412e5, -0.421278819301717e4, 0.381476328422343e3],
+        lt if lt < 3.90 => &[
+            -0.370510203809015e5,
+            0.385672629965804e5,
+            -0.150651486316025e5,
+            0.261724637119416e4,
+            -0.170623810323864e3,
+        ],
+        _ => &[
+            -0.118115450538963e6,
+            0.137145973583929e6,
+            -0.636233812100225e5,
+            0.147412923562646e5,
+            -0.170587278406872e4,
+            0.788731721804990e2,
+        ],
+    };
+    let lt = temperature_k.log10();
+    coefficients.iter().rev().fold(0.0, |sum, c| sum * lt + c)
+}
+
+/// Bolometric correction of a blackbody up to a constant
+fn blackbody_v_correction(temperature_k: f64) -> f64 {
+    let v_flux = 1.0 / (RADIATION_C2 / (V_BAND_NM * temperature_k)).exp_m1();
+    2.5 * (v_flux / temperature_k.powi(4)).log10()
+}
+
+/// B-V colour index (Ballesteros 2012, inverted), clamped to the range
+/// observed in real stars
+pub fn color_index_bv(temperature_k: f64) -> f64 {
+    // T = 4600 (1 / (0.92 BV + 1.7) + 1 / (0.92 BV + 0.62)), a quadratic in 0.92 BV
+    let k = temperature_k / 4600.0;
+    let b = 2.32 * k - 2.0;
+    let c = 1.054 * k - 2.32;
+    let x = (-b + (b * b - 4.0 * k * c).sqrt()) / (2.0 * k);
+    (x / 0.92).clamp(MIN_B_V, MAX_B_V)
+}
+
+/// Effective temperature from a B-V colour index (Ballesteros 2012)
+pub fn bv_temperature(color_index_bv: f64) -> f64 {
+    let bv = color_index_bv.clamp(MIN_B_V, MAX_B_V);
+    4600.0 * (1.0 / (0.92 * bv + 1.7) + 1.0 / (0.92 * bv + 0.62))
+}
+
+/// Display colour of a blackbody as 8-bit sRGB, brightest channel at 255
+/// Integrates Planck's law against the CIE 1931 colour-matching functions
+/// (multi-lobe fit of Wyman, Sloan & Shirley 2013) from 380 to 780 nm
+pub fn blackbody_rgb(temperature_k: f64) -> [u8; 3] {
+    let lobe = |lambda: f64, mu: f64, below: f64, above: f64| {
+        let sigma = if lambda < mu { below } else { above };
+        (-0.5 * ((lambda - mu) / sigma).powi(2)).exp()
+    };
+
+    let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+    for step in 0..=80 {
+        let lambda = 380.0 + 5.0 * step as f64;
+        let radiance = lambda.powi(-5) / (RADIATION_C2 / (lambda * temperature_k)).exp_m1();
+        x += radiance
+            * (1.056 * lobe(lambda, 599.8, 37.9, 31.0) + 0.362 * lobe(lambda, 442.0, 16.0, 26.7)
+                - 0.065 * lobe(lambda, 501.1, 20.4, 26.2));
+        y += radiance * (0.821 * lobe(lambda, 568.8, 46.9, 40.5) + 0.286 * lobe(lambda, 530.9, 16.3, 31.1));
+        z += radiance * (1.217 * lobe(lambda, 437.0, 11.8, 36.0) + 0.681 * lobe(lambda, 459.0, 26.0, 13.8));
+    }
+
+    // XYZ to linear sRGB (D65); out-of-gamut negatives are clipped
+    let linear = [
+        (3.2406 * x - 1.5372 * y - 0.4986 * z).max(0.0),
+        (-0.9689 * x + 1.8758 * y + 0.0415 * z).max(0.0),
+        (0.0557 * x - 0.2040 * y + 1.0570 * z).max(0.0),
+    ];
+    let brightest = linear.iter().copied().fold(0.0, f64::max);
+    if brightest <= 0.0 {
+        return [0, 0, 0];
+    }
+
+    linear.map(|channel| {
+        let c = channel / brightest;
+        let encoded = if c <= 0.0031308 { 12.92 * c } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+        (encoded * 255.0).round() as u8
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::SUN_TEMPERATURE_K;
+
+    #[test]
+    fn test_solar_photometry() {
+        let sun = star_photometry(SUN_TEMPERATURE_K, 1.0).unwrap();
+        // M_V = 4.81, BC_V = -0.07, B-V = 0.65
+        assert!((sun.absolute_magnitude_v - 4.81).abs() < 0.05, "{:?}", sun);
+        assert!((sun.bolometric_correction + 0.07).abs() < 0.05);
+        assert!((sun.color_index_bv - 0.65).abs() < 0.03);
+
+        // Nearly white, a touch warm
+        let [r, g, b] = blackbody_rgb(SUN_TEMPERATURE_K);
+        assert_eq!(r, 255);
+        assert!(g > 220 && b > 200 && b < g, "{:?}", [r, g, b]);
+
+        assert!(star_photometry(0.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_colour_and_corrections_follow_temperature() {
+        let red = blackbody_rgb(3000.0);
+        let blue = blackbody_rgb(30000.0);
+        assert!(red[0] > red[2] && blue[2] > blue[0]);
+
+        // Cool and hot stars put less of their light into V than the Sun
+        for t in [250.0, 1000.0, 2400.0, 3500.0, 20000.0, 80000.0] {
+            let bc = bolometric_correction(t);
+            assert!(bc < 0.0 && bc.is_finite(), "BC({}) = {}", t, bc);
+        }
+        assert!(bolometric_correction(1000.0) < bolometric_correction(3000.0));
+        assert!(bolometric_correction(80000.0) < bolometric_correction(30000.0));
+        // No jumps where the polynomial pieces meet
+        for edge in [FLOWER_MIN_K, 10f64.powf(3.70), 10f64.powf(3.90), FLOWER_MAX_K] {
+            assert!((bolometric_correction(edge * 0.999) - bolometric_correction(edge * 1.001)).abs() < 0.05);
+        }
+
+        let mut previous = f64::INFINITY;
+        for t in [2000.0, 3000.0, 4000.0, 6000.0, 10000.0, 20000.0] {
+            let bv = color_index_bv(t);
+            assert!(bv <= previous);
+            previous = bv;
+            if bv > MIN_B_V && bv < MAX_B_V {
+                assert!((bv_temperature(bv) / t - 1.0).abs() < 1e-9, "B-V {} should give back {} K", bv, t);
+            }
+        }
+        assert!(color_index_bv(9700.0).abs() < 0.05, "A0 stars have B-V near 0");
+    }
+}
@@ -0,0 +1,311 @@
+use rand::Rng;
+use uuid::Uuid;
+use crate::models::{EvolutionaryStage, Planet, PlanetType, WorldProfile};
+use super::{generate_planet_name, kepler_period_years, log_uniform, poisson, rayleigh, PlanetHost, AU_PER_SOLAR_RADIUS};
+
+/// Water snow line of a solar-mass star in AU; scales as M² (∝ √L on the
+/// main sequence)
+const SNOW_LINE_AU: f64 = 2.7;
+
+/// Inner edge of a solar-mass star's planet-forming disk in AU
+const INNER_EDGE_AU: f64 = 0.03;
+
+/// Closest orbit a hot Jupiter migrates to, in AU
+const HOT_JUPITER_MAX_AU: f64 = 0.1;
+
+/// Fraction of giant planets that migrate inwards to become hot Jupiters
+const HOT_JUPITER_FRACTION: f64 = 0.1;
+
+/// Smallest ratio between neighbouring semi-major axes; closer pairs are
+/// dynamically unstable (period ratio of about 1.4)
+const MIN_SPACING_RATIO: f64 = 1.25;
+
+/// White dwarf progenitors swallowed everything inside about this radius
+/// while on the giant branch
+const WHITE_DWARF_CLEARED_AU: f64 = 2.0;
+
+/// Highest eccentricity kept for a planet
+const MAX_ECCENTRICITY: f64 = 0.9;
+
+/// Water snow line of a star in AU
+pub fn snow_line_au(star_mass: f64) -> f64 {
+    SNOW_LINE_AU * star_mass.powi(2)
+}
+
+/// Expected number of rocky planets and super-Earths around a star
+/// Small planets are most common around M dwarfs (about 2.5 per star;
+/// Dressing & Charbonneau 2015) and nearly independent of metallicity
+pub fn small_planet_rate(star_mass: f64) -> f64 {
+    (2.0 * star_mass.powf(-0.5)).clamp(0.3, 2.5)
+}
+
+/// Probability that a star hosts at least one gas giant
+/// f = 0.07 (M / M☉) 10^(1.2 [Fe/H]) (Johnson et al. 2010)
+pub fn giant_planet_probability(star_mass: f64, metallicity_feh: f64) -> f64 {
+    (0.07 * star_mass.min(3.0) * 10f64.powf(1.2 * metallicity_feh)).min(0.9)
+}
+
+/// Expected number of ice giants, which need less metal-rich disks than gas
+/// giants
+pub fn ice_giant_rate(star_mass: f64, metallicity_feh: f64) -> f64 {
+    0.4 * star_mass.min(3.0).sqrt() * 10f64.powf(0.5 * metallicity_feh)
+}
+
+/// Planet radius in Earth radii from its mass in Earth masses
+/// Piecewise power law of Chen & Kipping (2017): R ∝ M^0.28 for rocky
+/// planets, M^0.59 for volatile-rich ones, flattening to about one Jupiter
+/// radius for gas giants
+pub fn planet_radius_earth(mass_earth: f64) -> f64 {
+    if mass_earth < 2.0 {
+        return mass_earth.powf(0.279);
+    }
+    let neptunian = 1.213 * (mass_earth / 2.0).powf(0.589);
+    let jovian = 11.2 * (mass_earth / 317.8).powf(-0.04);
+    neptunian.min(jovian)
+}
+
+/// Generate the planets of one star or barycentre
+/// Occurrence depends on the host's mass and metallicity. Small planets
+/// form inside the snow line, giants beyond it (a few migrate inwards as hot
+/// Jupiters). Formation zones are trimmed to the host's stable region, and
+/// planets that stray out of it, crowd a neighbour or sit inside the region
+/// the star has engulfed are discarded. Neutron stars and black holes get none
+pub fn generate_planets<R: Rng + ?Sized>(rng: &mut R, host: &PlanetHost) -> Vec<Planet> {
+    let star = host.star;
+    let mass = host.mass_solar;
+    let (small_rate, giant_probability, ice_rate) = match star.evolutionary_stage {
+        EvolutionaryStage::NeutronStar | EvolutionaryStage::BlackHole => return Vec::new(),
+        // Brown dwarf disks only hold enough material for small rocky worlds
+        EvolutionaryStage::BrownDwarf => (0.5, 0.0, 0.0),
+        _ => (
+            small_planet_rate(mass),
+            giant_planet_probability(mass, star.metallicity_feh),
+            ice_giant_rate(mass, star.metallicity_feh),
+        ),
+    };
+
+    let snow_line = snow_line_au(mass);
+    let inner_edge = INNER_EDGE_AU * mass;
+
+    // Semi-major axis in [min, max] clipped to the stable region, if any
+    // of the range survives
+    let draw = |rng: &mut R, min: f64, max: f64| {
+        let (min, max) = (min.max(host.inner_limit_au), max.min(host.outer_limit_au));
+        (min < max).then(|| log_uniform(rng, min, max))
+    };
+
+    // Candidates in placement priority: giants form first and clear their
+    // neighbourhood
+    let mut candidates = Vec::new();
+    if rng.gen::<f64>() < giant_probability {
+        for _ in 0..1 + poisson(rng, 0.4) {
+            let a = if rng.gen::<f64>() < HOT_JUPITER_FRACTION {
+                draw(rng, inner_edge, HOT_JUPITER_MAX_AU.max(inner_edge * 2.0))
+            } else {
+                draw(rng, 0.5 * snow_line, 5.0 * snow_line)
+            };
+            candidates.extend(a.map(|a| (PlanetType::GasGiant, a)));
+        }
+    }
+    for _ in 0..poisson(rng, ice_rate) {
+        candidates.extend(draw(rng, snow_line, 15.0 * snow_line).map(|a| (PlanetType::IceGiant, a)));
+    }
+    for _ in 0..poisson(rng, small_rate) {
+        let planet_type = if star.evolutionary_stage == EvolutionaryStage::BrownDwarf || rng.gen::<f64>() < 0.5 {
+            PlanetType::Rocky
+        } else {
+            PlanetType::SuperEarth
+        };
+        candidates.extend(draw(rng, inner_edge, snow_line.max(inner_edge * 2.0)).map(|a| (planet_type, a)));
+    }
+
+    let cleared = match star.evolutionary_stage {
+        EvolutionaryStage::WhiteDwarf => WHITE_DWARF_CLEARED_AU,
+        _ => 2.0 * star.radius_solar * AU_PER_SOLAR_RADIUS,
+    };
+
+    let mut planets: Vec<Planet> = Vec::new();
+    for (planet_type, semi_major_axis_au) in candidates {
+        let crowded = planets.iter().any(|p| {
+            let ratio = semi_major_axis_au / p.semi_major_axis_au;
+            ratio < MIN_SPACING_RATIO && ratio > 1.0 / MIN_SPACING_RATIO
+        });
+        if crowded {
+            continue;
+        }
+
+        let (min_mass, max_mass) = planet_type.mass_range();
+        let mass_earth = log_uniform(rng, min_mass, max_mass);
+        let eccentricity = if semi_major_axis_au < HOT_JUPITER_MAX_AU && planet_type == PlanetType::GasGiant {
+            0.0 // tidally circularised
+        } else {
+            let sigma = match planet_type {
+                PlanetType::Rocky | PlanetType::SuperEarth => 0.05,
+                PlanetType::IceGiant => 0.1,
+                PlanetType::GasGiant => 0.25,
+            };
+            rayleigh(rng, sigma).min(MAX_ECCENTRICITY)
+        };
+
+        if semi_major_axis_au * (1.0 - eccentricity) < cleared || !host.is_stable(semi_major_axis_au, eccentricity) {
+            continue;
+        }
+
+        planets.push(Planet {
+            id: Some(Uuid::new_v4()),
+            star_id: star.id.unwrap_or_default(),
+            system_id: star.system_id,
+            project_id: star.project_id,
+            name: String::new(),
+            orbit_index: 0,
+            barycentre_orbit_id: host.barycentre_orbit_id,
+            planet_type,
+            semi_major_axis_au,
+            eccentricity,
+            period_years: kepler_period_years(semi_major_axis_au, mass),
+            mass_earth,
+            radius_earth: planet_radius_earth(mass_earth),
+            ring: None,
+            // Filled in once the whole system exists (assess_habitability)
+            profile: WorldProfile::default(),
+            insolation_earth: 0.0,
+            habitability: 0.0,
+        });
+    }
+
+    // Number and name outwards from the host
+    planets.sort_by(|a, b| a.semi_major_axis_au.total_cmp(&b.semi_major_axis_au));
+    for (index, planet) in planets.iter_mut().enumerate() {
+        planet.orbit_index = index as i32 + 1;
+        planet.name = generate_planet_name(&host.name, index);
+    }
+
+    planets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::sun_like_star;
+    use crate::generator::{main_sequence_properties, seeded_rng};
+    use crate::models::{Star, StarProperties};
+
+    fn host(properties: StarProperties, metallicity_feh: f64) -> Star {
+        Star {
+            spectral_class: properties.spectral_class,
+            spectral_subclass: properties.spectral_subclass,
+            luminosity_class: properties.luminosity_class,
+            temperature_k: properties.temperature_k,
+            mass_solar: properties.mass_solar,
+            radius_solar: properties.radius_solar,
+            luminosity_solar: properties.luminosity_solar,
+            metallicity_feh,
+            evolutionary_stage: properties.evolutionary_stage,
+            ..sun_like_star(1.0)
+        }
+    }
+
+    #[test]
+    fn test_mass_radius_relation() {
+        assert!((planet_radius_earth(1.0) - 1.0).abs() < 1e-9);
+        // Neptune: 17.1 M⊕, 3.9 R⊕
+        assert!((planet_radius_earth(17.1) - 3.9).abs() < 0.5);
+        // Jupiter: 317.8 M⊕, 11.2 R⊕
+        assert!((planet_radius_earth(317.8) - 11.2).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_occurrence_rates() {
+        assert!(small_planet_rate(0.3) > small_planet_rate(1.0));
+        assert!(giant_planet_probability(1.0, 0.3) > 2.0 * giant_planet_probability(1.0, 0.0));
+        assert!(giant_planet_probability(2.0, 0.0) > giant_planet_probability(0.5, 0.0));
+        assert!(ice_giant_rate(1.0, 0.3) > ice_giant_rate(1.0, -0.5));
+    }
+
+    #[test]
+    fn test_generated_planets() {
+        let mut rng = seeded_rng(10);
+        let sun = host(main_sequence_properties(1.0), 0.0);
+        let mut total = 0;
+
+        for _ in 0..500 {
+            let planets = generate_planets(&mut rng, &PlanetHost::single(&sun));
+            total += planets.len();
+
+            for (index, planet) in planets.iter().enumerate() {
+                let (min_mass, max_mass) = planet.planet_type.mass_range();
+                assert!((min_mass..=max_mass).contains(&planet.mass_earth));
+                assert!((0.0..1.0).contains(&planet.eccentricity));
+                assert!((planet.period_years - kepler_period_years(planet.semi_major_axis_au, 1.0)).abs() < 1e-9);
+                assert_eq!(planet.orbit_index, index as i32 + 1);
+                assert_eq!(planet.star_id, sun.id.unwrap());
+            }
+            for pair in planets.windows(2) {
+                assert!(pair[1].semi_major_axis_au / pair[0].semi_major_axis_au >= MIN_SPACING_RATIO);
+            }
+        }
+
+        assert!(total > 500, "Sun-like stars should average more than one planet");
+    }
+
+    #[test]
+    fn test_metal_rich_stars_host_more_giants() {
+        let mut rng = seeded_rng(11);
+        let mut giant_hosts = |feh: f64| {
+            let star = host(main_sequence_properties(1.0), feh);
+            (0..3000)
+                .filter(|_| {
+                    generate_planets(&mut rng, &PlanetHost::single(&star))
+                        .iter()
+                        .any(|p| p.planet_type == PlanetType::GasGiant)
+                })
+                .count()
+        };
+
+        let metal_poor = giant_hosts(-0.5);
+        let metal_rich = giant_hosts(0.3);
+        assert!(metal_rich > 3 * metal_poor, "{} vs {}", metal_rich, metal_poor);
+    }
+
+    #[test]
+    fn test_planets_stay_in_stable_region() {
+        let mut rng = seeded_rng(13);
+        let sun = host(main_sequence_properties(1.0), 0.3);
+        let s_type = PlanetHost { outer_limit_au: 3.0, ..PlanetHost::single(&sun) };
+        let p_type = PlanetHost {
+            name: "S0001ab".to_string(),
+            barycentre_orbit_id: Some(Uuid::new_v4()),
+            inner_limit_au: 1.0,
+            ..PlanetHost::single(&sun)
+        };
+
+        for _ in 0..300 {
+            for planet in generate_planets(&mut rng, &s_type) {
+                assert!(planet.semi_major_axis_au * (1.0 + planet.eccentricity) <= 3.0);
+                assert_eq!(planet.barycentre_orbit_id, None);
+            }
+            for planet in generate_planets(&mut rng, &p_type) {
+                assert!(planet.semi_major_axis_au * (1.0 - planet.eccentricity) >= 1.0);
+                assert_eq!(planet.barycentre_orbit_id, p_type.barycentre_orbit_id);
+                assert_eq!(planet.host_name(), "S0001ab");
+            }
+        }
+    }
+
+    #[test]
+    fn test_remnants() {
+        let mut rng = seeded_rng(12);
+        let mut white_dwarf = host(main_sequence_properties(1.0), 0.0);
+        white_dwarf.evolutionary_stage = EvolutionaryStage::WhiteDwarf;
+        white_dwarf.mass_solar = 0.6;
+        let mut neutron_star = white_dwarf.clone();
+        neutron_star.evolutionary_stage = EvolutionaryStage::NeutronStar;
+
+        for _ in 0..200 {
+            assert!(generate_planets(&mut rng, &PlanetHost::single(&neutron_star)).is_empty());
+            for planet in generate_planets(&mut rng, &PlanetHost::single(&white_dwarf)) {
+                assert!(planet.semi_major_axis_au * (1.0 - planet.eccentricity) >= WHITE_DWARF_CLEARED_AU);
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use crate::models::Position3D;
+
+/// Spatial hash for Poisson-disk (blue-noise) placement
+/// Cells are `min_separation` wide, so a candidate only has to be checked
+/// against the 27 cells around it
+pub struct SeparationGrid {
+    min_separation: f64,
+    cells: HashMap<(i64, i64, i64), Vec<Position3D>>,
+}
+
+impl SeparationGrid {
+    pub fn new(min_separation: f64) -> Self {
+        Self {
+            min_separation,
+            cells: HashMap::new(),
+        }
+    }
+
+    fn cell_of(&self, pos: &Position3D) -> (i64, i64, i64) {
+        (
+            (pos.x / self.min_separation).floor() as i64,
+            (pos.y / self.min_separation).floor() as i64,
+            (pos.z / self.min_separation).floor() as i64,
+        )
+    }
+
+    /// Whether `pos` is at least `min_separation` from every stored position
+    pub fn fits(&self, pos: &Position3D) -> bool {
+        let (cx, cy, cz) = self.cell_of(pos);
+        let min_sq = self.min_separation * self.min_separation;
+
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    if let Some(points) = self.cells.get(&(cx + dx, cy + dy, cz + dz)) {
+                        if points.iter().any(|p| distance_sq(p, pos) < min_sq) {
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    pub fn insert(&mut self, pos: Position3D) {
+        let cell = self.cell_of(&pos);
+        self.cells.entry(cell).or_default().push(pos);
+    }
+}
+
+/// Smallest distance between any two positions (None for fewer than two)
+/// Sweeps positions sorted by x, stopping once the x gap alone exceeds the
+/// best distance found so far
+pub fn minimum_separation(positions: &[Position3D]) -> Option<f64> {
+    if positions.len() < 2 {
+        return None;
+    }
+
+    let mut sorted: Vec<&Position3D> = positions.iter().collect();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+    let mut best_sq = f64::MAX;
+    for i in 0..sorted.len() {
+        for j in (i + 1)..sorted.len() {
+            let dx = sorted[j].x - sorted[i].x;
+            if dx * dx >= best_sq {
+                break;
+            }
+            best_sq = best_sq.min(distance_sq(sorted[i], sorted[j]));
+        }
+    }
+
+    Some(best_sq.sqrt())
+}
+
+fn distance_sq(a: &Position3D, b: &Position3D) -> f64 {
+    (a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x: f64, y: f64, z: f64) -> Position3D {
+        Position3D { x, y, z }
+    }
+
+    #[test]
+    fn test_grid_rejects_close_points() {
+        let mut grid = SeparationGrid::new(2.0);
+        grid.insert(pos(0.0, 0.0, 0.0));
+
+        assert!(!grid.fits(&pos(1.0, 1.0, 0.0)));
+        assert!(!grid.fits(&pos(-1.9, 0.0, 0.0)));
+        assert!(grid.fits(&pos(2.0, 0.0, 0.0)));
+        assert!(grid.fits(&pos(-1.5, -1.5, -1.5)));
+    }
+
+    #[test]
+    fn test_minimum_separation() {
+        let points = vec![
+            pos(0.0, 0.0, 0.0),
+            pos(10.0, 0.0, 0.0),
+            pos(10.0, 3.0, 4.0),
+            pos(-7.0, 1.0, 0.0),
+        ];
+        assert_eq!(minimum_separation(&points), Some(5.0));
+        assert_eq!(minimum_separation(&points[..1]), None);
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::models::{Position3D, SkyFrame, SkyStar, Star, StarSystem};
+
+/// Light years per parsec
+pub const LY_PER_PARSEC: f64 = 3.261_563_777;
+
+/// Obliquity of the ecliptic at J2000 in degrees
+const OBLIQUITY_DEG: f64 = 23.439_291;
+
+/// Naked-eye limit under a dark sky
+pub const NAKED_EYE_MAGNITUDE: f64 = 6.5;
+
+/// Apparent magnitude of a star of absolute magnitude `absolute_magnitude`
+/// seen from `distance_ly` (no extinction)
+pub fn apparent_magnitude(absolute_magnitude: f64, distance_ly: f64) -> f64 {
+    absolute_magnitude + 5.0 * (distance_ly / LY_PER_PARSEC).log10() - 5.0
+}
+
+/// Sky position of `target` seen from `observer` as (longitude, latitude)
+/// in degrees: right ascension and declination, or ecliptic coordinates
+pub fn sky_position(observer: Position3D, target: Position3D, frame: SkyFrame) -> (f64, f64) {
+    let (x, mut y, mut z) = (target.x - observer.x, target.y - observer.y, target.z - observer.z);
+    if frame == SkyFrame::Ecliptic {
+        let (sin_e, cos_e) = OBLIQUITY_DEG.to_radians().sin_cos();
+        (y, z) = (y * cos_e + z * sin_e, z * cos_e - y * sin_e);
+    }
+
+    let longitude_deg = y.atan2(x).to_degrees().rem_euclid(360.0);
+    let latitude_deg = z.atan2(x.hypot(y)).to_degrees();
+    (longitude_deg, latitude_deg)
+}
+
+/// Angle between two points on the sky in degrees
+pub fn angular_separation_deg(a: &SkyStar, b: &SkyStar) -> f64 {
+    let (lat_a, lat_b) = (a.latitude_deg.to_radians(), b.latitude_deg.to_radians());
+    let cos_angle = lat_a.sin() * lat_b.sin()
+        + lat_a.cos() * lat_b.cos() * (a.longitude_deg - b.longitude_deg).to_radians().cos();
+    cos_angle.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// Every star outside the observer's system brighter than
+/// `limiting_magnitude`, brightest first
+/// Companions in one system share its position; dark remnants never show
+pub fn sky_view(
+    observer: &StarSystem,
+    systems: &[StarSystem],
+    stars: &[Star],
+    frame: SkyFrame,
+    limiting_magnitude: f64,
+) -> Vec<SkyStar> {
+    let origin = Position3D { x: observer.x_ly, y: observer.y_ly, z: observer.z_ly };
+    let mut sky = Vec::new();
+
+    let mut by_system: HashMap<Uuid, Vec<&Star>> = HashMap::new();
+    for star in stars {
+        by_system.entry(star.system_id).or_default().push(star);
+    }
+
+    for system in systems.iter().filter(|s| s.id != observer.id) {
+        let Some(members) = system.id.and_then(|id| by_system.get(&id)) else {
+            continue;
+        };
+        let position = Position3D { x: system.x_ly, y: system.y_ly, z: system.z_ly };
+        let distance_ly = ((position.x - origin.x).powi(2) + (position.y - origin.y).powi(2) + (position.z - origin.z).powi(2)).sqrt();
+        if distance_ly <= 0.0 {
+            continue;
+        }
+        let (longitude_deg, latitude_deg) = sky_position(origin, position, frame);
+
+        for star in members {
+            let Some(photometry) = &star.photometry else {
+                continue;
+            };
+            let magnitude = apparent_magnitude(photometry.absolute_magnitude_v, distance_ly);
+            if magnitude > limiting_magnitude {
+                continue;
+            }
+
+            sky.push(SkyStar {
+                star_id: star.id.unwrap_or_default(),
+                name: star.name.clone(),
+                system_name: system.name.clone(),
+                spectral_class: star.spectral_type(),
+                longitude_deg,
+                latitude_deg,
+                distance_ly,
+                apparent_magnitude: magnitude,
+                color_hex: photometry.color_hex.clone(),
+            });
+        }
+    }
+
+    sky.sort_by(|a, b| a.apparent_magnitude.total_cmp(&b.apparent_magnitude));
+    sky
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::sun_like_star;
+    use crate::generator::star_photometry;
+    use crate::models::SystemType;
+    use uuid::Uuid;
+
+    fn system(name: &str, x_ly: f64, y_ly: f64, z_ly: f64) -> StarSystem {
+        StarSystem {
+            id: Some(Uuid::new_v4()),
+            project_id: Uuid::nil(),
+            name: name.to_string(),
+            system_type: SystemType::Solo,
+            x_ly,
+            y_ly,
+            z_ly,
+            zone_id: None,
+            faction_id: None,
+            contested_by: None,
+            border: false,
+        }
+    }
+
+    fn star(system: &StarSystem, mass: f64) -> Star {
+        let star = sun_like_star(mass);
+        Star {
+            system_id: system.id.unwrap(),
+            name: system.name.clone(),
+            age_gyr: 1.0,
+            photometry: star_photometry(star.temperature_k, star.luminosity_solar),
+            ..star
+        }
+    }
+
+    #[test]
+    fn test_apparent_magnitude() {
+        // Absolute magnitude is the magnitude at 10 pc
+        assert!((apparent_magnitude(4.83, 10.0 * LY_PER_PARSEC) - 4.83).abs() < 1e-9);
+        // The Sun from Alpha Centauri (4.37 ly) is about magnitude 0.5
+        assert!((apparent_magnitude(4.83, 4.37) - 0.46).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_sky_positions() {
+        let origin = Position3D { x: 10.0, y: 10.0, z: 10.0 };
+        let at = |x, y, z| Position3D { x: origin.x + x, y: origin.y + y, z: origin.z + z };
+
+        assert_eq!(sky_position(origin, at(5.0, 0.0, 0.0), SkyFrame::Equatorial), (0.0, 0.0));
+        let (ra, dec) = sky_position(origin, at(0.0, -5.0, 0.0), SkyFrame::Equatorial);
+        assert!((ra - 270.0).abs() < 1e-9 && dec.abs() < 1e-9);
+        let (_, dec) = sky_position(origin, at(0.0, 0.0, 5.0), SkyFrame::Equatorial);
+        assert!((dec - 90.0).abs() < 1e-9);
+
+        // The north ecliptic pole sits at RA 18h, Dec 66.56°
+        let (sin_e, cos_e) = OBLIQUITY_DEG.to_radians().sin_cos();
+        let (_, beta) = sky_position(origin, at(0.0, -sin_e, cos_e), SkyFrame::Ecliptic);
+        assert!((beta - 90.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sky_view() {
+        let home = system("S0001", 0.0, 0.0, 0.0);
+        let near = system("S0002", 4.37, 0.0, 0.0);
+        let far = system("S0003", 0.0, 3000.0, 0.0);
+        let systems = vec![home.clone(), near.clone(), far.clone()];
+        let stars = vec![star(&home, 1.0), star(&near, 1.0), star(&far, 0.3)];
+
+        let sky = sky_view(&home, &systems, &stars, SkyFrame::Equatorial, NAKED_EYE_MAGNITUDE);
+        assert_eq!(sky.len(), 1, "The home star and a faint M dwarf should not show");
+        assert_eq!(sky[0].system_name, "S0002");
+        assert!((sky[0].distance_ly - 4.37).abs() < 1e-9);
+
+        let deep = sky_view(&home, &systems, &stars, SkyFrame::Equatorial, 30.0);
+        assert_eq!(deep.len(), 2);
+        assert!(deep[0].apparent_magnitude < deep[1].apparent_magnitude);
+        assert!((deep[1].longitude_deg - 90.0).abs() < 1e-9);
+    }
+}
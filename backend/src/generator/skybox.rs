@@ -0,0 +1,406 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::models::{Constellation, SkyProjection, SkyStar};
+
+/// Peak brightness (0-1) of a star at the limiting magnitude; each magnitude
+/// brighter is 2.512 times brighter until the core saturates
+const LIMIT_BRIGHTNESS: f64 = 0.25;
+
+/// Star image width (Gaussian sigma) in pixels for a faint star
+const STAR_SIGMA_PX: f64 = 0.7;
+
+/// Saturated stars grow by this much sigma per e-fold of excess brightness
+const STAR_GROWTH: f64 = 0.35;
+
+const LINE_COLOR: [u8; 3] = [70, 110, 170];
+const LINE_ALPHA: f64 = 0.6;
+const LABEL_COLOR: [u8; 3] = [160, 180, 210];
+
+/// 3x5 pixel glyphs for A-Z and 0-9, rows top to bottom, 3 bits per row
+const GLYPHS: [u16; 36] = [
+    0b010_101_111_101_101, 0b110_101_110_101_110, 0b011_100_100_100_011, 0b110_101_101_101_110,
+    0b111_100_110_100_111, 0b111_100_110_100_100, 0b011_100_101_101_011, 0b101_101_111_101_101,
+    0b111_010_010_010_111, 0b001_001_001_101_010, 0b101_101_110_101_101, 0b100_100_100_100_111,
+    0b101_111_111_101_101, 0b110_101_101_101_101, 0b010_101_101_101_010, 0b110_101_110_100_100,
+    0b010_101_101_110_011, 0b110_101_110_101_101, 0b011_100_010_001_110, 0b111_010_010_010_010,
+    0b101_101_101_101_111, 0b101_101_101_101_010, 0b101_101_111_111_101, 0b101_101_010_101_101,
+    0b101_101_010_010_010, 0b111_001_010_100_111, 0b111_101_101_101_111, 0b010_110_010_010_111,
+    0b110_001_010_100_111, 0b110_001_010_001_110, 0b101_101_111_001_001, 0b111_100_110_001_110,
+    0b011_100_111_101_111, 0b111_001_010_010_010, 0b111_101_111_101_111, 0b111_101_111_001_110,
+];
+
+/// What to draw and how large
+#[derive(Debug, Clone)]
+pub struct SkyboxOptions {
+    pub projection: SkyProjection,
+    /// Panorama width, or the edge of one cube face, in pixels
+    pub size: u32,
+    /// Faintest star drawn
+    pub limiting_magnitude: f64,
+    /// Draw constellation figures
+    pub lines: bool,
+    /// Write constellation names next to their brightest star
+    pub labels: bool,
+}
+
+/// Render the sky as a PNG image
+/// `sky` is the equatorial view from `sky_view`; stars fainter than the
+/// limiting magnitude are left out but still anchor constellation lines.
+/// Star brightness follows apparent magnitude and colour the star's
+/// blackbody colour
+pub fn render_skybox(sky: &[SkyStar], constellations: &[Constellation], options: &SkyboxOptions) -> Result<Vec<u8>, String> {
+    let mut canvas = Canvas::new(options.projection, options.size);
+
+    for star in sky.iter().filter(|s| s.apparent_magnitude <= options.limiting_magnitude) {
+        let brightness = LIMIT_BRIGHTNESS * 10f64.powf(-0.4 * (star.apparent_magnitude - options.limiting_magnitude));
+        let sigma = canvas.scale * STAR_SIGMA_PX * (1.0 + STAR_GROWTH * brightness.max(1.0).ln());
+        let color = parse_hex_color(&star.color_hex).unwrap_or([255, 255, 255]);
+        // Equirectangular rows shrink towards the poles, so stars widen with them
+        let stretch = 1.0 / star.latitude_deg.to_radians().cos().max(0.05);
+
+        for spot in canvas.spots(direction(star)) {
+            let x_sigma = if options.projection == SkyProjection::Equirectangular { sigma * stretch } else { sigma };
+            canvas.splat(&spot, x_sigma, sigma, color, brightness.min(1.0));
+        }
+    }
+
+    if options.lines || options.labels {
+        let by_id: HashMap<Uuid, &SkyStar> = sky.iter().map(|s| (s.star_id, s)).collect();
+        for constellation in constellations {
+            if options.lines {
+                for member in &constellation.stars {
+                    let (Some(from), Some(to)) = (by_id.get(&member.star_id), member.line_to_star_id.and_then(|id| by_id.get(&id))) else {
+                        continue;
+                    };
+                    canvas.arc(direction(from), direction(to));
+                }
+            }
+            if options.labels {
+                if let Some(brightest) = constellation.stars.first().and_then(|s| by_id.get(&s.star_id)) {
+                    if let Some(spot) = canvas.spots(direction(brightest)).first() {
+                        canvas.text(spot, &constellation.name);
+                    }
+                }
+            }
+        }
+    }
+
+    canvas.to_png()
+}
+
+/// "#rrggbb" to RGB
+pub fn parse_hex_color(hex: &str) -> Option<[u8; 3]> {
+    let digits = hex.strip_prefix('#')?;
+    if digits.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Unit vector towards a star in the equatorial frame
+fn direction(star: &SkyStar) -> [f64; 3] {
+    let (sin_lat, cos_lat) = star.latitude_deg.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = star.longitude_deg.to_radians().sin_cos();
+    [cos_lat * cos_lon, cos_lat * sin_lon, sin_lat]
+}
+
+/// Where a direction lands on the image, and the columns it may spill into
+#[derive(Clone, Copy)]
+struct Spot {
+    x: f64,
+    y: f64,
+    min_x: i64,
+    max_x: i64,
+    /// Columns wrap around (the panorama's RA seam)
+    wrap: bool,
+}
+
+struct Canvas {
+    projection: SkyProjection,
+    /// Panorama width or cube face edge
+    size: u32,
+    width: u32,
+    height: u32,
+    /// Pixels per degree near the image centre
+    pixels_per_degree: f64,
+    /// Star, line and label size relative to a 2048-pixel panorama
+    scale: f64,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(projection: SkyProjection, size: u32) -> Self {
+        let (width, height) = match projection {
+            SkyProjection::Equirectangular => (size, size / 2),
+            SkyProjection::CubeMap => (size * 6, size),
+        };
+        let pixels_per_degree = match projection {
+            SkyProjection::Equirectangular => size as f64 / 360.0,
+            SkyProjection::CubeMap => size as f64 / 90.0,
+        };
+
+        Self {
+            projection,
+            size,
+            width,
+            height,
+            pixels_per_degree,
+            scale: (pixels_per_degree / (2048.0 / 360.0)).max(1.0),
+            pixels: vec![0; width as usize * height as usize * 3],
+        }
+    }
+
+    /// Image positions of a direction: one on the panorama (RA falling from
+    /// 360° at the left edge to 0° at the right, as on a sky chart), or one
+    /// per cube face it falls on or just beyond (so stars cross face seams)
+    fn spots(&self, d: [f64; 3]) -> Vec<Spot> {
+        let size = self.size as f64;
+        match self.projection {
+            SkyProjection::Equirectangular => {
+                let ra = d[1].atan2(d[0]).to_degrees().rem_euclid(360.0);
+                let dec = d[2].clamp(-1.0, 1.0).asin().to_degrees();
+                vec![Spot {
+                    x: (1.0 - ra / 360.0) * size,
+                    y: (90.0 - dec) / 180.0 * self.height as f64,
+                    min_x: 0,
+                    max_x: self.width as i64 - 1,
+                    wrap: true,
+                }]
+            },
+            SkyProjection::CubeMap => {
+                let [x, y, z] = d;
+                // (major axis, s, t) per face, OpenGL order
+                let faces = [(x, -z, -y), (-x, z, -y), (y, x, z), (-y, x, -z), (z, x, -y), (-z, -x, -y)];
+                let margin = 8.0 * self.scale / size;
+                faces
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &(major, _, _))| major > 0.0)
+                    .filter_map(|(face, &(major, s, t))| {
+                        let (u, v) = (s / major, t / major);
+                        if u.abs() > 1.0 + margin || v.abs() > 1.0 + margin {
+                            return None;
+                        }
+                        let origin = face as f64 * size;
+                        Some(Spot {
+                            x: origin + (u + 1.0) / 2.0 * size,
+                            y: (v + 1.0) / 2.0 * size,
+                            min_x: origin as i64,
+                            max_x: (origin + size) as i64 - 1,
+                            wrap: false,
+                        })
+                    })
+                    .collect()
+            },
+        }
+    }
+
+    /// Pixel index, or None outside the spot's columns or the image
+    fn index(&self, spot: &Spot, px: i64, py: i64) -> Option<usize> {
+        if py < 0 || py >= self.height as i64 {
+            return None;
+        }
+        let px = if spot.wrap {
+            px.rem_euclid(self.width as i64)
+        } else if px < spot.min_x || px > spot.max_x {
+            return None;
+        } else {
+            px
+        };
+        Some((py as usize * self.width as usize + px as usize) * 3)
+    }
+
+    /// Add a Gaussian star image
+    fn splat(&mut self, spot: &Spot, x_sigma: f64, y_sigma: f64, color: [u8; 3], peak: f64) {
+        let (rx, ry) = ((3.0 * x_sigma).ceil() as i64, (3.0 * y_sigma).ceil() as i64);
+        let (cx, cy) = (spot.x.floor() as i64, spot.y.floor() as i64);
+        for py in cy - ry..=cy + ry {
+            for px in cx - rx..=cx + rx {
+                let Some(i) = self.index(spot, px, py) else {
+                    continue;
+                };
+                let dx = (px as f64 + 0.5 - spot.x) / x_sigma;
+                let dy = (py as f64 + 0.5 - spot.y) / y_sigma;
+                let weight = peak * (-0.5 * (dx * dx + dy * dy)).exp();
+                for (channel, &c) in self.pixels[i..i + 3].iter_mut().zip(&color) {
+                    *channel = channel.saturating_add((c as f64 * weight).round() as u8);
+                }
+            }
+        }
+    }
+
+    /// Blend a square dot of `size` pixels
+    fn dot(&mut self, spot: &Spot, size: i64, color: [u8; 3], alpha: f64) {
+        let (cx, cy) = (spot.x.floor() as i64, spot.y.floor() as i64);
+        for py in cy..cy + size {
+            for px in cx..cx + size {
+                if let Some(i) = self.index(spot, px, py) {
+                    for (channel, &c) in self.pixels[i..i + 3].iter_mut().zip(&color) {
+                        *channel = (*channel as f64 * (1.0 - alpha) + c as f64 * alpha).round() as u8;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Great-circle line between two directions, sampled twice per pixel
+    fn arc(&mut self, a: [f64; 3], b: [f64; 3]) {
+        let cos_angle = (a[0] * b[0] + a[1] * b[1] + a[2] * b[2]).clamp(-1.0, 1.0);
+        let angle = cos_angle.acos();
+        if angle < 1e-9 {
+            return;
+        }
+        let steps = (angle.to_degrees() * self.pixels_per_degree * 2.0).ceil() as usize;
+        let thickness = self.scale.round() as i64;
+
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            let (wa, wb) = (((1.0 - t) * angle).sin() / angle.sin(), (t * angle).sin() / angle.sin());
+            let d = [wa * a[0] + wb * b[0], wa * a[1] + wb * b[1], wa * a[2] + wb * b[2]];
+            for spot in self.spots(d) {
+                self.dot(&spot, thickness, LINE_COLOR, LINE_ALPHA);
+            }
+        }
+    }
+
+    /// Write upper-case text just below and right of a spot
+    fn text(&mut self, spot: &Spot, text: &str) {
+        let scale = (2.0 * self.scale).round() as i64;
+        let mut x = spot.x + 4.0 * scale as f64;
+        let y = spot.y + 3.0 * scale as f64;
+
+        for c in text.chars().map(|c| c.to_ascii_uppercase()) {
+            let glyph = match c {
+                'A'..='Z' => Some(GLYPHS[c as usize - 'A' as usize]),
+                '0'..='9' => Some(GLYPHS[26 + c as usize - '0' as usize]),
+                _ => None,
+            };
+            if let Some(bits) = glyph {
+                for row in 0..5 {
+                    for col in 0..3 {
+                        if bits >> (14 - (row * 3 + col)) & 1 == 1 {
+                            let pixel = Spot { x: x + (col * scale) as f64, y: y + (row * scale) as f64, ..*spot };
+                            self.dot(&pixel, scale, LABEL_COLOR, 1.0);
+                        }
+                    }
+                }
+            }
+            x += 4.0 * scale as f64;
+        }
+    }
+
+    fn to_png(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer.write_image_data(&self.pixels).map_err(|e| e.to_string())?;
+        writer.finish().map_err(|e| e.to_string())?;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConstellationStar;
+
+    fn sky_star(name: &str, ra: f64, dec: f64, magnitude: f64) -> SkyStar {
+        SkyStar {
+            star_id: Uuid::new_v4(),
+            name: name.to_string(),
+            system_name: name.to_string(),
+            spectral_class: "G2V".to_string(),
+            longitude_deg: ra,
+            latitude_deg: dec,
+            distance_ly: 10.0,
+            apparent_magnitude: magnitude,
+            color_hex: "#ffe0c0".to_string(),
+        }
+    }
+
+    fn decode(png_bytes: &[u8]) -> (u32, u32, Vec<u8>) {
+        let decoder = png::Decoder::new(png_bytes);
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        (info.width, info.height, pixels)
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#ff8000"), Some([255, 128, 0]));
+        assert_eq!(parse_hex_color("ff8000"), None);
+        assert_eq!(parse_hex_color("#ff80"), None);
+    }
+
+    #[test]
+    fn test_equirectangular_skybox() {
+        let bright = sky_star("S0002", 180.0, 0.0, 0.0);
+        let faint = sky_star("S0003", 90.0, 45.0, 5.0);
+        let hidden = sky_star("S0004", 270.0, -30.0, 9.0);
+        let options = SkyboxOptions {
+            projection: SkyProjection::Equirectangular,
+            size: 512,
+            limiting_magnitude: 6.5,
+            lines: false,
+            labels: false,
+        };
+        let (width, height, pixels) = decode(&render_skybox(&[bright, faint, hidden], &[], &options).unwrap());
+        assert_eq!((width, height), (512, 256));
+
+        let at = |ra: f64, dec: f64| {
+            let x = ((1.0 - ra / 360.0) * 512.0) as usize;
+            let y = ((90.0 - dec) / 180.0 * 256.0) as usize;
+            &pixels[(y * 512 + x) * 3..(y * 512 + x) * 3 + 3]
+        };
+        // RA 180° lies mid-image; warm stars are redder than blue
+        assert!(at(180.0, 0.0)[0] > 200 && at(180.0, 0.0)[0] > at(180.0, 0.0)[2]);
+        assert!(at(90.0, 45.0)[0] > 0 && at(90.0, 45.0)[0] < at(180.0, 0.0)[0]);
+        assert_eq!(at(270.0, -30.0), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_cube_map_lines_and_labels() {
+        let a = sky_star("S0002", 10.0, 10.0, 1.0);
+        let b = sky_star("S0003", 60.0, 20.0, 2.0);
+        let figures = vec![Constellation {
+            id: None,
+            project_id: Uuid::nil(),
+            observer_system_id: Uuid::nil(),
+            name: "Serrath".to_string(),
+            seed: 0,
+            stars: vec![
+                ConstellationStar { star_id: a.star_id, bayer_index: 1, designation: "Alpha Serrath".to_string(), line_to_star_id: None },
+                ConstellationStar {
+                    star_id: b.star_id,
+                    bayer_index: 2,
+                    designation: "Beta Serrath".to_string(),
+                    line_to_star_id: Some(a.star_id),
+                },
+            ],
+        }];
+        let sky = [a, b];
+        let mut options = SkyboxOptions {
+            projection: SkyProjection::CubeMap,
+            size: 128,
+            limiting_magnitude: 6.5,
+            lines: false,
+            labels: false,
+        };
+
+        let lit = |pixels: &[u8]| pixels.chunks(3).filter(|p| p.iter().any(|&c| c > 0)).count();
+        let (width, height, plain) = decode(&render_skybox(&sky, &figures, &options).unwrap());
+        assert_eq!((width, height), (768, 128));
+
+        options.lines = true;
+        let (_, _, lined) = decode(&render_skybox(&sky, &figures, &options).unwrap());
+        assert!(lit(&lined) > lit(&plain) + 20, "The figure line should cross the +X and +Y faces");
+
+        options.labels = true;
+        let (_, _, labelled) = decode(&render_skybox(&sky, &figures, &options).unwrap());
+        assert!(lit(&labelled) > lit(&lined));
+    }
+}
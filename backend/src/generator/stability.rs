@@ -0,0 +1,167 @@
+use uuid::Uuid;
+use crate::models::{Orbit, Star, StarSystem};
+use super::component_label;
+
+/// Critical semi-major axis of an S-type planet (orbiting one star of a
+/// binary) as a fraction of the binary separation (Holman & Wiegert 1999)
+/// `mu` is the companion's share of the pair's mass
+pub fn s_type_critical_ratio(mu: f64, eccentricity: f64) -> f64 {
+    let e = eccentricity;
+    (0.464 - 0.380 * mu - 0.631 * e + 0.586 * mu * e + 0.150 * e * e - 0.198 * mu * e * e).max(0.0)
+}
+
+/// Critical semi-major axis of a P-type (circumbinary) planet as a multiple
+/// of the binary separation (Holman & Wiegert 1999)
+/// `mu` is the secondary's share of the pair's mass
+pub fn p_type_critical_ratio(mu: f64, eccentricity: f64) -> f64 {
+    let e = eccentricity;
+    1.60 + 5.10 * e - 2.22 * e * e + 4.12 * mu - 4.27 * e * mu - 5.09 * mu * mu + 4.61 * e * e * mu * mu
+}
+
+/// A star, or the barycentre of an inner group of stars, that planets can
+/// orbit, with the region where their orbits stay stable
+#[derive(Debug, Clone)]
+pub struct PlanetHost<'a> {
+    /// Star whose disk the planets form from; the heaviest member for a
+    /// barycentre
+    pub star: &'a Star,
+    /// Star name, or the enclosed stars for a barycentre ("S0001ab")
+    pub name: String,
+    /// Mass the planets orbit in M☉
+    pub mass_solar: f64,
+    /// Component orbit whose barycentre the planets circle (None = the star)
+    pub barycentre_orbit_id: Option<Uuid>,
+    pub inner_limit_au: f64,
+    pub outer_limit_au: f64,
+}
+
+impl<'a> PlanetHost<'a> {
+    /// A star without companions: every orbit is stable
+    pub fn single(star: &'a Star) -> Self {
+        Self {
+            star,
+            name: star.name.clone(),
+            mass_solar: star.mass_solar,
+            barycentre_orbit_id: None,
+            inner_limit_au: 0.0,
+            outer_limit_au: f64::INFINITY,
+        }
+    }
+
+    /// Whether an orbit with this semi-major axis and eccentricity stays
+    /// inside the stable region at both apsides
+    pub fn is_stable(&self, semi_major_axis_au: f64, eccentricity: f64) -> bool {
+        semi_major_axis_au * (1.0 - eccentricity) >= self.inner_limit_au
+            && semi_major_axis_au * (1.0 + eccentricity) <= self.outer_limit_au
+    }
+}
+
+/// Everything planets can orbit in one system
+/// Stars and orbits come from `generate_orbits`: orbit k pairs stars
+/// 0..=k with star k + 1. Each star gets S-type planets inside the tightest
+/// limit its orbits allow, and each pair or inner group gets P-type planets
+/// outside its circumbinary limit and inside the limit set by the next star
+/// out. Regions that close up (inner limit beyond the outer) are dropped
+pub fn planet_hosts<'a>(system: &StarSystem, stars: &'a [Star], orbits: &[Orbit]) -> Vec<PlanetHost<'a>> {
+    let group_mass = |level: usize| stars[..=level + 1].iter().map(|s| s.mass_solar).sum::<f64>();
+    let mut hosts = Vec::new();
+
+    for (i, star) in stars.iter().enumerate() {
+        let outer_limit_au = orbits
+            .iter()
+            .enumerate()
+            .skip(i.saturating_sub(1))
+            .map(|(level, orbit)| {
+                let companion_mass = if level + 1 == i {
+                    group_mass(level) - star.mass_solar
+                } else {
+                    stars[level + 1].mass_solar
+                };
+                let mu = companion_mass / (star.mass_solar + companion_mass);
+                s_type_critical_ratio(mu, orbit.eccentricity) * orbit.semi_major_axis_au
+            })
+            .fold(f64::INFINITY, f64::min);
+
+        hosts.push(PlanetHost { outer_limit_au, ..PlanetHost::single(star) });
+    }
+
+    for (level, orbit) in orbits.iter().enumerate() {
+        let mass = group_mass(level);
+        let mu = stars[level + 1].mass_solar / mass;
+        let inner_limit_au = p_type_critical_ratio(mu, orbit.eccentricity) * orbit.semi_major_axis_au;
+        let outer_limit_au = match orbits.get(level + 1) {
+            Some(outer) => {
+                let mu = stars[level + 2].mass_solar / (mass + stars[level + 2].mass_solar);
+                s_type_critical_ratio(mu, outer.eccentricity) * outer.semi_major_axis_au
+            },
+            None => f64::INFINITY,
+        };
+
+        if inner_limit_au < outer_limit_au {
+            hosts.push(PlanetHost {
+                star: &stars[0],
+                name: component_label(
+                    &system.name,
+                    stars,
+                    &format!("{}{}", orbit.primary_component, orbit.secondary_component),
+                ),
+                mass_solar: mass,
+                barycentre_orbit_id: orbit.id,
+                inner_limit_au,
+                outer_limit_au,
+            });
+        }
+    }
+
+    hosts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::sphere_params;
+    use crate::generator::{generate_star_systems, GeneratedGalaxy};
+
+    #[test]
+    fn test_holman_wiegert_limits() {
+        // Equal-mass circular binary: 0.27 and 2.39 times the separation
+        assert!((s_type_critical_ratio(0.5, 0.0) - 0.274).abs() < 1e-3);
+        assert!((p_type_critical_ratio(0.5, 0.0) - 2.387).abs() < 1e-3);
+        // Eccentric binaries shrink the S-type region and push P-type planets out
+        assert!(s_type_critical_ratio(0.3, 0.5) < s_type_critical_ratio(0.3, 0.0));
+        assert!(p_type_critical_ratio(0.3, 0.5) > p_type_critical_ratio(0.3, 0.0));
+        // A light companion leaves more room around the heavy star
+        assert!(s_type_critical_ratio(0.1, 0.2) > s_type_critical_ratio(0.5, 0.2));
+    }
+
+    #[test]
+    fn test_generated_planets_are_stable() {
+        let galaxy = generate_star_systems(
+            Uuid::new_v4(),
+            &sphere_params(1500, 300.0, 13),
+        )
+        .unwrap();
+        let GeneratedGalaxy { systems, stars, orbits, planets, .. } = &galaxy;
+
+        let mut circumbinary = 0;
+        for system in systems.iter().filter(|s| s.system_type.num_stars() > 1) {
+            let members: Vec<Star> = stars.iter().filter(|s| s.system_id == system.id.unwrap()).cloned().collect();
+            let links: Vec<Orbit> = orbits.iter().filter(|o| o.system_id == system.id.unwrap()).cloned().collect();
+            let hosts = planet_hosts(system, &members, &links);
+
+            for planet in planets.iter().filter(|p| p.system_id == system.id.unwrap()) {
+                let host = hosts
+                    .iter()
+                    .find(|h| h.star.id == Some(planet.star_id) && h.barycentre_orbit_id == planet.barycentre_orbit_id)
+                    .expect("every planet orbits a stable host");
+                assert!(host.is_stable(planet.semi_major_axis_au, planet.eccentricity), "{} is unstable", planet.name);
+                assert_eq!(planet.host_name(), host.name);
+                if planet.barycentre_orbit_id.is_some() {
+                    circumbinary += 1;
+                }
+            }
+        }
+
+        assert!(circumbinary > 0, "Close binaries should host circumbinary planets");
+    }
+}
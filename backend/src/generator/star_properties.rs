@@ -1,77 +1,450 @@
 use rand::Rng;
-use rand::distributions::WeightedIndex;
-use rand::prelude::Distribution;
-use crate::models::star::{SpectralClass, StarProperties};
+use crate::models::{EvolutionaryStage, Imf, LuminosityClass, SpectralClass, StarProperties};
+use super::{evolve_star, sample_mass, standard_normal, MIN_STELLAR_MASS};
 
-/// Generate random spectral class with weighted distribution
-/// M stars are 20x more common than O stars
-pub fn generate_spectral_class() -> SpectralClass {
-    let mut rng = rand::thread_rng();
+/// Solar effective temperature in Kelvin
+pub const SUN_TEMPERATURE_K: f64 = 5772.0;
 
-    let classes = SpectralClass::all_classes();
-    let weights: Vec<u32> = classes.iter().map(|c| c.weight()).collect();
+/// Solar radius in kilometres
+const SUN_RADIUS_KM: f64 = 695_700.0;
 
-    let dist = WeightedIndex::new(&weights).unwrap();
-    classes[dist.sample(&mut rng)]
+/// Chandrasekhar limit in solar masses
+pub const CHANDRASEKHAR_MASS: f64 = 1.44;
+
+/// Typical brown dwarf radius (about one Jupiter radius); it barely
+/// depends on mass
+pub const BROWN_DWARF_RADIUS_SOLAR: f64 = 0.1;
+
+/// Neutron star radius in kilometres
+const NEUTRON_STAR_RADIUS_KM: f64 = 12.0;
+
+/// Deuterium-burning limit; the lightest object counted as a brown dwarf
+pub const MIN_BROWN_DWARF_MASS: f64 = 0.013;
+
+/// Share of star slots holding a brown dwarf rather than a star
+/// Brown dwarfs lie below the IMF's hydrogen-burning limit, so they are
+/// drawn separately (roughly one per five stars in the solar neighbourhood)
+pub const BROWN_DWARF_FRACTION: f64 = 0.15;
+
+/// Main-sequence luminosity in solar units for a mass in solar masses
+/// Piecewise power-law mass-luminosity relation
+pub fn main_sequence_luminosity(mass: f64) -> f64 {
+    if mass < 0.43 {
+        0.23 * mass.powf(2.3)
+    } else if mass < 2.0 {
+        mass.powi(4)
+    } else if mass < 55.0 {
+        1.4 * mass.powf(3.5)
+    } else {
+        32000.0 * mass
+    }
+}
+
+/// Main-sequence mass for a luminosity, inverting `main_sequence_luminosity`
+pub fn main_sequence_mass(luminosity_solar: f64) -> f64 {
+    if luminosity_solar < main_sequence_luminosity(0.43) {
+        (luminosity_solar / 0.23).powf(1.0 / 2.3)
+    } else if luminosity_solar < main_sequence_luminosity(2.0) {
+        luminosity_solar.powf(0.25)
+    } else if luminosity_solar < main_sequence_luminosity(55.0) {
+        (luminosity_solar / 1.4).powf(1.0 / 3.5)
+    } else {
+        luminosity_solar / 32000.0
+    }
+}
+
+/// Main-sequence radius in solar units for a mass in solar masses
+pub fn main_sequence_radius(mass: f64) -> f64 {
+    if mass < 1.0 {
+        mass.powf(0.8)
+    } else {
+        mass.powf(0.75)
+    }
+}
+
+/// Effective temperature from luminosity and radius (Stefan-Boltzmann)
+pub fn effective_temperature(luminosity_solar: f64, radius_solar: f64) -> f64 {
+    SUN_TEMPERATURE_K * (luminosity_solar / radius_solar.powi(2)).powf(0.25)
+}
+
+/// Luminosity from radius and temperature (Stefan-Boltzmann)
+pub fn stefan_boltzmann_luminosity(radius_solar: f64, temperature_k: f64) -> f64 {
+    radius_solar.powi(2) * (temperature_k / SUN_TEMPERATURE_K).powi(4)
+}
+
+/// Radius from luminosity and temperature (Stefan-Boltzmann)
+pub fn stefan_boltzmann_radius(luminosity_solar: f64, temperature_k: f64) -> f64 {
+    luminosity_solar.sqrt() * (SUN_TEMPERATURE_K / temperature_k).powi(2)
+}
+
+/// Main-sequence effective temperature for a mass, clamped to the O-M range
+fn main_sequence_temperature(mass: f64) -> f64 {
+    effective_temperature(main_sequence_luminosity(mass), main_sequence_radius(mass)).clamp(2400.0, 50000.0)
+}
+
+/// Derive a main-sequence star's properties from its mass
+/// Luminosity comes from the mass relation, temperature from
+/// Stefan-Boltzmann clamped to the O-M range, radius from luminosity and
+/// that temperature, and class/subclass from temperature, so every value is
+/// consistent with the others
+pub fn main_sequence_properties(mass: f64) -> StarProperties {
+    let luminosity_solar = main_sequence_luminosity(mass);
+    let temperature_k = main_sequence_temperature(mass);
+    let radius_solar = stefan_boltzmann_radius(luminosity_solar, temperature_k);
+    let (spectral_class, spectral_subclass) = SpectralClass::from_temperature(temperature_k);
+
+    StarProperties {
+        spectral_class,
+        spectral_subclass: Some(spectral_subclass),
+        luminosity_class: Some(LuminosityClass::V),
+        evolutionary_stage: EvolutionaryStage::MainSequence,
+        temperature_k,
+        mass_solar: mass,
+        radius_solar,
+        luminosity_solar,
+    }
+}
+
+/// Draw the initial mass of a system primary
+/// A fixed share of slots hold brown dwarfs (log-uniform in mass); the
+/// rest take a stellar mass from the IMF
+pub fn sample_initial_mass<R: Rng + ?Sized>(rng: &mut R, imf: Imf) -> f64 {
+    if rng.gen::<f64>() < BROWN_DWARF_FRACTION {
+        return 10f64.powf(rng.gen_range(MIN_BROWN_DWARF_MASS.log10()..MIN_STELLAR_MASS.log10()));
+    }
+    sample_mass(rng, imf)
+}
+
+/// Properties at `age_gyr` of an object formed with `initial_mass`
+/// Below the hydrogen-burning limit it is a brown dwarf; stars are evolved
+/// to their age
+pub fn star_from_initial_mass<R: Rng + ?Sized>(rng: &mut R, initial_mass: f64, age_gyr: f64) -> StarProperties {
+    if initial_mass < MIN_STELLAR_MASS {
+        return brown_dwarf_properties(initial_mass, age_gyr);
+    }
+    evolve_star(rng, initial_mass, age_gyr)
+}
+
+/// Star with temperature and luminosity fixed; the radius follows from
+/// Stefan-Boltzmann so it matches both
+fn evolved_properties(
+    stage: EvolutionaryStage,
+    class: LuminosityClass,
+    mass_solar: f64,
+    temperature_k: f64,
+    luminosity_solar: f64,
+) -> StarProperties {
+    let (spectral_class, spectral_subclass) = SpectralClass::from_temperature(temperature_k);
+
+    StarProperties {
+        spectral_class,
+        spectral_subclass: Some(spectral_subclass),
+        luminosity_class: Some(class),
+        evolutionary_stage: stage,
+        temperature_k,
+        mass_solar,
+        radius_solar: stefan_boltzmann_radius(luminosity_solar, temperature_k),
+        luminosity_solar,
+    }
+}
+
+/// Subgiant crossing from the main sequence towards the giant branch
+/// `progress` runs from 0 (leaving the main sequence) to 1 (base of the
+/// giant branch): the star cools towards 5000 K and brightens
+pub fn subgiant_properties(mass: f64, progress: f64) -> StarProperties {
+    let p = progress.clamp(0.0, 1.0);
+    let start_t = main_sequence_temperature(mass);
+    let end_t = start_t.min(5000.0);
+    let temperature_k = start_t + (end_t - start_t) * p;
+    let luminosity_solar = main_sequence_luminosity(mass) * (1.0 + p);
+
+    evolved_properties(EvolutionaryStage::Subgiant, LuminosityClass::IV, mass, temperature_k, luminosity_solar)
+}
+
+/// Giant climbing the giant branch
+/// Stars above 3 M☉ become bright giants (II), lighter ones giants (III);
+/// `progress` runs from the base (5200 K) to the tip (3500 K)
+pub fn giant_properties(mass: f64, progress: f64) -> StarProperties {
+    let p = progress.clamp(0.0, 1.0);
+    let (stage, class, (min_l, max_l)): (_, _, (f64, f64)) = if mass >= 3.0 {
+        (EvolutionaryStage::BrightGiant, LuminosityClass::II, (1.0e3, 1.0e4))
+    } else {
+        (EvolutionaryStage::Giant, LuminosityClass::III, (30.0, 1000.0))
+    };
+    let temperature_k = 5200.0 - 1700.0 * p;
+    let luminosity_solar = min_l * (max_l / min_l).powf(p);
+
+    evolved_properties(stage, class, mass, temperature_k, luminosity_solar)
+}
+
+/// Massive star after the main sequence, moving from blue to red
+/// supergiant as `progress` runs from 0 to 1
+/// Luminosity class Ia above 10^5 L☉, Ib below
+pub fn supergiant_properties(mass: f64, progress: f64) -> StarProperties {
+    let p = progress.clamp(0.0, 1.0);
+    let temperature_k = main_sequence_temperature(mass).powf(1.0 - p) * 3500f64.powf(p);
+    let luminosity_solar = main_sequence_luminosity(mass) * (2.0 + 3.0 * p);
+    let class = if luminosity_solar >= 1.0e5 { LuminosityClass::Ia } else { LuminosityClass::Ib };
+
+    evolved_properties(EvolutionaryStage::Supergiant, class, mass, temperature_k, luminosity_solar)
+}
+
+/// White dwarf radius from the Nauenberg mass-radius relation
+pub fn white_dwarf_radius(mass_solar: f64) -> f64 {
+    let x = (mass_solar / CHANDRASEKHAR_MASS).min(0.999);
+    0.0126 * x.powf(-1.0 / 3.0) * (1.0 - x.powf(4.0 / 3.0)).sqrt()
 }
 
-/// Generate complete star properties based on spectral class
-pub fn generate_star_properties() -> StarProperties {
-    let mut rng = rand::thread_rng();
+/// White dwarf of a given mass that has been cooling for `cooling_age_gyr`
+/// Mestel cooling (T ∝ t^-0.35) from about 100,000 K; the spectral type
+/// follows the atmosphere and temperature, and the subclass is the
+/// temperature index 50400 / T
+pub fn white_dwarf_properties<R: Rng + ?Sized>(rng: &mut R, mass_solar: f64, cooling_age_gyr: f64) -> StarProperties {
+    let temperature_k = 4000.0 * (10.0 / cooling_age_gyr.max(0.001)).powf(0.35);
 
-    let spectral_class = generate_spectral_class();
-    let spectral_subclass = rng.gen_range(0..=9);
+    let hydrogen_atmosphere = rng.gen::<f64>() < 0.75;
+    let spectral_class = if temperature_k >= 45000.0 {
+        SpectralClass::DO
+    } else if hydrogen_atmosphere {
+        SpectralClass::DA
+    } else if temperature_k >= 11000.0 {
+        SpectralClass::DB
+    } else {
+        // Cool helium atmospheres show no lines unless polluted
+        let roll = rng.gen::<f64>();
+        if roll < 0.6 {
+            SpectralClass::DC
+        } else if roll < 0.75 {
+            SpectralClass::DQ
+        } else {
+            SpectralClass::DZ
+        }
+    };
 
-    let (base_temp, base_mass, base_radius, base_lum) = spectral_class.typical_properties();
+    let radius_solar = white_dwarf_radius(mass_solar);
+    let subclass = ((50400.0 / temperature_k).round() as i32).clamp(0, 9);
 
-    // Add some variation (±10%) to make stars within same class unique
-    let mut variation = || 1.0 + rng.gen_range(-0.1..0.1);
+    StarProperties {
+        spectral_class,
+        spectral_subclass: Some(subclass),
+        luminosity_class: None,
+        evolutionary_stage: EvolutionaryStage::WhiteDwarf,
+        temperature_k,
+        mass_solar,
+        radius_solar,
+        luminosity_solar: stefan_boltzmann_luminosity(radius_solar, temperature_k),
+    }
+}
+
+/// Brown dwarfs (L, T and Y)
+/// Brown dwarfs never stop cooling; temperature follows the Burrows &
+/// Liebert scaling T ≈ 1550 K (t / Gyr)^-0.32 (M / 0.05 M☉)^0.83
+pub fn brown_dwarf_properties(mass_solar: f64, age_gyr: f64) -> StarProperties {
+    let mass_solar = mass_solar.clamp(MIN_BROWN_DWARF_MASS, MIN_STELLAR_MASS);
+    let temperature_k =
+        (1550.0 * age_gyr.max(0.01).powf(-0.32) * (mass_solar / 0.05).powf(0.83)).clamp(250.0, 2399.0);
+    let (spectral_class, spectral_subclass) = SpectralClass::from_temperature(temperature_k);
 
     StarProperties {
         spectral_class,
-        spectral_subclass,
-        temperature_k: base_temp * variation(),
-        mass_solar: base_mass * variation(),
-        radius_solar: base_radius * variation(),
-        luminosity_solar: base_lum * variation(),
+        spectral_subclass: Some(spectral_subclass),
+        luminosity_class: Some(LuminosityClass::V),
+        evolutionary_stage: EvolutionaryStage::BrownDwarf,
+        temperature_k,
+        mass_solar,
+        radius_solar: BROWN_DWARF_RADIUS_SOLAR,
+        luminosity_solar: stefan_boltzmann_luminosity(BROWN_DWARF_RADIUS_SOLAR, temperature_k),
+    }
+}
+
+/// Neutron stars: treated as dark remnants
+pub fn neutron_star_properties<R: Rng + ?Sized>(rng: &mut R) -> StarProperties {
+    StarProperties {
+        spectral_class: SpectralClass::NS,
+        spectral_subclass: None,
+        luminosity_class: None,
+        evolutionary_stage: EvolutionaryStage::NeutronStar,
+        temperature_k: 0.0,
+        mass_solar: (1.4 + 0.15 * standard_normal(rng)).clamp(1.1, 2.2),
+        radius_solar: NEUTRON_STAR_RADIUS_KM / SUN_RADIUS_KM,
+        luminosity_solar: 0.0,
+    }
+}
+
+/// Stellar-mass black holes; the radius is the Schwarzschild radius
+pub fn black_hole_properties(mass_solar: f64) -> StarProperties {
+    StarProperties {
+        spectral_class: SpectralClass::BH,
+        spectral_subclass: None,
+        luminosity_class: None,
+        evolutionary_stage: EvolutionaryStage::BlackHole,
+        temperature_k: 0.0,
+        mass_solar,
+        radius_solar: 2.953 * mass_solar / SUN_RADIUS_KM,
+        luminosity_solar: 0.0,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::seeded_rng;
+
+    fn generate_star_properties<R: Rng + ?Sized>(rng: &mut R, imf: Imf, age_gyr: f64) -> StarProperties {
+        let initial_mass = sample_initial_mass(rng, imf);
+        star_from_initial_mass(rng, initial_mass, age_gyr)
+    }
 
     #[test]
     fn test_spectral_class_distribution() {
+        let mut rng = seeded_rng(7);
         let mut counts = std::collections::HashMap::new();
 
         // Generate 10,000 stars
         for _ in 0..10000 {
-            let class = generate_spectral_class();
+            let class = generate_star_properties(&mut rng, Imf::Kroupa, 5.0).spectral_class;
             *counts.entry(class).or_insert(0) += 1;
         }
 
-        // M stars should be roughly 20x more common than O stars
+        // M stars should be far more common than O stars
         let m_count = counts.get(&SpectralClass::M).unwrap_or(&0);
         let o_count = counts.get(&SpectralClass::O).unwrap_or(&0);
 
-        println!("M stars: {}, O stars: {}, ratio: {:.1}", m_count, o_count, *m_count as f64 / *o_count as f64);
+        println!("M stars: {}, O stars: {}", m_count, o_count);
 
-        // Allow some variance (15x to 25x)
         assert!(*m_count > *o_count * 15, "M stars should be much more common");
+        assert!(*m_count > 4000, "M dwarfs should dominate a Kroupa IMF");
     }
 
     #[test]
     fn test_star_properties() {
-        let props = generate_star_properties();
+        let mut rng = seeded_rng(7);
+        for i in 0..5000 {
+            let props = generate_star_properties(&mut rng, Imf::Kroupa, i as f64 * 0.0026);
+
+            // Mass and radius should be positive
+            assert!(props.mass_solar > 0.0);
+            assert!(props.radius_solar > 0.0);
+
+            // Subclass should be 0-9, and absent only for remnants
+            match props.spectral_subclass {
+                Some(subclass) => assert!((0..=9).contains(&subclass)),
+                None => assert!(matches!(props.spectral_class, SpectralClass::NS | SpectralClass::BH)),
+            }
+
+            // Temperature should sit inside its class
+            if let Some((min_t, max_t)) = props.spectral_class.temperature_range() {
+                assert!(props.temperature_k >= min_t && props.temperature_k <= max_t);
+            }
+        }
+    }
 
-        // Temperature should be in valid range
-        assert!(props.temperature_k >= 2400.0 && props.temperature_k <= 50000.0);
+    #[test]
+    fn test_sun_is_g2() {
+        let sun = main_sequence_properties(1.0);
+        assert_eq!(sun.spectral_class, SpectralClass::G);
+        assert_eq!(sun.spectral_subclass, Some(2));
+        assert_eq!(sun.luminosity_class, Some(LuminosityClass::V));
+        assert!((sun.temperature_k - SUN_TEMPERATURE_K).abs() < 1.0);
+    }
 
-        // Mass should be positive
-        assert!(props.mass_solar > 0.0);
+    #[test]
+    fn test_main_sequence_mass_inverts_luminosity() {
+        for mass in [0.1, 0.43, 0.8, 1.0, 2.0, 10.0, 55.0, 90.0] {
+            let back = main_sequence_mass(main_sequence_luminosity(mass));
+            assert!((back / mass - 1.0).abs() < 1e-9, "{} M☉ came back as {}", mass, back);
+        }
+    }
+
+    #[test]
+    fn test_properties_are_consistent() {
+        let mut rng = seeded_rng(8);
+        for i in 0..5000 {
+            let p = generate_star_properties(&mut rng, Imf::Chabrier, i as f64 * 0.0026);
+            if p.temperature_k == 0.0 {
+                assert_eq!(p.luminosity_solar, 0.0);
+                continue;
+            }
+            let l = stefan_boltzmann_luminosity(p.radius_solar, p.temperature_k);
+            assert!((l / p.luminosity_solar - 1.0).abs() < 1e-9, "{:?}", p);
+        }
+
+        // Masses cool enough to be clamped, and the hottest main-sequence star
+        for mass in [0.02, 0.03, 55.0] {
+            let p = main_sequence_properties(mass);
+            if mass < 0.05 {
+                assert_eq!(p.temperature_k, 2400.0);
+            }
+            let l = stefan_boltzmann_luminosity(p.radius_solar, p.temperature_k);
+            assert!((l / p.luminosity_solar - 1.0).abs() < 1e-9, "{:?}", p);
+        }
+    }
+
+    #[test]
+    fn test_subclass_tracks_temperature() {
+        // Lower mass means cooler, and later subclasses within a class are cooler
+        let mut previous: Option<StarProperties> = None;
+        for i in 0..200 {
+            let mass = 0.08 + i as f64 * 0.002;
+            let p = main_sequence_properties(mass);
+            if let Some(prev) = previous {
+                assert!(p.temperature_k >= prev.temperature_k);
+                if p.spectral_class == prev.spectral_class {
+                    assert!(p.spectral_subclass <= prev.spectral_subclass);
+                }
+            }
+            previous = Some(p);
+        }
+
+        let m0 = SpectralClass::from_temperature(3690.0);
+        let m5 = SpectralClass::from_temperature(3000.0);
+        assert_eq!((m0.0, m5.0), (SpectralClass::M, SpectralClass::M));
+        assert!(m5.1 > m0.1);
+    }
+
+    #[test]
+    fn test_evolved_stars_are_larger_than_dwarfs() {
+        for progress in [0.0, 0.5, 1.0] {
+            let subgiant = subgiant_properties(1.2, progress);
+            assert_eq!(subgiant.luminosity_class, Some(LuminosityClass::IV));
+            assert!(subgiant.radius_solar >= main_sequence_radius(1.2) * 0.99);
+
+            let giant = giant_properties(1.2, progress);
+            assert_eq!(giant.luminosity_class, Some(LuminosityClass::III));
+            assert!(giant.radius_solar > 4.0, "giant radius {}", giant.radius_solar);
+
+            let bright = giant_properties(4.0, progress);
+            assert_eq!(bright.luminosity_class, Some(LuminosityClass::II));
+
+            let supergiant = supergiant_properties(30.0, progress);
+            assert_eq!(supergiant.luminosity_class, Some(LuminosityClass::Ia));
+            assert!(supergiant.radius_solar > main_sequence_radius(30.0));
+        }
+    }
+
+    #[test]
+    fn test_degenerate_objects() {
+        let mut rng = seeded_rng(11);
+        for i in 0..500 {
+            let wd = white_dwarf_properties(&mut rng, 0.6, i as f64 * 0.02);
+            assert!(wd.spectral_class.code().starts_with('D'));
+            assert!(wd.radius_solar < 0.03);
+            assert!(wd.luminosity_class.is_none());
+
+            let bd = brown_dwarf_properties(0.013 + i as f64 * 0.0001, i as f64 * 0.02);
+            assert!(matches!(bd.spectral_class, SpectralClass::L | SpectralClass::T | SpectralClass::Y), "{:?}", bd);
+            assert!(bd.mass_solar < 0.08);
+
+            for r in [neutron_star_properties(&mut rng), black_hole_properties(10.0)] {
+                assert_eq!(r.luminosity_solar, 0.0);
+                assert!(r.spectral_subclass.is_none() && r.luminosity_class.is_none());
+            }
+        }
 
-        // Subclass should be 0-9
-        assert!(props.spectral_subclass >= 0 && props.spectral_subclass <= 9);
+        // More massive white dwarfs are smaller, and white dwarfs cool with age
+        assert!(white_dwarf_radius(1.2) < white_dwarf_radius(0.6));
+        let young = white_dwarf_properties(&mut rng, 0.6, 0.01);
+        let old = white_dwarf_properties(&mut rng, 0.6, 8.0);
+        assert!(young.temperature_k > old.temperature_k);
     }
 }
@@ -0,0 +1,427 @@
+use rand::Rng;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use uuid::Uuid;
+use crate::models::{Faction, Lane, PartitionMethod, Position3D, StarSystem};
+use crate::spatial::{shortest_distances, KdTree, TravelGraph};
+use super::lanes::neighbour_pairs;
+
+/// Neighbours each system counts as adjacent when a project has no lanes
+const NEIGHBOURS_WITHOUT_LANES: usize = 6;
+
+/// Spread of the random delay on each jump of an expansion, as a fraction
+/// either side of the jump's length
+const EXPANSION_JITTER: f64 = 0.5;
+
+/// A faction to found: its capital and relative strength
+#[derive(Debug, Clone)]
+pub struct CapitalSeed {
+    pub name: String,
+    /// Index of the capital in the project's systems
+    pub capital: usize,
+    pub strength: f64,
+}
+
+/// How territory is divided
+#[derive(Debug, Clone, Copy)]
+pub struct TerritoryParams {
+    pub method: PartitionMethod,
+    /// Farthest a faction of strength 1 claims; stronger ones reach
+    /// proportionally further. Unlimited when None
+    pub max_claim_ly: Option<f64>,
+    /// A rival claim within this fraction of the owner's contests a system
+    pub contested_margin: f64,
+}
+
+/// Factions and the project's systems with ownership filled in
+#[derive(Debug, Clone)]
+pub struct Territories {
+    pub factions: Vec<Faction>,
+    pub systems: Vec<StarSystem>,
+}
+
+/// `count` capitals spread over the map: the first at random, each next one
+/// drawn with odds rising with the square of its distance to the nearest
+/// capital so far. Strengths are drawn between 0.5 and 2
+pub fn seed_capitals<R: Rng + ?Sized>(rng: &mut R, systems: &[StarSystem], count: usize) -> Vec<CapitalSeed> {
+    let positions = positions(systems);
+    let mut capitals: Vec<usize> = Vec::new();
+    let mut nearest = vec![f64::INFINITY; positions.len()];
+
+    while capitals.len() < count.min(positions.len()) {
+        let total: f64 = nearest.iter().filter(|d| d.is_finite()).map(|d| d * d).sum();
+        let capital = if capitals.is_empty() || total <= 0.0 {
+            rng.gen_range(0..positions.len())
+        } else {
+            let mut pick = rng.gen_range(0.0..total);
+            let mut chosen = positions.len() - 1;
+            for (i, d) in nearest.iter().enumerate() {
+                pick -= d * d;
+                if pick < 0.0 {
+                    chosen = i;
+                    break;
+                }
+            }
+            chosen
+        };
+        if capitals.contains(&capital) {
+            continue;
+        }
+
+        for (i, position) in positions.iter().enumerate() {
+            nearest[i] = nearest[i].min(distance(position, &positions[capital]));
+        }
+        capitals.push(capital);
+    }
+
+    capitals
+        .into_iter()
+        .enumerate()
+        .map(|(i, capital)| CapitalSeed {
+            name: format!("Faction {}", i + 1),
+            capital,
+            strength: rng.gen_range(0.5..2.0),
+        })
+        .collect()
+}
+
+/// Divide systems among factions
+/// Weighted Voronoi and lane flood give each system to the faction with the
+/// smallest distance over strength, measured straight or along lanes. An
+/// expansion lets every faction settle outwards one jump at a time, each jump
+/// taking its length over the faction's strength with a random delay, and a
+/// settled system blocks the others. A system is contested when a second
+/// faction's claim comes within the margin of the owner's, and a border
+/// system when it is adjacent to a system held by anyone else; adjacency
+/// follows the lanes, or each system's nearest neighbours without them
+pub fn partition_territories<R: Rng + ?Sized>(
+    rng: &mut R,
+    project_id: Uuid,
+    systems: &[StarSystem],
+    lanes: &[Lane],
+    capitals: &[CapitalSeed],
+    params: &TerritoryParams,
+) -> Result<Territories, String> {
+    if capitals.is_empty() {
+        return Err("At least one capital is required".to_string());
+    }
+    for (i, seed) in capitals.iter().enumerate() {
+        if capitals[..i].iter().any(|other| other.capital == seed.capital) {
+            return Err(format!("{} is the capital of more than one faction", systems[seed.capital].name));
+        }
+    }
+    if params.method == PartitionMethod::LaneFlood && lanes.is_empty() {
+        return Err("lane_flood needs lanes; generate them first".to_string());
+    }
+
+    let positions = positions(systems);
+    let graph = adjacency(systems, &positions, lanes);
+    let reach = |f: usize, claim: f64| params.max_claim_ly.is_none_or(|max| claim <= max * capitals[f].strength);
+
+    let (owner, rival) = match params.method {
+        PartitionMethod::WeightedVoronoi | PartitionMethod::LaneFlood => {
+            // Claim of every faction on every system, smaller is stronger
+            let claims: Vec<Vec<Option<f64>>> = capitals
+                .iter()
+                .map(|seed| match params.method {
+                    PartitionMethod::WeightedVoronoi => {
+                        positions.iter().map(|p| Some(distance(p, &positions[seed.capital]))).collect()
+                    }
+                    _ => shortest_distances(&graph, &positions, seed.capital),
+                })
+                .collect();
+            strongest_claims(&claims, capitals, params.contested_margin, reach)
+        }
+        PartitionMethod::Expansion => expand(rng, &graph, &positions, capitals, params.contested_margin, reach),
+    };
+
+    let factions: Vec<Faction> = capitals
+        .iter()
+        .map(|seed| Faction {
+            id: Some(Uuid::new_v4()),
+            project_id,
+            name: seed.name.clone(),
+            capital_system_id: systems[seed.capital].id.unwrap(),
+            strength: seed.strength,
+        })
+        .collect();
+
+    let systems = systems
+        .iter()
+        .enumerate()
+        .map(|(i, system)| StarSystem {
+            faction_id: owner[i].map(|f| factions[f].id.unwrap()),
+            contested_by: rival[i].map(|f| factions[f].id.unwrap()),
+            border: owner[i].is_some() && graph.neighbours(&positions, i).iter().any(|&(j, _)| owner[j] != owner[i]),
+            ..system.clone()
+        })
+        .collect();
+
+    Ok(Territories { factions, systems })
+}
+
+/// Owner and rival of every system from each faction's distance to it
+fn strongest_claims(
+    claims: &[Vec<Option<f64>>],
+    capitals: &[CapitalSeed],
+    margin: f64,
+    reach: impl Fn(usize, f64) -> bool,
+) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+    let n = claims.first().map_or(0, |c| c.len());
+    let mut owner = vec![None; n];
+    let mut rival = vec![None; n];
+    let mut capital_of = vec![None; n];
+    for (f, seed) in capitals.iter().enumerate() {
+        capital_of[seed.capital] = Some(f);
+    }
+
+    for i in 0..n {
+        let mut ranked: Vec<(f64, usize)> = claims
+            .iter()
+            .enumerate()
+            .filter_map(|(f, c)| c[i].filter(|&d| reach(f, d)).map(|d| (d / capitals[f].strength, f)))
+            .collect();
+        ranked.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        // A capital always belongs to its own faction, whoever claims it hardest
+        if let Some(p) = capital_of[i].and_then(|c| ranked.iter().position(|&(_, f)| f == c)) {
+            let own = ranked.remove(p);
+            ranked.insert(0, own);
+        }
+
+        if let Some(&(best, f)) = ranked.first() {
+            owner[i] = Some(f);
+            rival[i] = ranked.get(1).filter(|&&(second, _)| second <= best * (1.0 + margin)).map(|&(_, r)| r);
+        }
+    }
+
+    (owner, rival)
+}
+
+/// Owner and rival of every system after every faction has settled outwards
+/// from its capital until it meets the others
+fn expand<R: Rng + ?Sized>(
+    rng: &mut R,
+    graph: &TravelGraph,
+    positions: &[Position3D],
+    capitals: &[CapitalSeed],
+    margin: f64,
+    reach: impl Fn(usize, f64) -> bool,
+) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+    let mut owner: Vec<Option<usize>> = vec![None; positions.len()];
+    let mut rival: Vec<Option<usize>> = vec![None; positions.len()];
+    let mut settled_at = vec![0.0; positions.len()];
+    let mut arrivals: BinaryHeap<Arrival> = capitals
+        .iter()
+        .enumerate()
+        .map(|(faction, seed)| Arrival { time: 0.0, distance: 0.0, faction, system: seed.capital })
+        .collect();
+
+    while let Some(Arrival { time, distance, faction, system }) = arrivals.pop() {
+        match owner[system] {
+            Some(f) if f == faction => continue,
+            Some(_) => {
+                if rival[system].is_none() && time <= settled_at[system] * (1.0 + margin) {
+                    rival[system] = Some(faction);
+                }
+                continue;
+            }
+            None => {
+                owner[system] = Some(faction);
+                settled_at[system] = time;
+            }
+        }
+
+        for (next, length) in graph.neighbours(positions, system) {
+            if owner[next].is_some() || !reach(faction, distance + length) {
+                continue;
+            }
+            let delay = length * rng.gen_range(1.0 - EXPANSION_JITTER..1.0 + EXPANSION_JITTER);
+            arrivals.push(Arrival {
+                time: time + delay / capitals[faction].strength,
+                distance: distance + length,
+                faction,
+                system: next,
+            });
+        }
+    }
+
+    (owner, rival)
+}
+
+/// Lanes as a graph, or each system linked to its nearest neighbours
+fn adjacency(systems: &[StarSystem], positions: &[Position3D], lanes: &[Lane]) -> TravelGraph<'static> {
+    if lanes.is_empty() {
+        let tree = KdTree::new(positions);
+        let pairs = neighbour_pairs(&tree, positions, NEIGHBOURS_WITHOUT_LANES);
+        return TravelGraph::from_lanes(
+            positions.len(),
+            pairs.into_iter().map(|(a, b)| (a, b, distance(&positions[a], &positions[b]))),
+        );
+    }
+
+    let by_id: std::collections::HashMap<Uuid, usize> =
+        systems.iter().enumerate().map(|(i, s)| (s.id.unwrap(), i)).collect();
+    TravelGraph::from_lanes(
+        positions.len(),
+        lanes.iter().map(|l| (by_id[&l.system_a_id], by_id[&l.system_b_id], l.length_ly)),
+    )
+}
+
+/// A faction reaching a system during an expansion, earliest on top
+#[derive(Debug, Clone, Copy)]
+struct Arrival {
+    time: f64,
+    /// Length flown from the capital
+    distance: f64,
+    faction: usize,
+    system: usize,
+}
+
+impl PartialEq for Arrival {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Arrival {}
+
+impl PartialOrd for Arrival {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Arrival {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .time
+            .total_cmp(&self.time)
+            .then(other.faction.cmp(&self.faction))
+            .then(other.system.cmp(&self.system))
+    }
+}
+
+fn positions(systems: &[StarSystem]) -> Vec<Position3D> {
+    systems.iter().map(|s| Position3D { x: s.x_ly, y: s.y_ly, z: s.z_ly }).collect()
+}
+
+fn distance(a: &Position3D, b: &Position3D) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{generate_lanes, seeded_rng, LaneParams};
+    use crate::models::{LaneRule, SystemType};
+
+    fn random_systems(n: usize, seed: u64) -> Vec<StarSystem> {
+        let mut rng = seeded_rng(seed);
+        (0..n)
+            .map(|i| StarSystem {
+                id: Some(Uuid::new_v4()),
+                project_id: Uuid::nil(),
+                name: format!("S{:04}", i + 1),
+                system_type: SystemType::Solo,
+                x_ly: rng.gen_range(-50.0..50.0),
+                y_ly: rng.gen_range(-50.0..50.0),
+                z_ly: rng.gen_range(-10.0..10.0),
+                zone_id: None,
+                faction_id: None,
+                contested_by: None,
+                border: false,
+            })
+            .collect()
+    }
+
+    fn params(method: PartitionMethod) -> TerritoryParams {
+        TerritoryParams { method, max_claim_ly: None, contested_margin: 0.1 }
+    }
+
+    fn held(territories: &Territories, faction: usize) -> usize {
+        let id = territories.factions[faction].id;
+        territories.systems.iter().filter(|s| s.faction_id == id).count()
+    }
+
+    #[test]
+    fn test_stronger_capitals_claim_more() {
+        let systems = random_systems(500, 11);
+        let capitals = vec![
+            CapitalSeed { name: "Weak".to_string(), capital: 0, strength: 1.0 },
+            CapitalSeed { name: "Strong".to_string(), capital: 1, strength: 3.0 },
+        ];
+        let mut rng = seeded_rng(1);
+
+        let voronoi = partition_territories(&mut rng, Uuid::nil(), &systems, &[], &capitals, &params(PartitionMethod::WeightedVoronoi)).unwrap();
+        assert!(voronoi.systems.iter().all(|s| s.faction_id.is_some()));
+        assert!(held(&voronoi, 1) > held(&voronoi, 0));
+        assert_eq!(voronoi.systems[0].faction_id, voronoi.factions[0].id);
+        assert!(voronoi.systems.iter().any(|s| s.border && s.faction_id == voronoi.factions[0].id));
+        assert!(voronoi.systems.iter().all(|s| s.contested_by.is_none() || s.contested_by != s.faction_id));
+
+        // A short reach leaves the outskirts unclaimed
+        let limited = TerritoryParams { max_claim_ly: Some(10.0), ..params(PartitionMethod::WeightedVoronoi) };
+        let limited = partition_territories(&mut rng, Uuid::nil(), &systems, &[], &capitals, &limited).unwrap();
+        assert!(limited.systems.iter().any(|s| s.faction_id.is_none()));
+
+        assert!(partition_territories(&mut rng, Uuid::nil(), &systems, &[], &capitals, &params(PartitionMethod::LaneFlood)).is_err());
+    }
+
+    #[test]
+    fn test_capitals_stay_with_their_faction() {
+        // The weak capital shares its position with the strong one, so the
+        // strong faction's claim on it ties and ranks first
+        let mut systems = random_systems(50, 7);
+        (systems[1].x_ly, systems[1].y_ly, systems[1].z_ly) = (systems[0].x_ly, systems[0].y_ly, systems[0].z_ly);
+        let capitals = vec![
+            CapitalSeed { name: "Strong".to_string(), capital: 0, strength: 3.0 },
+            CapitalSeed { name: "Weak".to_string(), capital: 1, strength: 1.0 },
+        ];
+
+        let territories = partition_territories(&mut seeded_rng(1), Uuid::nil(), &systems, &[], &capitals, &params(PartitionMethod::WeightedVoronoi)).unwrap();
+        let weak_capital = &territories.systems[1];
+        assert_eq!(weak_capital.faction_id, territories.factions[1].id);
+        assert_eq!(weak_capital.contested_by, territories.factions[0].id);
+        assert!(territories.systems.iter().all(|s| s.contested_by.is_none() || s.contested_by != s.faction_id));
+    }
+
+    #[test]
+    fn test_lane_flood_and_expansion_hold_connected_territory() {
+        let systems = random_systems(400, 4);
+        let lanes = generate_lanes(Uuid::nil(), &systems, &LaneParams { rule: LaneRule::Gabriel, chokepoint_regions: None }, 2).unwrap();
+        let capitals = seed_capitals(&mut seeded_rng(9), &systems, 4);
+        assert_eq!(capitals.len(), 4);
+
+        for method in [PartitionMethod::LaneFlood, PartitionMethod::Expansion] {
+            let run = |seed| partition_territories(&mut seeded_rng(seed), Uuid::nil(), &systems, &lanes, &capitals, &params(method)).unwrap();
+            // Faction ids are fresh each run, so compare faction positions
+            let owners = |t: &Territories| -> Vec<Option<usize>> {
+                t.systems.iter().map(|s| t.factions.iter().position(|f| f.id == s.faction_id)).collect()
+            };
+            let territories = run(3);
+            assert_eq!(owners(&territories), owners(&run(3)));
+
+            // Every expansion territory is one piece around its capital
+            let positions = positions(&systems);
+            let graph = adjacency(&systems, &positions, &lanes);
+            for (f, seed) in capitals.iter().enumerate() {
+                let id = territories.factions[f].id;
+                assert_eq!(territories.systems[seed.capital].faction_id, id);
+                if method == PartitionMethod::Expansion {
+                    let mut seen = vec![false; systems.len()];
+                    let mut stack = vec![seed.capital];
+                    seen[seed.capital] = true;
+                    while let Some(i) = stack.pop() {
+                        for (j, _) in graph.neighbours(&positions, i) {
+                            if !seen[j] && territories.systems[j].faction_id == id {
+                                seen[j] = true;
+                                stack.push(j);
+                            }
+                        }
+                    }
+                    assert_eq!(seen.iter().filter(|&&s| s).count(), held(&territories, f));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Fixtures shared by the generator tests
+
+use uuid::Uuid;
+use crate::models::{DistributionType, Imf, Star, StarFormationHistory};
+use super::{main_sequence_properties, GenerationParams};
+
+/// Main-sequence star of `mass` with the Sun's age and composition, named
+/// "S0001" and belonging to no particular system or project
+pub fn sun_like_star(mass: f64) -> Star {
+    let p = main_sequence_properties(mass);
+    Star {
+        id: Some(Uuid::new_v4()),
+        system_id: Uuid::nil(),
+        project_id: Uuid::nil(),
+        name: "S0001".to_string(),
+        component: String::new(),
+        spectral_class: p.spectral_class,
+        spectral_subclass: p.spectral_subclass,
+        luminosity_class: p.luminosity_class,
+        temperature_k: p.temperature_k,
+        mass_solar: p.mass_solar,
+        radius_solar: p.radius_solar,
+        luminosity_solar: p.luminosity_solar,
+        age_gyr: 4.6,
+        metallicity_feh: 0.0,
+        evolutionary_stage: p.evolutionary_stage,
+        habitable_zone: None,
+        photometry: None,
+    }
+}
+
+/// Uniform sphere of `num_systems` with a Kroupa IMF and constant star
+/// formation, no zones and no minimum separation
+pub fn sphere_params(num_systems: usize, radius: f64, seed: u64) -> GenerationParams {
+    GenerationParams {
+        num_systems,
+        distribution: DistributionType::Sphere { radius },
+        zones: Vec::new(),
+        min_separation_ly: None,
+        imf: Imf::Kroupa,
+        star_formation: StarFormationHistory::Constant,
+        seed,
+    }
+}
@@ -0,0 +1,191 @@
+use rand::Rng;
+use crate::models::{Axis, DensityZone, Position3D, ZoneType};
+use super::{generate_sphere_position, standard_normal};
+
+/// Plummer scale radius as a fraction of a globular cluster's zone radius
+const PLUMMER_SCALE_FRACTION: f64 = 0.2;
+
+/// Gaussian sigma as a fraction of an open cluster's zone radius
+const OPEN_CLUSTER_SIGMA_FRACTION: f64 = 0.5;
+
+/// Number of star systems each zone receives from a budget of `num_systems`
+/// Shares are rounded down, so the zones never take more than the budget
+pub fn zone_system_counts(zones: &[DensityZone], num_systems: usize) -> Vec<usize> {
+    zones
+        .iter()
+        .map(|zone| match zone.zone_type {
+            ZoneType::Void => 0,
+            _ => (zone.star_share * num_systems as f64).floor() as usize,
+        })
+        .collect()
+}
+
+/// Generate a random position inside a (non-void) density zone
+pub fn generate_zone_position<R: Rng + ?Sized>(rng: &mut R, zone: &DensityZone) -> Position3D {
+    let offset = match zone.zone_type {
+        ZoneType::OpenCluster => open_cluster_offset(rng, zone.radius_ly),
+        ZoneType::GlobularCluster => plummer_offset(rng, zone.radius_ly),
+        ZoneType::Gradient => gradient_offset(
+            rng,
+            zone.radius_ly,
+            zone.gradient_axis.unwrap_or(Axis::X),
+            zone.gradient_strength.unwrap_or(0.0),
+        ),
+        // Voids hold no stars; fall back to their centre
+        ZoneType::Void => Position3D { x: 0.0, y: 0.0, z: 0.0 },
+    };
+
+    Position3D {
+        x: zone.center_x_ly + offset.x,
+        y: zone.center_y_ly + offset.y,
+        z: zone.center_z_ly + offset.z,
+    }
+}
+
+/// Whether a position falls inside any void zone
+pub fn in_void(zones: &[DensityZone], pos: &Position3D) -> bool {
+    zones.iter().any(|zone| {
+        zone.zone_type == ZoneType::Void
+            && (pos.x - zone.center_x_ly).powi(2)
+                + (pos.y - zone.center_y_ly).powi(2)
+                + (pos.z - zone.center_z_ly).powi(2)
+                <= zone.radius_ly.powi(2)
+    })
+}
+
+/// Gaussian blob, truncated at the zone radius
+fn open_cluster_offset<R: Rng + ?Sized>(rng: &mut R, radius: f64) -> Position3D {
+    let sigma = radius * OPEN_CLUSTER_SIGMA_FRACTION;
+    loop {
+        let pos = Position3D {
+            x: standard_normal(rng) * sigma,
+            y: standard_normal(rng) * sigma,
+            z: standard_normal(rng) * sigma,
+        };
+        if pos.x.powi(2) + pos.y.powi(2) + pos.z.powi(2) <= radius.powi(2) {
+            return pos;
+        }
+    }
+}
+
+/// Plummer sphere, truncated at the zone radius
+fn plummer_offset<R: Rng + ?Sized>(rng: &mut R, radius: f64) -> Position3D {
+    let a = radius * PLUMMER_SCALE_FRACTION;
+
+    // Cumulative mass M(<r) = r^3 / (r^2 + a^2)^(3/2); invert it for a
+    // uniform draw below M(radius)
+    let m_max = radius.powi(3) / (radius.powi(2) + a.powi(2)).powf(1.5);
+    let m = (rng.gen::<f64>() * m_max).max(f64::MIN_POSITIVE);
+    let r = a / (m.powf(-2.0 / 3.0) - 1.0).sqrt();
+
+    let direction = generate_sphere_position(rng, 1.0);
+    let length = (direction.x.powi(2) + direction.y.powi(2) + direction.z.powi(2)).sqrt();
+    if length == 0.0 {
+        return Position3D { x: 0.0, y: 0.0, z: r };
+    }
+
+    Position3D {
+        x: direction.x / length * r,
+        y: direction.y / length * r,
+        z: direction.z / length * r,
+    }
+}
+
+/// Uniform sphere thinned so density rises linearly along `axis`
+/// from (1 - strength) on one side to (1 + strength) on the other
+fn gradient_offset<R: Rng + ?Sized>(rng: &mut R, radius: f64, axis: Axis, strength: f64) -> Position3D {
+    loop {
+        let pos = generate_sphere_position(rng, radius);
+        let t = match axis {
+            Axis::X => pos.x,
+            Axis::Y => pos.y,
+            Axis::Z => pos.z,
+        } / radius;
+
+        let accept = (1.0 + strength * t) / (1.0 + strength.abs());
+        if rng.gen::<f64>() < accept {
+            return pos;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::seeded_rng;
+    use uuid::Uuid;
+
+    fn zone(zone_type: ZoneType, share: f64) -> DensityZone {
+        DensityZone {
+            id: Some(Uuid::new_v4()),
+            project_id: Uuid::new_v4(),
+            zone_index: 0,
+            zone_type,
+            center_x_ly: 100.0,
+            center_y_ly: -50.0,
+            center_z_ly: 0.0,
+            radius_ly: 20.0,
+            star_share: share,
+            gradient_axis: Some(Axis::X),
+            gradient_strength: Some(1.0),
+        }
+    }
+
+    #[test]
+    fn test_zone_counts_never_exceed_budget() {
+        let zones = vec![
+            zone(ZoneType::OpenCluster, 0.333),
+            zone(ZoneType::GlobularCluster, 0.333),
+            zone(ZoneType::Void, 0.0),
+            zone(ZoneType::Gradient, 0.334),
+        ];
+        let counts = zone_system_counts(&zones, 100);
+        assert_eq!(counts, vec![33, 33, 0, 33]);
+        assert!(counts.iter().sum::<usize>() <= 100);
+    }
+
+    #[test]
+    fn test_cluster_positions_stay_in_zone() {
+        let mut rng = seeded_rng(1);
+        for zone_type in [ZoneType::OpenCluster, ZoneType::GlobularCluster, ZoneType::Gradient] {
+            let z = zone(zone_type, 0.5);
+            for _ in 0..500 {
+                let pos = generate_zone_position(&mut rng, &z);
+                let d = ((pos.x - 100.0).powi(2) + (pos.y + 50.0).powi(2) + pos.z.powi(2)).sqrt();
+                assert!(d <= 20.0 + 1e-9, "{} placed a star {} ly from centre", zone_type, d);
+            }
+        }
+    }
+
+    #[test]
+    fn test_globular_is_centrally_concentrated() {
+        let mut rng = seeded_rng(2);
+        let z = zone(ZoneType::GlobularCluster, 1.0);
+        let inner = (0..2000)
+            .map(|_| generate_zone_position(&mut rng, &z))
+            .filter(|p| ((p.x - 100.0).powi(2) + (p.y + 50.0).powi(2) + p.z.powi(2)).sqrt() < 5.0)
+            .count();
+
+        // The inner quarter of the radius is 1/64 of the volume but holds
+        // most of a Plummer sphere with a = 0.2 r
+        assert!(inner > 800, "Only {} of 2000 stars in the core", inner);
+    }
+
+    #[test]
+    fn test_gradient_favours_positive_side() {
+        let mut rng = seeded_rng(3);
+        let z = zone(ZoneType::Gradient, 1.0);
+        let positive = (0..2000)
+            .map(|_| generate_zone_position(&mut rng, &z))
+            .filter(|p| p.x > 100.0)
+            .count();
+        assert!(positive > 1300, "Only {} of 2000 stars on the dense side", positive);
+    }
+
+    #[test]
+    fn test_in_void() {
+        let zones = vec![zone(ZoneType::Void, 0.0)];
+        assert!(in_void(&zones, &Position3D { x: 100.0, y: -50.0, z: 10.0 }));
+        assert!(!in_void(&zones, &Position3D { x: 0.0, y: 0.0, z: 0.0 }));
+    }
+}
@@ -6,8 +6,10 @@ mod api;
 mod models;
 mod database;
 mod generator;
+mod spatial;
 
 use crate::database::init_pool;
+use crate::spatial::SpatialCache;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -30,6 +32,9 @@ async fn main() -> std::io::Result<()> {
     let pool = init_pool().await.expect("Failed to create database pool");
     log::info!("Database connection pool initialized");
 
+    // Spatial indexes are shared by all workers
+    let spatial_cache = web::Data::new(SpatialCache::default());
+
     // Create HTTP server
     log::info!("Starting HTTP server at {}:{}", host, port);
 
@@ -43,6 +48,7 @@ async fn main() -> std::io::Result<()> {
 
         App::new()
             .app_data(web::Data::new(pool.clone()))
+            .app_data(spatial_cache.clone())
             .wrap(middleware::Logger::default())
             .wrap(cors)
             .configure(api::configure_routes)
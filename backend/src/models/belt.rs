@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// A belt of small bodies around one star
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Belt {
+    pub id: Option<Uuid>,
+    pub star_id: Uuid,
+    pub system_id: Uuid,
+    pub project_id: Uuid,
+    /// Host star name plus the belt kind, e.g. "S0001a Asteroid Belt"
+    pub name: String,
+    pub belt_type: BeltType,
+    pub inner_radius_au: f64,
+    pub outer_radius_au: f64,
+    /// Total mass in Earth masses
+    pub mass_earth: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum BeltType {
+    /// Rocky leftovers inside the snow line, held apart by a giant planet
+    AsteroidBelt,
+    /// Icy debris beyond the outermost planet
+    KuiperBelt,
+}
+
+impl BeltType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "asteroid_belt" => Some(BeltType::AsteroidBelt),
+            "kuiper_belt" => Some(BeltType::KuiperBelt),
+            _ => None,
+        }
+    }
+
+    /// Title used in belt names
+    pub fn title(&self) -> &'static str {
+        match self {
+            BeltType::AsteroidBelt => "Asteroid Belt",
+            BeltType::KuiperBelt => "Kuiper Belt",
+        }
+    }
+}
+
+impl fmt::Display for BeltType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BeltType::AsteroidBelt => write!(f, "asteroid_belt"),
+            BeltType::KuiperBelt => write!(f, "kuiper_belt"),
+        }
+    }
+}
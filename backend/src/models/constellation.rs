@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A figure of bright stars in the sky of one observer system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Constellation {
+    pub id: Option<Uuid>,
+    pub project_id: Uuid,
+    /// System whose sky the figure belongs to
+    pub observer_system_id: Uuid,
+    /// Generated name, e.g. "Serrath"
+    pub name: String,
+    /// Seed the observer's constellations were drawn with
+    pub seed: u64,
+    /// Brightest first
+    pub stars: Vec<ConstellationStar>,
+}
+
+/// One star of a constellation figure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstellationStar {
+    pub star_id: Uuid,
+    /// 1 = brightest star of the constellation
+    pub bayer_index: i32,
+    /// Bayer-style name, e.g. "Gamma Serrath"
+    pub designation: String,
+    /// Star this one is joined to by a figure line (None for the first star)
+    pub line_to_star_id: Option<Uuid>,
+}
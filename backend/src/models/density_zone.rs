@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use std::fmt;
+
+/// A region inside the distribution volume with its own star placement rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DensityZone {
+    pub id: Option<Uuid>,
+    pub project_id: Uuid,
+    pub zone_index: i32,
+    pub zone_type: ZoneType,
+    pub center_x_ly: f64,
+    pub center_y_ly: f64,
+    pub center_z_ly: f64,
+    pub radius_ly: f64,
+    /// Fraction of the project's star systems placed in this zone (0 for voids)
+    pub star_share: f64,
+    /// Gradient zones only: axis the density varies along
+    pub gradient_axis: Option<Axis>,
+    /// Gradient zones only: -1..1, density goes from (1 - s) to (1 + s) along the axis
+    pub gradient_strength: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneType {
+    /// Gaussian blob
+    OpenCluster,
+    /// Plummer profile
+    GlobularCluster,
+    /// Empty region; background stars are kept out
+    Void,
+    /// Linear density gradient along an axis
+    Gradient,
+}
+
+impl ZoneType {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "open_cluster" => Some(ZoneType::OpenCluster),
+            "globular_cluster" => Some(ZoneType::GlobularCluster),
+            "void" => Some(ZoneType::Void),
+            "gradient" => Some(ZoneType::Gradient),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ZoneType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZoneType::OpenCluster => write!(f, "open_cluster"),
+            ZoneType::GlobularCluster => write!(f, "globular_cluster"),
+            ZoneType::Void => write!(f, "void"),
+            ZoneType::Gradient => write!(f, "gradient"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "x" => Some(Axis::X),
+            "y" => Some(Axis::Y),
+            "z" => Some(Axis::Z),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Axis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Axis::X => write!(f, "x"),
+            Axis::Y => write!(f, "y"),
+            Axis::Z => write!(f, "z"),
+        }
+    }
+}
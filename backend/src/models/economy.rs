@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Goods a system produces, consumes and trades
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Resource {
+    /// Grown on habitable, watery worlds
+    Food,
+    /// Mined from belts and rocky planets, richer around metal-rich stars
+    Ores,
+    /// Collected from starlight and skimmed from giant planets
+    Energy,
+    /// Manufactured by populations
+    Goods,
+}
+
+/// One amount per resource, in `Resource::ALL` order
+pub type Amounts = [f64; Resource::COUNT];
+
+impl Resource {
+    pub const COUNT: usize = 4;
+    pub const ALL: [Resource; Resource::COUNT] = [Resource::Food, Resource::Ores, Resource::Energy, Resource::Goods];
+
+    /// Position in `Resource::ALL` and in `Amounts`
+    pub fn index(&self) -> usize {
+        match self {
+            Resource::Food => 0,
+            Resource::Ores => 1,
+            Resource::Energy => 2,
+            Resource::Goods => 3,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "food" => Some(Resource::Food),
+            "ores" => Some(Resource::Ores),
+            "energy" => Some(Resource::Energy),
+            "goods" => Some(Resource::Goods),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Resource::Food => write!(f, "food"),
+            Resource::Ores => write!(f, "ores"),
+            Resource::Energy => write!(f, "energy"),
+            Resource::Goods => write!(f, "goods"),
+        }
+    }
+}
+
+/// How goods travel between systems
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeNetwork {
+    /// Along stored lanes, through the systems in between
+    Lanes,
+    /// Directly from seller to buyer
+    StraightLine,
+}
+
+impl TradeNetwork {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "lanes" => Some(TradeNetwork::Lanes),
+            "straight_line" => Some(TradeNetwork::StraightLine),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TradeNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TradeNetwork::Lanes => write!(f, "lanes"),
+            TradeNetwork::StraightLine => write!(f, "straight_line"),
+        }
+    }
+}
+
+/// Settings and clock of a project's economy simulation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Economy {
+    pub project_id: Uuid,
+    /// Ticks simulated so far
+    pub tick: i32,
+    pub network: TradeNetwork,
+    /// Longest trade route; unlimited when None
+    pub max_route_ly: Option<f64>,
+}
+
+/// A system's market after the latest tick
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemMarket {
+    pub system_id: Uuid,
+    pub project_id: Uuid,
+    pub production: Amounts,
+    pub consumption: Amounts,
+    /// Stockpile carried into the next tick
+    pub stock: Amounts,
+    pub imports: Amounts,
+    pub exports: Amounts,
+    /// Volume bought, sold or carried through during the latest tick
+    pub throughput: f64,
+}
+
+/// Goods moved between two systems during the latest tick: one lane for
+/// lane trade, seller to buyer for straight-line trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeFlow {
+    pub id: Option<Uuid>,
+    pub project_id: Uuid,
+    pub tick: i32,
+    pub resource: Resource,
+    pub from_system_id: Uuid,
+    pub to_system_id: Uuid,
+    pub volume: f64,
+}
+
+/// Project-wide totals of one tick
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EconomyTick {
+    pub tick: i32,
+    pub produced: f64,
+    pub consumed: f64,
+    /// Volume delivered by trade
+    pub traded: f64,
+    /// Demand left unmet
+    pub shortage: f64,
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// A political power holding territory around its capital system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Faction {
+    pub id: Option<Uuid>,
+    pub project_id: Uuid,
+    pub name: String,
+    pub capital_system_id: Uuid,
+    /// Relative reach; a faction twice as strong claims twice as far
+    pub strength: f64,
+}
+
+/// How systems are divided among factions
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PartitionMethod {
+    /// Straight-line distance to each capital divided by its strength
+    WeightedVoronoi,
+    /// Distance along lanes to each capital divided by its strength
+    LaneFlood,
+    /// Factions settle outwards jump by jump until they meet
+    Expansion,
+}
+
+impl PartitionMethod {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "weighted_voronoi" => Some(PartitionMethod::WeightedVoronoi),
+            "lane_flood" => Some(PartitionMethod::LaneFlood),
+            "expansion" => Some(PartitionMethod::Expansion),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PartitionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartitionMethod::WeightedVoronoi => write!(f, "weighted_voronoi"),
+            PartitionMethod::LaneFlood => write!(f, "lane_flood"),
+            PartitionMethod::Expansion => write!(f, "expansion"),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// A travel lane between two star systems, usable in either direction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lane {
+    pub id: Option<Uuid>,
+    pub project_id: Uuid,
+    pub system_a_id: Uuid,
+    pub system_b_id: Uuid,
+    pub length_ly: f64,
+    /// Lane joining two chokepoint regions
+    pub chokepoint: bool,
+}
+
+/// Rule deciding which systems a lane network links directly
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LaneRule {
+    /// Every pair of systems closer than the range
+    MaxJump { range_ly: f64 },
+    /// Each system to its k nearest neighbours
+    KNearest { k: usize },
+    /// Delaunay edges whose diametral sphere holds no other system
+    Gabriel,
+    /// Pairs with no third system closer to both than they are to each other
+    RelativeNeighbourhood,
+}
+
+impl fmt::Display for LaneRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaneRule::MaxJump { .. } => write!(f, "max_jump"),
+            LaneRule::KNearest { .. } => write!(f, "k_nearest"),
+            LaneRule::Gabriel => write!(f, "gabriel"),
+            LaneRule::RelativeNeighbourhood => write!(f, "relative_neighbourhood"),
+        }
+    }
+}
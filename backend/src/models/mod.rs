@@ -1,11 +1,31 @@
 pub mod project;
 pub mod star_system;
 pub mod star;
+pub mod density_zone;
+pub mod orbit;
+pub mod planet;
+pub mod moon;
+pub mod belt;
+pub mod sky;
+pub mod constellation;
+pub mod lane;
+pub mod faction;
+pub mod economy;
 pub mod request;
 pub mod response;
 
-pub use project::{Project, DistributionType};
+pub use project::{Project, DistributionType, Imf, StarFormationHistory, CatalogueFormat};
 pub use star_system::{StarSystem, SystemType, Position3D};
-pub use star::{Star, SpectralClass, StarProperties};
+pub use star::{Star, SpectralClass, LuminosityClass, EvolutionaryStage, StarProperties, HabitableZone, Photometry};
+pub use density_zone::{DensityZone, ZoneType, Axis};
+pub use orbit::Orbit;
+pub use planet::{Planet, PlanetType, WorldProfile, Atmosphere, Climate};
+pub use moon::{Moon, Ring};
+pub use belt::{Belt, BeltType};
+pub use sky::{SkyFrame, SkyProjection, SkyStar};
+pub use constellation::{Constellation, ConstellationStar};
+pub use lane::{Lane, LaneRule};
+pub use faction::{Faction, PartitionMethod};
+pub use economy::{Resource, Amounts, TradeNetwork, Economy, SystemMarket, TradeFlow, EconomyTick};
 pub use request::*;
 pub use response::*;
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A natural satellite of a planet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Moon {
+    pub id: Option<Uuid>,
+    pub planet_id: Uuid,
+    pub star_id: Uuid,
+    pub system_id: Uuid,
+    pub project_id: Uuid,
+    /// Planet name plus a letter counted outwards, e.g. "S0001 III a"
+    pub name: String,
+    /// 1 = innermost moon of its planet
+    pub orbit_index: i32,
+    pub semi_major_axis_km: f64,
+    pub eccentricity: f64,
+    pub period_days: f64,
+    /// Mass in Earth masses
+    pub mass_earth: f64,
+    pub radius_km: f64,
+}
+
+/// Ring system of a planet, measured from the planet's centre
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Ring {
+    pub inner_radius_km: f64,
+    pub outer_radius_km: f64,
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Keplerian orbit of two components of a multiple star system
+/// Components are star suffixes ("a", "b") or, for outer orbits, the
+/// suffixes of the inner pair they enclose ("ab")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Orbit {
+    pub id: Option<Uuid>,
+    pub system_id: Uuid,
+    pub project_id: Uuid,
+    /// Hierarchy level: 0 = innermost pair, higher levels orbit lower ones
+    pub level: i32,
+    pub primary_component: String,
+    pub secondary_component: String,
+    pub semi_major_axis_au: f64,
+    pub eccentricity: f64,
+    /// 0-180 degrees, relative to the galactic plane
+    pub inclination_deg: f64,
+    pub period_years: f64,
+    /// Secondary mass over primary mass
+    pub mass_ratio: f64,
+}
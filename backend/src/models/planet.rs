@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+use crate::models::Ring;
+
+/// A planet orbiting one star of a system, or the barycentre of a close
+/// pair or group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Planet {
+    pub id: Option<Uuid>,
+    /// Host star; the heaviest enclosed star for a circumbinary planet
+    pub star_id: Uuid,
+    pub system_id: Uuid,
+    pub project_id: Uuid,
+    /// Host name plus a Roman numeral counted outwards, e.g. "S0001a III",
+    /// or "S0001ab I" around the barycentre of "a" and "b"
+    pub name: String,
+    /// 1 = innermost planet of its star or barycentre
+    pub orbit_index: i32,
+    /// Component orbit whose barycentre the planet circles (P-type);
+    /// None when it orbits `star_id` alone (S-type)
+    pub barycentre_orbit_id: Option<Uuid>,
+    pub planet_type: PlanetType,
+    pub semi_major_axis_au: f64,
+    pub eccentricity: f64,
+    pub period_years: f64,
+    /// Mass in Earth masses
+    pub mass_earth: f64,
+    /// Radius in Earth radii
+    pub radius_earth: f64,
+    pub ring: Option<Ring>,
+    /// Surface, atmosphere and climate (filled in with the habitability)
+    pub profile: WorldProfile,
+    /// Orbit-averaged stellar flux from every star in the system, relative
+    /// to Earth's
+    pub insolation_earth: f64,
+    /// 0 (outside the optimistic habitable zone, or not a terrestrial world)
+    /// to 1 (rocky, mid-zone, around a stable host)
+    pub habitability: f64,
+}
+
+/// Surface, atmosphere and climate of a planet, derived from its orbit and
+/// host
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct WorldProfile {
+    /// Bond albedo
+    pub albedo: f64,
+    /// Blackbody temperature from the insolation and albedo, in K
+    pub equilibrium_temperature_k: f64,
+    /// Mean surface temperature including the greenhouse effect, in K (the
+    /// 1-bar level for worlds without a surface)
+    pub surface_temperature_k: f64,
+    pub atmosphere: Atmosphere,
+    /// None for worlds without a solid surface
+    pub surface_pressure_bar: Option<f64>,
+    /// Fraction of the surface covered by water, liquid or frozen
+    pub water_fraction: f64,
+    /// Rotation synchronised with the orbit, one side always facing the host
+    pub tidally_locked: bool,
+    /// Sidereal rotation period in hours
+    pub day_length_hours: f64,
+    pub climate: Climate,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Atmosphere {
+    /// Stripped by stellar heating and wind, like Mercury
+    #[default]
+    Airless,
+    /// Under about 0.1 bar, like Mars
+    Thin,
+    /// Earth-like, around 1 bar
+    Moderate,
+    /// Runaway-greenhouse CO₂ blanket, like Venus
+    Dense,
+    /// Deep hydrogen-helium envelope: giants and mini-Neptunes
+    HydrogenEnvelope,
+}
+
+impl Atmosphere {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "airless" => Some(Atmosphere::Airless),
+            "thin" => Some(Atmosphere::Thin),
+            "moderate" => Some(Atmosphere::Moderate),
+            "dense" => Some(Atmosphere::Dense),
+            "hydrogen_envelope" => Some(Atmosphere::HydrogenEnvelope),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Atmosphere {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Atmosphere::Airless => write!(f, "airless"),
+            Atmosphere::Thin => write!(f, "thin"),
+            Atmosphere::Moderate => write!(f, "moderate"),
+            Atmosphere::Dense => write!(f, "dense"),
+            Atmosphere::HydrogenEnvelope => write!(f, "hydrogen_envelope"),
+        }
+    }
+}
+
+/// Köppen-like climate label
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Climate {
+    /// Airless rock
+    #[default]
+    Barren,
+    /// Surface above 1200 K: lava oceans
+    Molten,
+    /// Too hot for surface water (350-1200 K, runaway greenhouse)
+    Hothouse,
+    /// Liquid-water range but almost no water (Köppen B)
+    Arid,
+    /// Warm and wet, above 300 K (Köppen A)
+    Tropical,
+    /// 280-300 K with water (Köppen C)
+    Temperate,
+    /// 260-280 K, seasonally frozen (Köppen D)
+    Boreal,
+    /// Ice-covered, below 260 K (Köppen E)
+    Glacial,
+    /// No solid surface
+    Gaseous,
+}
+
+impl Climate {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "barren" => Some(Climate::Barren),
+            "molten" => Some(Climate::Molten),
+            "hothouse" => Some(Climate::Hothouse),
+            "arid" => Some(Climate::Arid),
+            "tropical" => Some(Climate::Tropical),
+            "temperate" => Some(Climate::Temperate),
+            "boreal" => Some(Climate::Boreal),
+            "glacial" => Some(Climate::Glacial),
+            "gaseous" => Some(Climate::Gaseous),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Climate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Climate::Barren => write!(f, "barren"),
+            Climate::Molten => write!(f, "molten"),
+            Climate::Hothouse => write!(f, "hothouse"),
+            Climate::Arid => write!(f, "arid"),
+            Climate::Tropical => write!(f, "tropical"),
+            Climate::Temperate => write!(f, "temperate"),
+            Climate::Boreal => write!(f, "boreal"),
+            Climate::Glacial => write!(f, "glacial"),
+            Climate::Gaseous => write!(f, "gaseous"),
+        }
+    }
+}
+
+impl Planet {
+    /// Name of the star or barycentre the planet orbits, e.g. "S0001ab"
+    pub fn host_name(&self) -> &str {
+        self.name.rsplit_once(' ').map_or(self.name.as_str(), |(host, _)| host)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanetType {
+    /// Below 2 Earth masses: Mercury to Earth-like
+    Rocky,
+    /// 2-10 Earth masses
+    SuperEarth,
+    /// 10-50 Earth masses, Neptune-like
+    IceGiant,
+    /// Above 50 Earth masses, Saturn- and Jupiter-like
+    GasGiant,
+}
+
+impl PlanetType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rocky" => Some(PlanetType::Rocky),
+            "super_earth" => Some(PlanetType::SuperEarth),
+            "ice_giant" => Some(PlanetType::IceGiant),
+            "gas_giant" => Some(PlanetType::GasGiant),
+            _ => None,
+        }
+    }
+
+    /// Mass range in Earth masses
+    pub fn mass_range(&self) -> (f64, f64) {
+        match self {
+            PlanetType::Rocky => (0.05, 2.0),
+            PlanetType::SuperEarth => (2.0, 10.0),
+            PlanetType::IceGiant => (10.0, 50.0),
+            PlanetType::GasGiant => (50.0, 4000.0),
+        }
+    }
+}
+
+impl fmt::Display for PlanetType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanetType::Rocky => write!(f, "rocky"),
+            PlanetType::SuperEarth => write!(f, "super_earth"),
+            PlanetType::IceGiant => write!(f, "ice_giant"),
+            PlanetType::GasGiant => write!(f, "gas_giant"),
+        }
+    }
+}
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use std::fmt;
+use crate::models::Position3D;
+
+/// Half-thickness of spiral and disk galaxies, in scale heights; their
+/// two-sided exponential leaves well under 1% of stars beyond it
+pub const DISK_EXTENT_SCALE_HEIGHTS: f64 = 5.0;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
@@ -13,33 +19,305 @@ pub struct Project {
     // Generation parameters
     pub distribution_type: String,
     pub num_star_systems: i32,
+    pub seed: Option<u64>,
 
     // Cube parameters
     pub size_x_ly: Option<f64>,
     pub size_y_ly: Option<f64>,
     pub size_z_ly: Option<f64>,
 
-    // Sphere parameters
+    // Sphere parameters (outer radius for spiral, disk, bulge and elliptical)
     pub radius_ly: Option<f64>,
 
+    // Galaxy shape parameters
+    pub arm_count: Option<i32>,
+    pub pitch_angle_deg: Option<f64>,
+    pub arm_spread_ly: Option<f64>,
+    pub scale_length_ly: Option<f64>,
+    pub scale_height_ly: Option<f64>,
+    pub effective_radius_ly: Option<f64>,
+    pub axis_ratio: Option<f64>,
+
+    // Poisson-disk minimum distance between systems
+    pub min_separation_ly: Option<f64>,
+
+    // Initial mass function stellar masses are drawn from
+    pub imf: String,
+
+    // Star-formation history system ages are drawn from
+    pub star_formation: String,
+    pub burst_age_gyr: Option<f64>,
+    pub burst_spread_gyr: Option<f64>,
+    pub sfh_timescale_gyr: Option<f64>,
+
+    // Star catalogue the project was imported from (None if generated)
+    pub catalogue: Option<String>,
+    // Catalogue stars stop here; procedural systems fill out to radius_ly
+    pub completeness_radius_ly: Option<f64>,
+
     // Statistics
     pub total_stars: i32,
     pub solo_systems: i32,
     pub binary_systems: i32,
     pub trinary_systems: i32,
+    /// Quadruple and larger systems
+    pub higher_order_systems: i32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum DistributionType {
     Cube { size_x: f64, size_y: f64, size_z: f64 },
     Sphere { radius: f64 },
+    /// Logarithmic spiral arms in a thin disk
+    Spiral {
+        radius: f64,
+        arm_count: i32,
+        pitch_angle_deg: f64,
+        arm_spread: f64,
+        scale_height: f64,
+    },
+    /// Exponential disk, truncated at `radius`
+    Disk { radius: f64, scale_length: f64, scale_height: f64 },
+    /// Exponential spheroid, optionally flattened along z
+    Bulge { radius: f64, scale_length: f64, axis_ratio: f64 },
+    /// De Vaucouleurs (r^1/4) profile, optionally flattened along z
+    Elliptical { radius: f64, effective_radius: f64, axis_ratio: f64 },
 }
 
 impl DistributionType {
-    pub fn to_string(&self) -> String {
+    /// Cube dimensions (x, y, z), if this is a cube distribution
+    pub fn cube_params(&self) -> Option<(f64, f64, f64)> {
+        match *self {
+            DistributionType::Cube { size_x, size_y, size_z } => Some((size_x, size_y, size_z)),
+            _ => None,
+        }
+    }
+
+    /// Outer radius for every shape except the cube
+    pub fn radius(&self) -> Option<f64> {
+        match *self {
+            DistributionType::Cube { .. } => None,
+            DistributionType::Sphere { radius }
+            | DistributionType::Spiral { radius, .. }
+            | DistributionType::Disk { radius, .. }
+            | DistributionType::Bulge { radius, .. }
+            | DistributionType::Elliptical { radius, .. } => Some(radius),
+        }
+    }
+
+    /// Whether a position lies inside the galaxy: the cube or sphere, the
+    /// disc out to `DISK_EXTENT_SCALE_HEIGHTS` above and below the plane, or
+    /// the flattened spheroid
+    pub fn contains(&self, pos: &Position3D) -> bool {
+        match *self {
+            DistributionType::Cube { size_x, size_y, size_z } => {
+                pos.x.abs() <= size_x / 2.0 && pos.y.abs() <= size_y / 2.0 && pos.z.abs() <= size_z / 2.0
+            },
+            DistributionType::Sphere { radius } => {
+                pos.x.powi(2) + pos.y.powi(2) + pos.z.powi(2) <= radius.powi(2)
+            },
+            DistributionType::Spiral { radius, scale_height, .. }
+            | DistributionType::Disk { radius, scale_height, .. } => {
+                pos.x.powi(2) + pos.y.powi(2) <= radius.powi(2)
+                    && pos.z.abs() <= scale_height * DISK_EXTENT_SCALE_HEIGHTS
+            },
+            DistributionType::Bulge { radius, axis_ratio, .. }
+            | DistributionType::Elliptical { radius, axis_ratio, .. } => {
+                pos.x.powi(2) + pos.y.powi(2) + (pos.z / axis_ratio).powi(2) <= radius.powi(2)
+            },
+        }
+    }
+
+    /// Volume of the space `contains` accepts, grown by `padding` on every side
+    pub fn volume(&self, padding: f64) -> f64 {
+        use std::f64::consts::PI;
+        match *self {
+            DistributionType::Cube { size_x, size_y, size_z } => {
+                (size_x + 2.0 * padding) * (size_y + 2.0 * padding) * (size_z + 2.0 * padding)
+            },
+            DistributionType::Sphere { radius } => 4.0 / 3.0 * PI * (radius + padding).powi(3),
+            DistributionType::Spiral { radius, scale_height, .. }
+            | DistributionType::Disk { radius, scale_height, .. } => {
+                PI * (radius + padding).powi(2) * 2.0 * (scale_height * DISK_EXTENT_SCALE_HEIGHTS + padding)
+            },
+            DistributionType::Bulge { radius, axis_ratio, .. }
+            | DistributionType::Elliptical { radius, axis_ratio, .. } => {
+                4.0 / 3.0 * PI * (radius + padding).powi(2) * (radius * axis_ratio + padding)
+            },
+        }
+    }
+
+    pub fn arm_count(&self) -> Option<i32> {
+        match *self {
+            DistributionType::Spiral { arm_count, .. } => Some(arm_count),
+            _ => None,
+        }
+    }
+
+    pub fn pitch_angle_deg(&self) -> Option<f64> {
+        match *self {
+            DistributionType::Spiral { pitch_angle_deg, .. } => Some(pitch_angle_deg),
+            _ => None,
+        }
+    }
+
+    pub fn arm_spread(&self) -> Option<f64> {
+        match *self {
+            DistributionType::Spiral { arm_spread, .. } => Some(arm_spread),
+            _ => None,
+        }
+    }
+
+    pub fn scale_length(&self) -> Option<f64> {
+        match *self {
+            DistributionType::Disk { scale_length, .. }
+            | DistributionType::Bulge { scale_length, .. } => Some(scale_length),
+            _ => None,
+        }
+    }
+
+    pub fn scale_height(&self) -> Option<f64> {
+        match *self {
+            DistributionType::Spiral { scale_height, .. }
+            | DistributionType::Disk { scale_height, .. } => Some(scale_height),
+            _ => None,
+        }
+    }
+
+    pub fn effective_radius(&self) -> Option<f64> {
+        match *self {
+            DistributionType::Elliptical { effective_radius, .. } => Some(effective_radius),
+            _ => None,
+        }
+    }
+
+    pub fn axis_ratio(&self) -> Option<f64> {
+        match *self {
+            DistributionType::Bulge { axis_ratio, .. }
+            | DistributionType::Elliptical { axis_ratio, .. } => Some(axis_ratio),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DistributionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DistributionType::Cube { .. } => write!(f, "cube"),
+            DistributionType::Sphere { .. } => write!(f, "sphere"),
+            DistributionType::Spiral { .. } => write!(f, "spiral"),
+            DistributionType::Disk { .. } => write!(f, "disk"),
+            DistributionType::Bulge { .. } => write!(f, "bulge"),
+            DistributionType::Elliptical { .. } => write!(f, "elliptical"),
+        }
+    }
+}
+
+/// Initial mass function used to sample stellar masses
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Imf {
+    /// Single power law, dN/dm ∝ m^-2.35
+    Salpeter,
+    /// Broken power law, slope -1.3 below 0.5 M☉ and -2.3 above
+    #[default]
+    Kroupa,
+    /// Log-normal below 1 M☉, power law -2.3 above
+    Chabrier,
+}
+
+impl Imf {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "salpeter" => Some(Imf::Salpeter),
+            "kroupa" => Some(Imf::Kroupa),
+            "chabrier" => Some(Imf::Chabrier),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Imf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Imf::Salpeter => write!(f, "salpeter"),
+            Imf::Kroupa => write!(f, "kroupa"),
+            Imf::Chabrier => write!(f, "chabrier"),
+        }
+    }
+}
+
+/// Star-formation history: how system ages are distributed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum StarFormationHistory {
+    /// Constant formation rate since the galaxy formed
+    #[default]
+    Constant,
+    /// Single episode centred on `age_gyr`, Gaussian with `spread_gyr`
+    Burst { age_gyr: f64, spread_gyr: f64 },
+    /// Rate falling as exp(-t / timescale) since the galaxy formed,
+    /// so old stars dominate
+    Declining { timescale_gyr: f64 },
+}
+
+impl StarFormationHistory {
+    pub fn burst_age_gyr(&self) -> Option<f64> {
+        match *self {
+            StarFormationHistory::Burst { age_gyr, .. } => Some(age_gyr),
+            _ => None,
+        }
+    }
+
+    pub fn burst_spread_gyr(&self) -> Option<f64> {
+        match *self {
+            StarFormationHistory::Burst { spread_gyr, .. } => Some(spread_gyr),
+            _ => None,
+        }
+    }
+
+    pub fn timescale_gyr(&self) -> Option<f64> {
+        match *self {
+            StarFormationHistory::Declining { timescale_gyr } => Some(timescale_gyr),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for StarFormationHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StarFormationHistory::Constant => write!(f, "constant"),
+            StarFormationHistory::Burst { .. } => write!(f, "burst"),
+            StarFormationHistory::Declining { .. } => write!(f, "declining"),
+        }
+    }
+}
+
+/// Layout of a star catalogue file a project can be imported from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CatalogueFormat {
+    /// HYG database: RA in hours, distance in parsecs, MK spectral types
+    Hyg,
+    /// Gaia archive subset: RA/Dec in degrees, parallax in mas, G magnitude and BP-RP colour
+    Gaia,
+}
+
+impl CatalogueFormat {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "hyg" => Some(CatalogueFormat::Hyg),
+            "gaia" => Some(CatalogueFormat::Gaia),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CatalogueFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            DistributionType::Cube { .. } => "cube".to_string(),
-            DistributionType::Sphere { .. } => "sphere".to_string(),
+            CatalogueFormat::Hyg => write!(f, "hyg"),
+            CatalogueFormat::Gaia => write!(f, "gaia"),
         }
     }
 }
@@ -1,4 +1,57 @@
 use serde::{Deserialize, Serialize};
+use std::path::{Component, Path};
+use uuid::Uuid;
+use crate::models::{DistributionType, DensityZone, ZoneType, Axis, Imf, StarFormationHistory, SpectralClass, SystemType, Position3D, PlanetType, Atmosphere, Climate, SkyFrame, SkyProjection, CatalogueFormat, LaneRule, PartitionMethod, Resource, TradeNetwork};
+use crate::generator::{LaneParams, TerritoryParams, GALAXY_AGE_GYR, NAKED_EYE_MAGNITUDE};
+use crate::spatial::RouteCost;
+
+/// Maximum number of density zones per project
+const MAX_ZONES: usize = 64;
+
+/// Packing fraction random sequential placement can reliably reach in 3D
+/// (the jamming limit is about 0.38; stay below it so placement converges)
+const MAX_PACKING_FRACTION: f64 = 0.3;
+
+/// Planet-search results returned when no limit is given, and the most a
+/// request may ask for
+const DEFAULT_HABITABLE_LIMIT: usize = 100;
+const MAX_HABITABLE_LIMIT: usize = 1000;
+
+/// Neighbours returned by a nearest query when no k is given, and the most
+/// any spatial query returns
+const DEFAULT_NEAREST: usize = 10;
+const MAX_SPATIAL_RESULTS: usize = 10_000;
+
+/// Neighbours a k_nearest lane rule may link, and chokepoint regions a map
+/// may be split into
+const MAX_LANE_K: usize = 20;
+const MAX_CHOKEPOINT_REGIONS: usize = 64;
+
+/// Longest free-flight jump a route or reachability query may take; beyond
+/// this nearly every pair of systems is linked and searches turn quadratic
+const MAX_JUMP_LY: f64 = 1000.0;
+
+/// Most factions a project may be divided among, and the share of the
+/// owner's claim a rival must come within to contest a system by default
+const MAX_FACTIONS: usize = 64;
+const DEFAULT_CONTESTED_MARGIN: f64 = 0.1;
+
+/// Most economy ticks one request may simulate, and trade flows or hubs
+/// listed when no limit is given
+const MAX_ECONOMY_TICKS: u32 = 100;
+const DEFAULT_TRADE_RESULTS: usize = 20;
+
+/// Catalogue import radius when none is given, and the largest allowed
+const DEFAULT_IMPORT_RADIUS_LY: f64 = 100.0;
+const MAX_IMPORT_RADIUS_LY: f64 = 10_000.0;
+
+/// Skybox sizes in pixels: panorama width, or cube face edge
+const MIN_PANORAMA_WIDTH: u32 = 256;
+const MAX_PANORAMA_WIDTH: u32 = 8192;
+const DEFAULT_PANORAMA_WIDTH: u32 = 2048;
+const MIN_CUBE_FACE: u32 = 64;
+const MAX_CUBE_FACE: u32 = 2048;
+const DEFAULT_CUBE_FACE: u32 = 512;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GenerateProjectRequest {
@@ -11,8 +64,50 @@ pub struct GenerateProjectRequest {
     pub size_y_ly: Option<f64>,
     pub size_z_ly: Option<f64>,
 
-    // Sphere parameters (required if distribution_type = "sphere")
+    // Sphere parameters (required if distribution_type = "sphere");
+    // also the outer radius for spiral, disk, bulge and elliptical
     pub radius_ly: Option<f64>,
+
+    // Spiral parameters (required if distribution_type = "spiral", with scale_height_ly)
+    pub arm_count: Option<i32>,
+    pub pitch_angle_deg: Option<f64>,
+    pub arm_spread_ly: Option<f64>,
+
+    // Disk parameters (required if distribution_type = "disk");
+    // scale_length_ly is also the bulge scale length
+    pub scale_length_ly: Option<f64>,
+    pub scale_height_ly: Option<f64>,
+
+    // Elliptical parameters (required if distribution_type = "elliptical")
+    pub effective_radius_ly: Option<f64>,
+
+    // Flattening c/a for bulge and elliptical (defaults to 1.0, spherical)
+    pub axis_ratio: Option<f64>,
+
+    // Density zones inside the volume (clusters, voids, gradients)
+    pub zones: Option<Vec<DensityZoneRequest>>,
+
+    // Minimum distance between any two systems (Poisson-disk placement)
+    pub min_separation_ly: Option<f64>,
+
+    // Initial mass function: "salpeter", "kroupa" (default) or "chabrier"
+    pub imf: Option<String>,
+
+    // Star formation history: "constant" (default), "burst" or "declining"
+    pub star_formation: Option<String>,
+    // Burst parameters (burst_age_gyr required if star_formation = "burst";
+    // burst_spread_gyr defaults to 0.1)
+    pub burst_age_gyr: Option<f64>,
+    pub burst_spread_gyr: Option<f64>,
+    // Declining parameters (required if star_formation = "declining")
+    pub sfh_timescale_gyr: Option<f64>,
+
+    // Generation seed (random if omitted); the same seed and parameters
+    // always reproduce the same galaxy
+    pub seed: Option<u64>,
+
+    // Link the generated systems with lanes
+    pub lanes: Option<LaneRequest>,
 }
 
 impl GenerateProjectRequest {
@@ -42,11 +137,1177 @@ impl GenerateProjectRequest {
                     return Err("Sphere radius must be positive".to_string());
                 }
             },
+            "spiral" => {
+                let (Some(radius), Some(arms), Some(pitch), Some(spread), Some(height)) = (
+                    self.radius_ly,
+                    self.arm_count,
+                    self.pitch_angle_deg,
+                    self.arm_spread_ly,
+                    self.scale_height_ly,
+                ) else {
+                    return Err("Spiral distribution requires radius_ly, arm_count, pitch_angle_deg, arm_spread_ly, and scale_height_ly".to_string());
+                };
+                if radius <= 0.0 || spread <= 0.0 || height <= 0.0 {
+                    return Err("Spiral radius, arm spread and scale height must be positive".to_string());
+                }
+                if spread > radius {
+                    return Err("Spiral arm_spread_ly cannot exceed radius_ly".to_string());
+                }
+                if !(1..=12).contains(&arms) {
+                    return Err("Spiral arm_count must be between 1 and 12".to_string());
+                }
+                if !(1.0..=80.0).contains(&pitch) {
+                    return Err("Spiral pitch_angle_deg must be between 1 and 80 degrees".to_string());
+                }
+            },
+            "disk" => {
+                let (Some(radius), Some(length), Some(height)) =
+                    (self.radius_ly, self.scale_length_ly, self.scale_height_ly)
+                else {
+                    return Err("Disk distribution requires radius_ly, scale_length_ly, and scale_height_ly".to_string());
+                };
+                if radius <= 0.0 || length <= 0.0 || height <= 0.0 {
+                    return Err("Disk radius, scale length and scale height must be positive".to_string());
+                }
+                if length > radius {
+                    return Err("Disk scale_length_ly cannot exceed radius_ly".to_string());
+                }
+            },
+            "bulge" => {
+                let (Some(radius), Some(length)) = (self.radius_ly, self.scale_length_ly) else {
+                    return Err("Bulge distribution requires radius_ly and scale_length_ly".to_string());
+                };
+                if radius <= 0.0 || length <= 0.0 {
+                    return Err("Bulge radius and scale length must be positive".to_string());
+                }
+                if length > radius {
+                    return Err("Bulge scale_length_ly cannot exceed radius_ly".to_string());
+                }
+            },
+            "elliptical" => {
+                let (Some(radius), Some(effective)) = (self.radius_ly, self.effective_radius_ly) else {
+                    return Err("Elliptical distribution requires radius_ly and effective_radius_ly".to_string());
+                };
+                if radius <= 0.0 || effective <= 0.0 {
+                    return Err("Elliptical radius and effective radius must be positive".to_string());
+                }
+                if effective > radius {
+                    return Err("Elliptical effective_radius_ly cannot exceed radius_ly".to_string());
+                }
+            },
             _ => {
-                return Err("distribution_type must be 'cube' or 'sphere'".to_string());
+                return Err("distribution_type must be 'cube', 'sphere', 'spiral', 'disk', 'bulge' or 'elliptical'".to_string());
+            }
+        }
+
+        if let Some(q) = self.axis_ratio {
+            if q <= 0.0 || q > 1.0 {
+                return Err("axis_ratio must be greater than 0 and at most 1".to_string());
+            }
+        }
+
+        self.imf()?;
+
+        match self.star_formation()? {
+            StarFormationHistory::Constant => {},
+            StarFormationHistory::Burst { age_gyr, spread_gyr } => {
+                if !(0.0..=GALAXY_AGE_GYR).contains(&age_gyr) {
+                    return Err(format!("burst_age_gyr must be between 0 and {}", GALAXY_AGE_GYR));
+                }
+                if spread_gyr < 0.0 {
+                    return Err("burst_spread_gyr cannot be negative".to_string());
+                }
+            },
+            StarFormationHistory::Declining { timescale_gyr } => {
+                if timescale_gyr <= 0.0 {
+                    return Err("sfh_timescale_gyr must be positive".to_string());
+                }
+            },
+        }
+
+        if let Some(separation) = self.min_separation_ly {
+            if separation <= 0.0 {
+                return Err("min_separation_ly must be positive".to_string());
+            }
+            let max = self.max_systems_for_separation(separation)?;
+            if self.num_stars as usize > max {
+                return Err(format!(
+                    "{} star systems cannot be kept {} ly apart in this volume; at most about {} fit",
+                    self.num_stars, separation, max
+                ));
+            }
+        }
+
+        if let Some(zones) = &self.zones {
+            if zones.len() > MAX_ZONES {
+                return Err(format!("At most {} density zones are allowed", MAX_ZONES));
+            }
+            let distribution = self.distribution()?;
+            for (i, zone) in zones.iter().enumerate() {
+                zone.validate().map_err(|e| format!("Zone {}: {}", i, e))?;
+                let center = Position3D { x: zone.center_x_ly, y: zone.center_y_ly, z: zone.center_z_ly };
+                if zone.star_share.unwrap_or(0.0) > 0.0 && !distribution.contains(&center) {
+                    return Err(format!("Zone {}: centre lies outside the {} volume", i, distribution));
+                }
+            }
+            let total_share: f64 = zones.iter().map(|z| z.star_share.unwrap_or(0.0)).sum();
+            if total_share > 1.0 {
+                return Err("Density zone star_share values cannot add up to more than 1".to_string());
+            }
+        }
+
+        if let Some(lanes) = &self.lanes {
+            lanes.validate().map_err(|e| format!("Lanes: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Upper bound on systems that fit `separation` apart in the
+    /// distribution's volume
+    /// Each system claims a sphere of diameter `separation`; the volume is
+    /// padded by half a separation since those spheres may poke out of it
+    fn max_systems_for_separation(&self, separation: f64) -> Result<usize, String> {
+        let volume = self.distribution()?.volume(separation / 2.0);
+        let per_system = std::f64::consts::PI / 6.0 * separation.powi(3);
+
+        Ok((MAX_PACKING_FRACTION * volume / per_system).floor() as usize)
+    }
+
+    /// Density zones for a validated request, numbered in request order
+    pub fn density_zones(&self, project_id: Uuid) -> Vec<DensityZone> {
+        self.zones
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(i, zone)| {
+                let zone_type = ZoneType::from_name(&zone.zone_type)?;
+                Some(DensityZone {
+                    id: Some(Uuid::new_v4()),
+                    project_id,
+                    zone_index: i as i32,
+                    zone_type,
+                    center_x_ly: zone.center_x_ly,
+                    center_y_ly: zone.center_y_ly,
+                    center_z_ly: zone.center_z_ly,
+                    radius_ly: zone.radius_ly,
+                    star_share: zone.star_share.unwrap_or(0.0),
+                    gradient_axis: zone.axis.as_deref().and_then(Axis::from_name),
+                    gradient_strength: zone.strength,
+                })
+            })
+            .collect()
+    }
+
+    /// Initial mass function for the request (Kroupa if omitted)
+    pub fn imf(&self) -> Result<Imf, String> {
+        match &self.imf {
+            None => Ok(Imf::default()),
+            Some(name) => Imf::from_name(name)
+                .ok_or_else(|| "imf must be 'salpeter', 'kroupa' or 'chabrier'".to_string()),
+        }
+    }
+
+    /// Star formation history for the request (constant if omitted)
+    pub fn star_formation(&self) -> Result<StarFormationHistory, String> {
+        match self.star_formation.as_deref().unwrap_or("constant") {
+            "constant" => Ok(StarFormationHistory::Constant),
+            "burst" => Ok(StarFormationHistory::Burst {
+                age_gyr: self.burst_age_gyr.ok_or("burst_age_gyr required for a burst")?,
+                spread_gyr: self.burst_spread_gyr.unwrap_or(0.1),
+            }),
+            "declining" => Ok(StarFormationHistory::Declining {
+                timescale_gyr: self.sfh_timescale_gyr.ok_or("sfh_timescale_gyr required for a declining history")?,
+            }),
+            _ => Err("star_formation must be 'constant', 'burst' or 'declining'".to_string()),
+        }
+    }
+
+    /// Distribution parameters for a validated request
+    pub fn distribution(&self) -> Result<DistributionType, String> {
+        match self.distribution_type.as_str() {
+            "cube" => Ok(DistributionType::Cube {
+                size_x: self.size_x_ly.ok_or("Cube parameters required")?,
+                size_y: self.size_y_ly.ok_or("Cube parameters required")?,
+                size_z: self.size_z_ly.ok_or("Cube parameters required")?,
+            }),
+            "sphere" => Ok(DistributionType::Sphere {
+                radius: self.radius_ly.ok_or("Sphere parameters required")?,
+            }),
+            "spiral" => Ok(DistributionType::Spiral {
+                radius: self.radius_ly.ok_or("Spiral parameters required")?,
+                arm_count: self.arm_count.ok_or("Spiral parameters required")?,
+                pitch_angle_deg: self.pitch_angle_deg.ok_or("Spiral parameters required")?,
+                arm_spread: self.arm_spread_ly.ok_or("Spiral parameters required")?,
+                scale_height: self.scale_height_ly.ok_or("Spiral parameters required")?,
+            }),
+            "disk" => Ok(DistributionType::Disk {
+                radius: self.radius_ly.ok_or("Disk parameters required")?,
+                scale_length: self.scale_length_ly.ok_or("Disk parameters required")?,
+                scale_height: self.scale_height_ly.ok_or("Disk parameters required")?,
+            }),
+            "bulge" => Ok(DistributionType::Bulge {
+                radius: self.radius_ly.ok_or("Bulge parameters required")?,
+                scale_length: self.scale_length_ly.ok_or("Bulge parameters required")?,
+                axis_ratio: self.axis_ratio.unwrap_or(1.0),
+            }),
+            "elliptical" => Ok(DistributionType::Elliptical {
+                radius: self.radius_ly.ok_or("Elliptical parameters required")?,
+                effective_radius: self.effective_radius_ly.ok_or("Elliptical parameters required")?,
+                axis_ratio: self.axis_ratio.unwrap_or(1.0),
+            }),
+            _ => Err("Invalid distribution type".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DensityZoneRequest {
+    /// "open_cluster", "globular_cluster", "void" or "gradient"
+    pub zone_type: String,
+    pub center_x_ly: f64,
+    pub center_y_ly: f64,
+    pub center_z_ly: f64,
+    pub radius_ly: f64,
+
+    // Fraction of all star systems placed in this zone (omit or 0 for voids)
+    pub star_share: Option<f64>,
+
+    // Gradient parameters (required if zone_type = "gradient")
+    pub axis: Option<String>,
+    pub strength: Option<f64>,
+}
+
+impl DensityZoneRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        let zone_type = ZoneType::from_name(&self.zone_type).ok_or(
+            "zone_type must be 'open_cluster', 'globular_cluster', 'void' or 'gradient'",
+        )?;
+
+        if !(self.center_x_ly.is_finite() && self.center_y_ly.is_finite() && self.center_z_ly.is_finite()) {
+            return Err("Zone centre must be finite".to_string());
+        }
+        if self.radius_ly <= 0.0 {
+            return Err("Zone radius must be positive".to_string());
+        }
+
+        let share = self.star_share.unwrap_or(0.0);
+        if !(0.0..=1.0).contains(&share) {
+            return Err("star_share must be between 0 and 1".to_string());
+        }
+
+        match zone_type {
+            ZoneType::Void => {
+                if share > 0.0 {
+                    return Err("Voids cannot have a star_share".to_string());
+                }
+            },
+            ZoneType::Gradient => {
+                if self.axis.as_deref().and_then(Axis::from_name).is_none() {
+                    return Err("Gradient zones require axis 'x', 'y' or 'z'".to_string());
+                }
+                match self.strength {
+                    Some(s) if (-1.0..=1.0).contains(&s) => {},
+                    _ => return Err("Gradient zones require strength between -1 and 1".to_string()),
+                }
+            },
+            ZoneType::OpenCluster | ZoneType::GlobularCluster => {},
+        }
+
+        Ok(())
+    }
+}
+
+/// Body of POST /api/projects/import
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImportCatalogueRequest {
+    pub name: String,
+    // CSV file inside the server's catalogue directory, e.g. "hygdata_v3.csv"
+    pub file: String,
+    // "hyg" or "gaia"; detected from the header row if omitted
+    pub format: Option<String>,
+    // Stars within this distance of the origin are imported (default 100)
+    pub radius_ly: Option<f64>,
+    // Catalogue stars stop here and procedural systems fill out to radius_ly
+    pub completeness_radius_ly: Option<f64>,
+    // Initial mass function for the procedural fill (default "kroupa")
+    pub imf: Option<String>,
+    // Seed for ages, planets and fill systems (random if omitted)
+    pub seed: Option<u64>,
+    // Link the imported systems with lanes
+    pub lanes: Option<LaneRequest>,
+}
+
+impl ImportCatalogueRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Project name cannot be empty".to_string());
+        }
+
+        // Only plain relative paths, so requests cannot read outside the catalogue directory
+        let path = Path::new(&self.file);
+        if self.file.trim().is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err("file must be a relative path inside the catalogue directory".to_string());
+        }
+
+        self.format()?;
+        self.imf()?;
+
+        let radius = self.radius_ly();
+        if !(radius > 0.0 && radius <= MAX_IMPORT_RADIUS_LY) {
+            return Err(format!("radius_ly must be between 0 and {}", MAX_IMPORT_RADIUS_LY));
+        }
+        if let Some(completeness) = self.completeness_radius_ly {
+            if !(completeness > 0.0 && completeness < radius) {
+                return Err("completeness_radius_ly must be positive and smaller than radius_ly".to_string());
+            }
+        }
+
+        if let Some(lanes) = &self.lanes {
+            lanes.validate().map_err(|e| format!("Lanes: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn format(&self) -> Result<Option<CatalogueFormat>, String> {
+        match &self.format {
+            None => Ok(None),
+            Some(name) => CatalogueFormat::from_name(name)
+                .map(Some)
+                .ok_or_else(|| "format must be 'hyg' or 'gaia'".to_string()),
+        }
+    }
+
+    pub fn imf(&self) -> Result<Imf, String> {
+        match &self.imf {
+            None => Ok(Imf::default()),
+            Some(name) => Imf::from_name(name)
+                .ok_or_else(|| "imf must be 'salpeter', 'kroupa' or 'chabrier'".to_string()),
+        }
+    }
+
+    pub fn radius_ly(&self) -> f64 {
+        self.radius_ly.unwrap_or(DEFAULT_IMPORT_RADIUS_LY)
+    }
+}
+
+/// Query string of GET /api/projects/{id}/habitable
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HabitableQuery {
+    // Comma-separated host spectral classes, e.g. "G,K"
+    pub spectral_class: Option<String>,
+    // solo, binary, trinary, quadruple, quintuple or sextuple
+    pub system_type: Option<String>,
+
+    // Reference point; results report their distance from it
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub z: Option<f64>,
+    // Only systems within this distance of the point (requires x, y and z)
+    pub max_distance_ly: Option<f64>,
+
+    // Lowest habitability score returned, 0-1 (default: anything above 0)
+    pub min_score: Option<f64>,
+    // Maximum number of planets returned (default 100, at most 1000)
+    pub limit: Option<usize>,
+}
+
+impl HabitableQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        self.spectral_classes()?;
+        self.system_type()?;
+        self.reference_point()?;
+
+        if let Some(score) = self.min_score {
+            if !(0.0..=1.0).contains(&score) {
+                return Err("min_score must be between 0 and 1".to_string());
+            }
+        }
+        if let Some(limit) = self.limit {
+            if !(1..=MAX_HABITABLE_LIMIT).contains(&limit) {
+                return Err(format!("limit must be between 1 and {}", MAX_HABITABLE_LIMIT));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Host spectral classes to keep (None = all)
+    pub fn spectral_classes(&self) -> Result<Option<Vec<SpectralClass>>, String> {
+        parse_spectral_classes(self.spectral_class.as_deref())
+    }
+
+    /// System type to keep (None = all)
+    pub fn system_type(&self) -> Result<Option<SystemType>, String> {
+        match &self.system_type {
+            None => Ok(None),
+            Some(name) => SystemType::from_name(name).map(Some).ok_or_else(|| {
+                "system_type must be 'solo', 'binary', 'trinary', 'quadruple', 'quintuple' or 'sextuple'".to_string()
+            }),
+        }
+    }
+
+    /// Reference point, if one was given
+    pub fn reference_point(&self) -> Result<Option<Position3D>, String> {
+        let point = match (self.x, self.y, self.z) {
+            (Some(x), Some(y), Some(z)) => Some(Position3D { x, y, z }),
+            (None, None, None) => None,
+            _ => return Err("x, y and z must be given together".to_string()),
+        };
+
+        match self.max_distance_ly {
+            Some(_) if point.is_none() => Err("max_distance_ly requires x, y and z".to_string()),
+            Some(distance) if distance <= 0.0 => Err("max_distance_ly must be positive".to_string()),
+            _ => Ok(point),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_HABITABLE_LIMIT)
+    }
+}
+
+/// Query string of GET /api/projects/{id}/planets
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PlanetQuery {
+    // Comma-separated host spectral classes, e.g. "K,M"
+    pub spectral_class: Option<String>,
+    // rocky, super_earth, ice_giant or gas_giant
+    pub planet_type: Option<String>,
+    // Comma-separated climates, e.g. "temperate,tropical"
+    pub climate: Option<String>,
+    // airless, thin, moderate, dense or hydrogen_envelope
+    pub atmosphere: Option<String>,
+    // Lowest surface water fraction, 0-1
+    pub min_water_fraction: Option<f64>,
+    pub tidally_locked: Option<bool>,
+    // Maximum number of planets returned (default 100, at most 1000)
+    pub limit: Option<usize>,
+}
+
+impl PlanetQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        self.spectral_classes()?;
+        self.planet_type()?;
+        self.climates()?;
+        self.atmosphere()?;
+
+        if let Some(fraction) = self.min_water_fraction {
+            if !(0.0..=1.0).contains(&fraction) {
+                return Err("min_water_fraction must be between 0 and 1".to_string());
+            }
+        }
+        if let Some(limit) = self.limit {
+            if !(1..=MAX_HABITABLE_LIMIT).contains(&limit) {
+                return Err(format!("limit must be between 1 and {}", MAX_HABITABLE_LIMIT));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Host spectral classes to keep (None = all)
+    pub fn spectral_classes(&self) -> Result<Option<Vec<SpectralClass>>, String> {
+        parse_spectral_classes(self.spectral_class.as_deref())
+    }
+
+    pub fn planet_type(&self) -> Result<Option<PlanetType>, String> {
+        match &self.planet_type {
+            None => Ok(None),
+            Some(name) => PlanetType::from_name(name).map(Some).ok_or_else(|| {
+                "planet_type must be 'rocky', 'super_earth', 'ice_giant' or 'gas_giant'".to_string()
+            }),
+        }
+    }
+
+    /// Climates to keep (None = all)
+    pub fn climates(&self) -> Result<Option<Vec<Climate>>, String> {
+        let Some(list) = &self.climate else {
+            return Ok(None);
+        };
+        list.split(',')
+            .map(|name| Climate::from_name(name.trim()).ok_or_else(|| format!("Unknown climate '{}'", name.trim())))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    }
+
+    pub fn atmosphere(&self) -> Result<Option<Atmosphere>, String> {
+        match &self.atmosphere {
+            None => Ok(None),
+            Some(name) => Atmosphere::from_name(name).map(Some).ok_or_else(|| {
+                "atmosphere must be 'airless', 'thin', 'moderate', 'dense' or 'hydrogen_envelope'".to_string()
+            }),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_HABITABLE_LIMIT)
+    }
+}
+
+/// Point a spatial query is measured from: a named system or coordinates
+#[derive(Debug, Clone)]
+pub enum QueryPoint {
+    System(String),
+    Position(Position3D),
+}
+
+impl QueryPoint {
+    fn parse(system: Option<&str>, x: Option<f64>, y: Option<f64>, z: Option<f64>, names: &str) -> Result<Self, String> {
+        match (system, x, y, z) {
+            (Some(name), None, None, None) => Ok(QueryPoint::System(name.to_string())),
+            (None, Some(x), Some(y), Some(z)) => Ok(QueryPoint::Position(Position3D { x, y, z })),
+            _ => Err(format!("give either {}", names)),
+        }
+    }
+}
+
+fn validate_spatial_limit(limit: Option<usize>) -> Result<(), String> {
+    match limit {
+        Some(limit) if !(1..=MAX_SPATIAL_RESULTS).contains(&limit) => {
+            Err(format!("limit must be between 1 and {}", MAX_SPATIAL_RESULTS))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Query string of GET /api/projects/{id}/spatial/nearest
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NearestQuery {
+    // Measure from this system (which is left out of the results)...
+    pub system: Option<String>,
+    // ...or from this point
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub z: Option<f64>,
+    // Number of neighbours (default 10)
+    pub k: Option<usize>,
+}
+
+impl NearestQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        self.point()?;
+        if let Some(k) = self.k {
+            if !(1..=MAX_SPATIAL_RESULTS).contains(&k) {
+                return Err(format!("k must be between 1 and {}", MAX_SPATIAL_RESULTS));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn point(&self) -> Result<QueryPoint, String> {
+        QueryPoint::parse(self.system.as_deref(), self.x, self.y, self.z, "system or x, y and z")
+    }
+
+    pub fn k(&self) -> usize {
+        self.k.unwrap_or(DEFAULT_NEAREST)
+    }
+}
+
+/// Query string of GET /api/projects/{id}/spatial/radius
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RadiusQuery {
+    // Centre: a system (left out of the results) or a point
+    pub system: Option<String>,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub z: Option<f64>,
+    pub radius_ly: f64,
+    // Nearest systems returned (default and maximum 10000)
+    pub limit: Option<usize>,
+}
+
+impl RadiusQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        self.point()?;
+        if !(self.radius_ly > 0.0 && self.radius_ly.is_finite()) {
+            return Err("radius_ly must be positive".to_string());
+        }
+        validate_spatial_limit(self.limit)
+    }
+
+    pub fn point(&self) -> Result<QueryPoint, String> {
+        QueryPoint::parse(self.system.as_deref(), self.x, self.y, self.z, "system or x, y and z")
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(MAX_SPATIAL_RESULTS)
+    }
+}
+
+/// Query string of GET /api/projects/{id}/spatial/box
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BoxQuery {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub min_z: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+    pub max_z: f64,
+    // Systems nearest the box centre returned (default and maximum 10000)
+    pub limit: Option<usize>,
+}
+
+impl BoxQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_x > self.max_x || self.min_y > self.max_y || self.min_z > self.max_z {
+            return Err("min_x, min_y and min_z must not exceed max_x, max_y and max_z".to_string());
+        }
+        validate_spatial_limit(self.limit)
+    }
+
+    pub fn min(&self) -> Position3D {
+        Position3D { x: self.min_x, y: self.min_y, z: self.min_z }
+    }
+
+    pub fn max(&self) -> Position3D {
+        Position3D { x: self.max_x, y: self.max_y, z: self.max_z }
+    }
+
+    pub fn center(&self) -> Position3D {
+        Position3D {
+            x: (self.min_x + self.max_x) / 2.0,
+            y: (self.min_y + self.max_y) / 2.0,
+            z: (self.min_z + self.max_z) / 2.0,
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(MAX_SPATIAL_RESULTS)
+    }
+}
+
+/// Query string of GET /api/projects/{id}/spatial/cylinder
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CylinderQuery {
+    // Axis start: a system or a point
+    pub from: Option<String>,
+    pub x1: Option<f64>,
+    pub y1: Option<f64>,
+    pub z1: Option<f64>,
+    // Axis end: a system or a point
+    pub to: Option<String>,
+    pub x2: Option<f64>,
+    pub y2: Option<f64>,
+    pub z2: Option<f64>,
+    pub radius_ly: f64,
+    // Systems returned in order along the axis (default and maximum 10000)
+    pub limit: Option<usize>,
+}
+
+impl CylinderQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        self.start()?;
+        self.end()?;
+        if !(self.radius_ly > 0.0 && self.radius_ly.is_finite()) {
+            return Err("radius_ly must be positive".to_string());
+        }
+        validate_spatial_limit(self.limit)
+    }
+
+    pub fn start(&self) -> Result<QueryPoint, String> {
+        QueryPoint::parse(self.from.as_deref(), self.x1, self.y1, self.z1, "from or x1, y1 and z1")
+    }
+
+    pub fn end(&self) -> Result<QueryPoint, String> {
+        QueryPoint::parse(self.to.as_deref(), self.x2, self.y2, self.z2, "to or x2, y2 and z2")
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(MAX_SPATIAL_RESULTS)
+    }
+}
+
+/// Query string of GET /api/projects/{id}/route
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RouteQuery {
+    pub from: String,
+    pub to: String,
+    // Comma-separated systems to pass through, in order
+    pub via: Option<String>,
+    // Fly freely with jumps up to this length instead of following lanes
+    pub max_jump_ly: Option<f64>,
+    // "distance" (default), "jumps" or "time"
+    pub cost: Option<String>,
+    // FTL speed in multiples of light speed (required for cost=time)
+    pub speed_c: Option<f64>,
+    // Days spent on each jump besides flight time (default 0)
+    pub jump_time_days: Option<f64>,
+    // Comma-separated systems never passed through
+    pub avoid: Option<String>,
+    // Comma-separated spectral classes whose systems are never passed through
+    pub avoid_classes: Option<String>,
+}
+
+impl RouteQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        self.cost()?;
+        self.avoid_classes()?;
+
+        if let Some(range) = self.max_jump_ly {
+            if !(range > 0.0 && range <= MAX_JUMP_LY) {
+                return Err(format!("max_jump_ly must be positive and at most {}", MAX_JUMP_LY));
+            }
+        }
+        if let Some(speed) = self.speed_c {
+            if !(speed > 0.0 && speed.is_finite()) {
+                return Err("speed_c must be positive".to_string());
+            }
+        }
+        if let Some(days) = self.jump_time_days {
+            if !(days >= 0.0 && days.is_finite()) {
+                return Err("jump_time_days must not be negative".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn cost(&self) -> Result<RouteCost, String> {
+        match self.cost.as_deref().unwrap_or("distance") {
+            "distance" => Ok(RouteCost::Distance),
+            "jumps" => Ok(RouteCost::Jumps),
+            "time" => Ok(RouteCost::Time {
+                speed_c: self.speed_c.ok_or("cost=time requires speed_c")?,
+                jump_time_days: self.jump_time_days.unwrap_or(0.0),
+            }),
+            _ => Err("cost must be 'distance', 'jumps' or 'time'".to_string()),
+        }
+    }
+
+    /// Waypoints in order
+    pub fn via(&self) -> Vec<String> {
+        split_names(self.via.as_deref())
+    }
+
+    /// Systems to keep out of the route
+    pub fn avoid(&self) -> Vec<String> {
+        split_names(self.avoid.as_deref())
+    }
+
+    pub fn avoid_classes(&self) -> Result<Option<Vec<SpectralClass>>, String> {
+        parse_spectral_classes(self.avoid_classes.as_deref())
+    }
+}
+
+/// Names from a comma-separated list, blanks dropped
+fn split_names(list: Option<&str>) -> Vec<String> {
+    list.map(|l| l.split(',').map(str::trim).filter(|n| !n.is_empty()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Query string of GET /api/projects/{id}/systems/{system}/reachable
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReachabilityQuery {
+    // Fly freely with jumps up to this length instead of following lanes
+    pub max_jump_ly: Option<f64>,
+    // Most jumps taken (default unlimited)
+    pub max_jumps: Option<usize>,
+}
+
+impl ReachabilityQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(range) = self.max_jump_ly {
+            if !(range > 0.0 && range <= MAX_JUMP_LY) {
+                return Err(format!("max_jump_ly must be positive and at most {}", MAX_JUMP_LY));
+            }
+        }
+        if self.max_jumps == Some(0) {
+            return Err("max_jumps must be at least 1".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Query string of GET /api/projects/{id}/systems/{system}/sky
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SkyQuery {
+    // Faintest apparent magnitude returned (default 6.5, the naked-eye limit)
+    pub limiting_magnitude: Option<f64>,
+    // equatorial (RA/Dec, default) or ecliptic
+    pub frame: Option<String>,
+}
+
+impl SkyQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        self.frame()?;
+
+        if let Some(magnitude) = self.limiting_magnitude {
+            if !(-30.0..=30.0).contains(&magnitude) {
+                return Err("limiting_magnitude must be between -30 and 30".to_string());
             }
         }
 
         Ok(())
     }
+
+    pub fn frame(&self) -> Result<SkyFrame, String> {
+        match &self.frame {
+            None => Ok(SkyFrame::default()),
+            Some(name) => SkyFrame::from_name(name).ok_or_else(|| "frame must be 'equatorial' or 'ecliptic'".to_string()),
+        }
+    }
+
+    pub fn limiting_magnitude(&self) -> f64 {
+        self.limiting_magnitude.unwrap_or(NAKED_EYE_MAGNITUDE)
+    }
+}
+
+/// Query string of GET /api/projects/{id}/systems/{system}/skybox
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SkyboxQuery {
+    // equirectangular (default) or cube_map
+    pub projection: Option<String>,
+    // Panorama width or cube face edge in pixels
+    pub size: Option<u32>,
+    // Faintest star drawn (default 6.5, the naked-eye limit)
+    pub limiting_magnitude: Option<f64>,
+    // Draw the stored constellation figures
+    pub constellations: Option<bool>,
+    // Write constellation names
+    pub labels: Option<bool>,
+}
+
+impl SkyboxQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        let projection = self.projection()?;
+
+        if let Some(size) = self.size {
+            let (min, max) = match projection {
+                SkyProjection::Equirectangular => (MIN_PANORAMA_WIDTH, MAX_PANORAMA_WIDTH),
+                SkyProjection::CubeMap => (MIN_CUBE_FACE, MAX_CUBE_FACE),
+            };
+            if !(min..=max).contains(&size) {
+                return Err(format!("size must be between {} and {} for a {} skybox", min, max, projection));
+            }
+        }
+
+        if let Some(magnitude) = self.limiting_magnitude {
+            if !(-30.0..=30.0).contains(&magnitude) {
+                return Err("limiting_magnitude must be between -30 and 30".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn projection(&self) -> Result<SkyProjection, String> {
+        match &self.projection {
+            None => Ok(SkyProjection::default()),
+            Some(name) => SkyProjection::from_name(name)
+                .ok_or_else(|| "projection must be 'equirectangular' or 'cube_map'".to_string()),
+        }
+    }
+
+    pub fn size(&self) -> u32 {
+        match self.projection().unwrap_or_default() {
+            // Keep the 2:1 panorama's height whole
+            SkyProjection::Equirectangular => self.size.unwrap_or(DEFAULT_PANORAMA_WIDTH) & !1,
+            SkyProjection::CubeMap => self.size.unwrap_or(DEFAULT_CUBE_FACE),
+        }
+    }
+
+    pub fn limiting_magnitude(&self) -> f64 {
+        self.limiting_magnitude.unwrap_or(NAKED_EYE_MAGNITUDE)
+    }
+}
+
+/// Body of POST /api/projects/{id}/systems/{system}/constellations
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConstellationRequest {
+    // Random when absent; the response reports the seed used
+    pub seed: Option<u64>,
+    // Faintest star that may join a figure (default: the 300 brightest)
+    pub limiting_magnitude: Option<f64>,
+}
+
+impl ConstellationRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(magnitude) = self.limiting_magnitude {
+            if !(-30.0..=30.0).contains(&magnitude) {
+                return Err("limiting_magnitude must be between -30 and 30".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Body of POST /api/projects/{id}/lanes, and the `lanes` stage of
+/// project generation and import
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LaneRequest {
+    // "max_jump", "k_nearest", "gabriel" or "relative_neighbourhood"
+    pub rule: String,
+    // Longest lane (required for max_jump)
+    pub range_ly: Option<f64>,
+    // Neighbours each system is linked to (k_nearest, default 3)
+    pub k: Option<usize>,
+    // Split the map into this many regions joined only by chokepoint lanes
+    pub chokepoint_regions: Option<usize>,
+    // Seed placing the regions (defaults to the project seed)
+    pub seed: Option<u64>,
+}
+
+impl LaneRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        self.rule()?;
+
+        if let Some(regions) = self.chokepoint_regions {
+            if !(2..=MAX_CHOKEPOINT_REGIONS).contains(&regions) {
+                return Err(format!("chokepoint_regions must be between 2 and {}", MAX_CHOKEPOINT_REGIONS));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn rule(&self) -> Result<LaneRule, String> {
+        match self.rule.as_str() {
+            "max_jump" => match self.range_ly {
+                Some(range_ly) if range_ly > 0.0 && range_ly.is_finite() => Ok(LaneRule::MaxJump { range_ly }),
+                Some(_) => Err("range_ly must be positive".to_string()),
+                None => Err("max_jump requires range_ly".to_string()),
+            },
+            "k_nearest" => match self.k.unwrap_or(3) {
+                k if (1..=MAX_LANE_K).contains(&k) => Ok(LaneRule::KNearest { k }),
+                _ => Err(format!("k must be between 1 and {}", MAX_LANE_K)),
+            },
+            "gabriel" => Ok(LaneRule::Gabriel),
+            "relative_neighbourhood" => Ok(LaneRule::RelativeNeighbourhood),
+            _ => Err("rule must be 'max_jump', 'k_nearest', 'gabriel' or 'relative_neighbourhood'".to_string()),
+        }
+    }
+
+    pub fn params(&self) -> Result<LaneParams, String> {
+        Ok(LaneParams {
+            rule: self.rule()?,
+            chokepoint_regions: self.chokepoint_regions,
+        })
+    }
+}
+
+/// Body of POST /api/projects/{id}/territories
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TerritoryRequest {
+    // "weighted_voronoi" (default), "lane_flood" or "expansion"
+    pub method: Option<String>,
+    // Capitals to found; give these or num_factions
+    pub capitals: Option<Vec<CapitalRequest>>,
+    // Found this many factions at seeded capitals instead
+    pub num_factions: Option<usize>,
+    // Farthest a faction of strength 1 claims (default unlimited)
+    pub max_claim_ly: Option<f64>,
+    // A rival claim within this fraction of the owner's contests a system (default 0.1)
+    pub contested_margin: Option<f64>,
+    // Seed for seeded capitals and expansion (defaults to the project seed)
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CapitalRequest {
+    pub system: String,
+    // Faction name (default "Faction N")
+    pub name: Option<String>,
+    // Relative strength (default 1)
+    pub strength: Option<f64>,
+}
+
+impl TerritoryRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        self.method()?;
+
+        match (&self.capitals, self.num_factions) {
+            (Some(_), Some(_)) => return Err("Give either capitals or num_factions, not both".to_string()),
+            (None, None) => return Err("Give capitals or num_factions".to_string()),
+            (Some(capitals), None) => {
+                if capitals.is_empty() || capitals.len() > MAX_FACTIONS {
+                    return Err(format!("capitals must list between 1 and {} systems", MAX_FACTIONS));
+                }
+                for (i, capital) in capitals.iter().enumerate() {
+                    if let Some(strength) = capital.strength {
+                        if !(strength > 0.0 && strength.is_finite()) {
+                            return Err(format!("Capital {}: strength must be positive", capital.system));
+                        }
+                    }
+                    let name = self.faction_name(i);
+                    if name.trim().is_empty() || name.len() > 100 {
+                        return Err(format!("Capital {}: name must be 1-100 characters", capital.system));
+                    }
+                    if (0..i).any(|j| self.faction_name(j) == name) {
+                        return Err(format!("Faction name '{}' is used twice", name));
+                    }
+                }
+            }
+            (None, Some(count)) => {
+                if !(1..=MAX_FACTIONS).contains(&count) {
+                    return Err(format!("num_factions must be between 1 and {}", MAX_FACTIONS));
+                }
+            }
+        }
+
+        if let Some(range) = self.max_claim_ly {
+            if !(range > 0.0 && range.is_finite()) {
+                return Err("max_claim_ly must be positive".to_string());
+            }
+        }
+        if let Some(margin) = self.contested_margin {
+            if !(0.0..=1.0).contains(&margin) {
+                return Err("contested_margin must be between 0 and 1".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn method(&self) -> Result<PartitionMethod, String> {
+        let name = self.method.as_deref().unwrap_or("weighted_voronoi");
+        PartitionMethod::from_name(name)
+            .ok_or_else(|| "method must be 'weighted_voronoi', 'lane_flood' or 'expansion'".to_string())
+    }
+
+    /// Name of the faction founded at the i-th listed capital
+    pub fn faction_name(&self, i: usize) -> String {
+        self.capitals
+            .as_ref()
+            .and_then(|c| c[i].name.clone())
+            .unwrap_or_else(|| format!("Faction {}", i + 1))
+    }
+
+    pub fn params(&self) -> Result<TerritoryParams, String> {
+        Ok(TerritoryParams {
+            method: self.method()?,
+            max_claim_ly: self.max_claim_ly,
+            contested_margin: self.contested_margin.unwrap_or(DEFAULT_CONTESTED_MARGIN),
+        })
+    }
+}
+
+/// Body of POST /api/projects/{id}/economy
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EconomyRequest {
+    // "lanes" or "straight_line" (default lanes when the project has any)
+    pub network: Option<String>,
+    // Longest trade route, tariffs included (default unlimited)
+    pub max_route_ly: Option<f64>,
+    // Ticks to simulate straight away (default 1)
+    pub ticks: Option<u32>,
+}
+
+impl EconomyRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        self.network()?;
+        validate_ticks(self.ticks)?;
+
+        if let Some(range) = self.max_route_ly {
+            if !(range > 0.0 && range.is_finite()) {
+                return Err("max_route_ly must be positive".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn network(&self) -> Result<Option<TradeNetwork>, String> {
+        self.network
+            .as_deref()
+            .map(|name| TradeNetwork::from_name(name).ok_or_else(|| "network must be 'lanes' or 'straight_line'".to_string()))
+            .transpose()
+    }
+
+    pub fn ticks(&self) -> u32 {
+        self.ticks.unwrap_or(1)
+    }
+}
+
+/// Body of POST /api/projects/{id}/economy/advance
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AdvanceEconomyRequest {
+    // Ticks to simulate (default 1)
+    pub ticks: Option<u32>,
+}
+
+impl AdvanceEconomyRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_ticks(self.ticks)
+    }
+
+    pub fn ticks(&self) -> u32 {
+        self.ticks.unwrap_or(1)
+    }
+}
+
+fn validate_ticks(ticks: Option<u32>) -> Result<(), String> {
+    match ticks {
+        Some(t) if !(1..=MAX_ECONOMY_TICKS).contains(&t) => {
+            Err(format!("ticks must be between 1 and {}", MAX_ECONOMY_TICKS))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Query string of GET /api/projects/{id}/economy/flows
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TradeFlowsQuery {
+    // Only flows of this resource
+    pub resource: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl TradeFlowsQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        self.resource()?;
+        validate_trade_limit(self.limit)
+    }
+
+    pub fn resource(&self) -> Result<Option<Resource>, String> {
+        self.resource
+            .as_deref()
+            .map(|name| Resource::from_name(name).ok_or_else(|| format!("Unknown resource '{}'", name)))
+            .transpose()
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_TRADE_RESULTS)
+    }
+}
+
+/// Query string of GET /api/projects/{id}/economy/hubs
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HubsQuery {
+    pub limit: Option<usize>,
+}
+
+impl HubsQuery {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_trade_limit(self.limit)
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_TRADE_RESULTS)
+    }
+}
+
+fn validate_trade_limit(limit: Option<usize>) -> Result<(), String> {
+    match limit {
+        Some(l) if !(1..=MAX_SPATIAL_RESULTS).contains(&l) => {
+            Err(format!("limit must be between 1 and {}", MAX_SPATIAL_RESULTS))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Parse a comma-separated list of spectral class codes
+fn parse_spectral_classes(list: Option<&str>) -> Result<Option<Vec<SpectralClass>>, String> {
+    let Some(list) = list else {
+        return Ok(None);
+    };
+    list.split(',')
+        .map(|code| {
+            SpectralClass::from_code(code.trim())
+                .ok_or_else(|| format!("Unknown spectral class '{}'", code.trim()))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::models::{Project, Star, StarSystem};
+use crate::models::{CatalogueFormat, DensityZone, EconomyTick, HabitableZone, Photometry, Resource, Ring, SkyFrame, SkyStar, WorldProfile};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GenerateProjectResponse {
@@ -11,9 +11,32 @@ pub struct GenerateProjectResponse {
     pub solo_systems: i32,
     pub binary_systems: i32,
     pub trinary_systems: i32,
+    /// Quadruple and larger systems
+    pub higher_order_systems: i32,
+    pub num_planets_total: i32,
+    pub num_moons_total: i32,
+    pub num_belts_total: i32,
+    pub num_lanes_total: i32,
+    pub seed: u64,
+    pub min_separation_ly: Option<f64>,
+    /// Smallest distance between any two generated systems
+    pub achieved_separation_ly: Option<f64>,
     pub generation_time_ms: u128,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportCatalogueResponse {
+    #[serde(flatten)]
+    pub project: GenerateProjectResponse,
+    pub catalogue: CatalogueFormat,
+    /// Systems built from catalogue stars
+    pub catalogue_systems: i32,
+    /// Procedural systems placed between the completeness radius and the project radius
+    pub filled_systems: i32,
+    /// Catalogue rows inside the radius that could not be classified
+    pub skipped_stars: i32,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProjectListResponse {
     pub projects: Vec<ProjectSummary>,
@@ -27,6 +50,8 @@ pub struct ProjectSummary {
     pub num_stars_total: i32,
     pub created_at: String,
     pub distribution_type: String,
+    /// Catalogue the project was imported from, if any
+    pub catalogue: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,14 +59,38 @@ pub struct ProjectStarsResponse {
     pub project_id: Uuid,
     pub project_name: String,
     pub systems: Vec<SystemWithStars>,
+    /// Lane network between the systems (empty if none was generated)
+    pub lanes: Vec<LaneDetail>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LanesResponse {
+    pub project_id: Uuid,
+    pub num_lanes: usize,
+    /// Lanes that are the only link between two chokepoint regions
+    pub num_chokepoints: usize,
+    pub total_length_ly: f64,
+    pub lanes: Vec<LaneDetail>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LaneDetail {
+    /// System names, e.g. "S0001"
+    pub from: String,
+    pub to: String,
+    pub length_ly: f64,
+    pub chokepoint: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SystemWithStars {
     pub system_name: String,
     pub system_type: String,
+    pub zone_id: Option<Uuid>,
     pub position: Position,
     pub stars: Vec<StarDetail>,
+    /// Component orbits, innermost first (empty for solo systems)
+    pub orbits: Vec<OrbitDetail>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -54,11 +103,444 @@ pub struct Position {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StarDetail {
     pub name: String,
+    /// Full spectral type, e.g. "G2V", "K0III", "DA3", "NS"
     pub spectral_class: String,
+    pub luminosity_class: Option<String>,
     pub temperature_k: f64,
     pub mass_solar: f64,
     pub radius_solar: f64,
     pub luminosity_solar: f64,
+    pub age_gyr: f64,
+    pub metallicity_feh: f64,
+    pub evolutionary_stage: String,
+    /// Habitable-zone boundaries in AU, including light from companions
+    pub habitable_zone: Option<HabitableZone>,
+    /// Magnitude, colour index and display colour; None for dark remnants
+    pub photometry: Option<Photometry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrbitDetail {
+    /// 0 = innermost pair; each higher level orbits everything inside it
+    pub level: i32,
+    /// Star name, or the inner pair (e.g. "S0001ab") for an outer orbit
+    pub primary: String,
+    pub secondary: String,
+    pub semi_major_axis_au: f64,
+    pub eccentricity: f64,
+    pub inclination_deg: f64,
+    pub period_years: f64,
+    pub mass_ratio: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SystemPlanetsResponse {
+    pub project_id: Uuid,
+    pub system_name: String,
+    /// Planets of every star in the system, grouped by star, innermost first
+    pub planets: Vec<PlanetDetail>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanetDetail {
+    pub name: String,
+    /// Host star name, or the stars a circumbinary planet encloses ("S0001ab")
+    pub star: String,
+    /// Orbits the barycentre of a close pair or group (P-type)
+    pub circumbinary: bool,
+    pub orbit_index: i32,
+    /// rocky, super_earth, ice_giant or gas_giant
+    pub planet_type: String,
+    pub semi_major_axis_au: f64,
+    pub eccentricity: f64,
+    pub period_years: f64,
+    pub mass_earth: f64,
+    pub radius_earth: f64,
+    pub ring: Option<Ring>,
+    /// Orbit-averaged flux relative to Earth's
+    pub insolation_earth: f64,
+    pub habitability: f64,
+    /// Temperatures, atmosphere, water, rotation and climate
+    pub profile: WorldProfile,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MoonDetail {
+    pub name: String,
+    pub orbit_index: i32,
+    pub semi_major_axis_km: f64,
+    pub eccentricity: f64,
+    pub period_days: f64,
+    pub mass_earth: f64,
+    pub radius_km: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BeltDetail {
+    pub name: String,
+    /// asteroid_belt or kuiper_belt
+    pub belt_type: String,
+    pub inner_radius_au: f64,
+    pub outer_radius_au: f64,
+    pub mass_earth: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SystemTreeResponse {
+    pub project_id: Uuid,
+    /// The system node; stars, planets and moons nest below it
+    pub root: BodyNode,
+}
+
+/// One body and everything orbiting it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BodyNode {
+    #[serde(flatten)]
+    pub body: Body,
+    /// Stars by name then barycentres, planets and belts outwards, moons
+    /// outwards
+    pub children: Vec<BodyNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Body {
+    System {
+        name: String,
+        system_type: String,
+        position: Position,
+    },
+    Star(StarDetail),
+    /// Centre of mass of a close pair or group, circled by P-type planets
+    Barycentre {
+        name: String,
+        /// Habitable zone from the combined light of the enclosed stars
+        habitable_zone: Option<HabitableZone>,
+    },
+    Planet(PlanetDetail),
+    Moon(MoonDetail),
+    Belt(BeltDetail),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HabitableResponse {
+    pub project_id: Uuid,
+    /// Matching planets before the limit was applied
+    pub total_matches: usize,
+    /// Best first
+    pub planets: Vec<HabitablePlanet>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HabitablePlanet {
+    pub name: String,
+    /// Host star name, or the stars a circumbinary planet encloses
+    pub star: String,
+    pub circumbinary: bool,
+    pub system_name: String,
+    pub system_type: String,
+    /// Host's full spectral type, e.g. "G2V"
+    pub spectral_class: String,
+    pub position: Position,
+    /// Distance from the query point, if one was given
+    pub distance_ly: Option<f64>,
+    pub planet_type: String,
+    pub semi_major_axis_au: f64,
+    pub eccentricity: f64,
+    pub mass_earth: f64,
+    pub radius_earth: f64,
+    pub insolation_earth: f64,
+    pub habitable_zone: Option<HabitableZone>,
+    pub habitability: f64,
+    pub profile: WorldProfile,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanetSearchResponse {
+    pub project_id: Uuid,
+    /// Matching planets before the limit was applied
+    pub total_matches: usize,
+    /// Most habitable first, then by name
+    pub planets: Vec<PlanetMatch>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanetMatch {
+    pub system_name: String,
+    /// Host's full spectral type, e.g. "K3V"
+    pub spectral_class: String,
+    pub position: Position,
+    #[serde(flatten)]
+    pub planet: PlanetDetail,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SkyResponse {
+    pub project_id: Uuid,
+    /// System the sky is seen from
+    pub observer: String,
+    pub frame: SkyFrame,
+    pub limiting_magnitude: f64,
+    /// Brightest first
+    pub stars: Vec<SkyStar>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConstellationsResponse {
+    pub project_id: Uuid,
+    /// System whose sky the constellations belong to
+    pub observer: String,
+    /// Seed the constellations were drawn with (None before any were generated)
+    pub seed: Option<u64>,
+    pub constellations: Vec<ConstellationDetail>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConstellationDetail {
+    pub name: String,
+    /// Brightest first
+    pub stars: Vec<ConstellationStarDetail>,
+    /// Figure lines as pairs of star names
+    pub lines: Vec<[String; 2]>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConstellationStarDetail {
+    /// Bayer-style name, e.g. "Gamma Serrath"
+    pub designation: String,
+    pub star: String,
+    pub system_name: String,
+    pub spectral_class: String,
+    pub ra_deg: f64,
+    pub dec_deg: f64,
+    pub apparent_magnitude: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectZonesResponse {
+    pub project_id: Uuid,
+    pub zones: Vec<DensityZone>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpatialQueryResponse {
+    pub project_id: Uuid,
+    /// Systems matching the query, which may be more than were returned
+    pub total: usize,
+    pub systems: Vec<SpatialMatch>,
+    /// Time spent searching the index, excluding any index build
+    pub query_time_us: u128,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpatialMatch {
+    pub system_name: String,
+    pub system_type: String,
+    pub position: Position,
+    /// Distance from the query point or box centre; for cylinders, from the axis
+    pub distance_ly: f64,
+    /// Cylinder queries only: distance along the axis from its start
+    pub along_axis_ly: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RouteResponse {
+    pub project_id: Uuid,
+    pub from: String,
+    pub to: String,
+    /// "lanes" or "free_flight"
+    pub network: String,
+    /// What the route minimises: "distance", "jumps" or "time"
+    pub cost: String,
+    /// Systems in travel order, both ends included
+    pub systems: Vec<String>,
+    pub hops: Vec<RouteHop>,
+    pub jumps: usize,
+    pub total_distance_ly: f64,
+    /// Only when a speed was given
+    pub total_time_days: Option<f64>,
+    /// Distance between the ends as the crow flies
+    pub straight_line_ly: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RouteHop {
+    pub from: String,
+    pub to: String,
+    pub distance_ly: f64,
+    pub cumulative_distance_ly: f64,
+    pub time_days: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReachabilityResponse {
+    pub project_id: Uuid,
+    pub origin: String,
+    /// "lanes" or "free_flight"
+    pub network: String,
+    pub max_jump_ly: Option<f64>,
+    pub max_jumps: Option<usize>,
+    /// Every system within max_jumps, origin first, then by jumps and distance
+    pub reachable: Vec<ReachableSystem>,
+    /// Systems connected to the origin but more than max_jumps away
+    pub beyond_max_jumps: usize,
+    /// Components of the network, the origin's included
+    pub num_components: usize,
+    /// Components the origin cannot reach at all, largest first
+    pub unreachable_components: Vec<UnreachableComponent>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReachableSystem {
+    pub system_name: String,
+    pub jumps: usize,
+    /// Shortest distance travelled with that many jumps
+    pub distance_ly: f64,
+    /// System the last jump starts from
+    pub previous: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnreachableComponent {
+    pub size: usize,
+    pub systems: Vec<String>,
+    /// Member closest to the origin and its straight-line distance
+    pub nearest_system: String,
+    pub nearest_distance_ly: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FactionsResponse {
+    pub project_id: Uuid,
+    pub num_factions: usize,
+    pub unclaimed_systems: usize,
+    pub contested_systems: usize,
+    /// Largest territory first
+    pub factions: Vec<FactionSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FactionSummary {
+    pub name: String,
+    pub capital: String,
+    pub strength: f64,
+    pub num_systems: usize,
+    pub num_border_systems: usize,
+    pub num_contested_systems: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TerritoryResponse {
+    pub project_id: Uuid,
+    pub faction: String,
+    pub capital: String,
+    pub total: usize,
+    /// Nearest the capital first
+    pub systems: Vec<TerritorySystem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TerritorySystem {
+    pub system_name: String,
+    pub position: Position,
+    pub distance_to_capital_ly: f64,
+    pub border: bool,
+    /// Rival faction whose claim is nearly as strong
+    pub contested_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContestedResponse {
+    pub project_id: Uuid,
+    pub total: usize,
+    pub systems: Vec<ContestedSystem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContestedSystem {
+    pub system_name: String,
+    pub owner: String,
+    pub contested_by: String,
+    pub border: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EconomyResponse {
+    pub project_id: Uuid,
+    pub tick: i32,
+    /// "lanes" or "straight_line"
+    pub network: String,
+    pub max_route_ly: Option<f64>,
+    /// Project-wide totals of the latest tick
+    pub resources: Vec<ResourceSummary>,
+    /// Totals of every tick, oldest first
+    pub history: Vec<EconomyTick>,
+    /// Busiest systems of the latest tick
+    pub hubs: Vec<HubSystem>,
+    /// Largest flows of the latest tick
+    pub flows: Vec<TradeFlowDetail>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceSummary {
+    pub resource: Resource,
+    pub production: f64,
+    pub consumption: f64,
+    pub stock: f64,
+    pub traded: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HubSystem {
+    pub system_name: String,
+    pub faction: Option<String>,
+    /// Volume bought, sold or carried through
+    pub throughput: f64,
+    pub imports: f64,
+    pub exports: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TradeFlowDetail {
+    pub resource: Resource,
+    pub from: String,
+    pub to: String,
+    pub volume: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TradeFlowsResponse {
+    pub project_id: Uuid,
+    pub tick: i32,
+    pub total: usize,
+    pub flows: Vec<TradeFlowDetail>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HubsResponse {
+    pub project_id: Uuid,
+    pub tick: i32,
+    pub hubs: Vec<HubSystem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MarketResponse {
+    pub project_id: Uuid,
+    pub system_name: String,
+    pub tick: i32,
+    pub faction: Option<String>,
+    pub throughput: f64,
+    pub resources: Vec<MarketResource>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MarketResource {
+    pub resource: Resource,
+    pub production: f64,
+    pub consumption: f64,
+    pub stock: f64,
+    pub imported: f64,
+    pub exported: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Celestial coordinate frame for sky positions
+/// The project's axes are the equatorial frame: +x points at RA 0h and +z
+/// at the north celestial pole
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SkyFrame {
+    /// Right ascension and declination
+    #[default]
+    Equatorial,
+    /// Ecliptic longitude and latitude (J2000 obliquity)
+    Ecliptic,
+}
+
+impl SkyFrame {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "equatorial" => Some(SkyFrame::Equatorial),
+            "ecliptic" => Some(SkyFrame::Ecliptic),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SkyFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkyFrame::Equatorial => write!(f, "equatorial"),
+            SkyFrame::Ecliptic => write!(f, "ecliptic"),
+        }
+    }
+}
+
+/// Image layout of a rendered skybox
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkyProjection {
+    /// One 2:1 panorama, RA across and Dec down
+    #[default]
+    Equirectangular,
+    /// Six square faces side by side, ordered +X, -X, +Y, -Y, +Z, -Z
+    CubeMap,
+}
+
+impl SkyProjection {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "equirectangular" => Some(SkyProjection::Equirectangular),
+            "cube_map" => Some(SkyProjection::CubeMap),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SkyProjection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkyProjection::Equirectangular => write!(f, "equirectangular"),
+            SkyProjection::CubeMap => write!(f, "cube_map"),
+        }
+    }
+}
+
+/// A star as seen from another system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkyStar {
+    #[serde(skip)]
+    pub star_id: Uuid,
+    pub name: String,
+    pub system_name: String,
+    /// Full spectral type, e.g. "G2V"
+    pub spectral_class: String,
+    /// Right ascension or ecliptic longitude, 0-360°
+    pub longitude_deg: f64,
+    /// Declination or ecliptic latitude, -90° to 90°
+    pub latitude_deg: f64,
+    pub distance_ly: f64,
+    /// Apparent visual magnitude from the observer (smaller is brighter)
+    pub apparent_magnitude: f64,
+    /// Display colour, "#rrggbb"
+    pub color_hex: String,
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,13 +8,65 @@ pub struct Star {
     pub system_id: Uuid,
     pub project_id: Uuid,
     pub name: String,
+    /// Letter within the system, "a" for the heaviest star; empty for the
+    /// star of a solo system
+    pub component: String,
     pub spectral_class: SpectralClass,
     pub spectral_subclass: Option<i32>,
-    pub luminosity_class: String,
+    /// None for white dwarfs, neutron stars and black holes
+    pub luminosity_class: Option<LuminosityClass>,
     pub temperature_k: f64,
     pub mass_solar: f64,
     pub radius_solar: f64,
     pub luminosity_solar: f64,
+    /// Age in Gyr, shared by every star in a system
+    pub age_gyr: f64,
+    /// Iron abundance [Fe/H] in dex relative to the Sun, shared by every star in a system
+    pub metallicity_feh: f64,
+    pub evolutionary_stage: EvolutionaryStage,
+    /// None for dark remnants, or when a close companion leaves no
+    /// temperate orbit around this star
+    pub habitable_zone: Option<HabitableZone>,
+    /// None for dark remnants
+    pub photometry: Option<Photometry>,
+}
+
+/// Habitable-zone boundaries around one star in AU (Kopparapu et al. 2014)
+/// Conservative: runaway to maximum greenhouse. Optimistic: recent Venus to
+/// early Mars
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct HabitableZone {
+    pub optimistic_inner_au: f64,
+    pub conservative_inner_au: f64,
+    pub conservative_outer_au: f64,
+    pub optimistic_outer_au: f64,
+}
+
+/// Observable brightness and colour of a star
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Photometry {
+    /// Absolute visual magnitude M_V
+    pub absolute_magnitude_v: f64,
+    /// BC_V = M_bol - M_V
+    pub bolometric_correction: f64,
+    /// B-V colour index
+    pub color_index_bv: f64,
+    /// Blackbody display colour in sRGB, e.g. "#fff4ea"
+    pub color_hex: String,
+}
+
+impl Star {
+    /// Full spectral type, e.g. "G2V", "K0III", "DA3", "NS"
+    pub fn spectral_type(&self) -> String {
+        let mut s = self.spectral_class.code().to_string();
+        if let Some(subclass) = self.spectral_subclass {
+            s.push_str(&subclass.to_string());
+        }
+        if let Some(lc) = self.luminosity_class {
+            s.push_str(lc.code());
+        }
+        s
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -25,51 +78,99 @@ pub enum SpectralClass {
     G, // Yellow (like Sun)
     K, // Orange
     M, // Red, cool
+    L, // Brown dwarf, dusty
+    T, // Brown dwarf, methane
+    Y, // Brown dwarf, coolest
+    DA, // White dwarf, hydrogen lines
+    DB, // White dwarf, neutral helium
+    DC, // White dwarf, featureless
+    DO, // White dwarf, ionised helium (hottest)
+    DQ, // White dwarf, carbon
+    DZ, // White dwarf, metals
+    NS, // Neutron star
+    BH, // Stellar-mass black hole
 }
 
 impl SpectralClass {
-    pub fn to_char(&self) -> char {
+    /// Class code as stored in the database ("G", "DA", "NS", ...)
+    pub fn code(self) -> &'static str {
         match self {
-            SpectralClass::O => 'O',
-            SpectralClass::B => 'B',
-            SpectralClass::A => 'A',
-            SpectralClass::F => 'F',
-            SpectralClass::G => 'G',
-            SpectralClass::K => 'K',
-            SpectralClass::M => 'M',
+            SpectralClass::O => "O",
+            SpectralClass::B => "B",
+            SpectralClass::A => "A",
+            SpectralClass::F => "F",
+            SpectralClass::G => "G",
+            SpectralClass::K => "K",
+            SpectralClass::M => "M",
+            SpectralClass::L => "L",
+            SpectralClass::T => "T",
+            SpectralClass::Y => "Y",
+            SpectralClass::DA => "DA",
+            SpectralClass::DB => "DB",
+            SpectralClass::DC => "DC",
+            SpectralClass::DO => "DO",
+            SpectralClass::DQ => "DQ",
+            SpectralClass::DZ => "DZ",
+            SpectralClass::NS => "NS",
+            SpectralClass::BH => "BH",
         }
     }
 
-    /// Returns typical properties for this spectral class
-    /// (temperature, mass, radius, luminosity)
-    pub fn typical_properties(&self) -> (f64, f64, f64, f64) {
-        match self {
-            // (temp_k, mass_solar, radius_solar, luminosity_solar)
-            SpectralClass::O => (40000.0, 40.0, 15.0, 100000.0),
-            SpectralClass::B => (20000.0, 10.0, 7.0, 1000.0),
-            SpectralClass::A => (8500.0, 2.5, 2.5, 40.0),
-            SpectralClass::F => (6750.0, 1.5, 1.5, 6.0),
-            SpectralClass::G => (5500.0, 1.0, 1.0, 1.0), // Sun-like
-            SpectralClass::K => (4500.0, 0.7, 0.8, 0.4),
-            SpectralClass::M => (3000.0, 0.3, 0.4, 0.04),
-        }
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::all_classes().into_iter().find(|c| c.code() == code)
     }
 
-    /// Get relative weight for random generation
-    /// M stars are 20x more common than O stars
-    pub fn weight(&self) -> u32 {
+    /// Effective temperature range (min_k, max_k) covered by this class
+    /// Only the O-Y temperature sequence has one; white dwarf subclasses
+    /// use a temperature index instead and remnants have none
+    pub fn temperature_range(self) -> Option<(f64, f64)> {
         match self {
-            SpectralClass::O => 1,
-            SpectralClass::B => 2,
-            SpectralClass::A => 4,
-            SpectralClass::F => 7,
-            SpectralClass::G => 11,
-            SpectralClass::K => 15,
-            SpectralClass::M => 20,
+            SpectralClass::O => Some((30000.0, 50000.0)),
+            SpectralClass::B => Some((10000.0, 30000.0)),
+            SpectralClass::A => Some((7500.0, 10000.0)),
+            SpectralClass::F => Some((6000.0, 7500.0)),
+            SpectralClass::G => Some((5200.0, 6000.0)),
+            SpectralClass::K => Some((3700.0, 5200.0)),
+            SpectralClass::M => Some((2400.0, 3700.0)),
+            SpectralClass::L => Some((1300.0, 2400.0)),
+            SpectralClass::T => Some((550.0, 1300.0)),
+            SpectralClass::Y => Some((250.0, 550.0)),
+            _ => None,
         }
     }
 
-    pub fn all_classes() -> [SpectralClass; 7] {
+    /// Spectral class and 0-9 subclass (0 = hottest) for a temperature
+    /// Subclasses split each class evenly in log temperature
+    pub fn from_temperature(temperature_k: f64) -> (SpectralClass, i32) {
+        let class = Self::temperature_sequence()
+            .into_iter()
+            .find(|c| c.temperature_range().is_some_and(|(min_t, _)| temperature_k >= min_t))
+            .unwrap_or(SpectralClass::Y);
+
+        let (min_t, max_t) = class.temperature_range().unwrap_or((250.0, 550.0));
+        let fraction = (max_t / temperature_k).ln() / (max_t / min_t).ln();
+        let subclass = ((fraction * 10.0).floor() as i32).clamp(0, 9);
+
+        (class, subclass)
+    }
+
+    /// Classes ordered hot to cool along the temperature sequence
+    pub fn temperature_sequence() -> [SpectralClass; 10] {
+        [
+            SpectralClass::O,
+            SpectralClass::B,
+            SpectralClass::A,
+            SpectralClass::F,
+            SpectralClass::G,
+            SpectralClass::K,
+            SpectralClass::M,
+            SpectralClass::L,
+            SpectralClass::T,
+            SpectralClass::Y,
+        ]
+    }
+
+    pub fn all_classes() -> [SpectralClass; 18] {
         [
             SpectralClass::O,
             SpectralClass::B,
@@ -78,14 +179,126 @@ impl SpectralClass {
             SpectralClass::G,
             SpectralClass::K,
             SpectralClass::M,
+            SpectralClass::L,
+            SpectralClass::T,
+            SpectralClass::Y,
+            SpectralClass::DA,
+            SpectralClass::DB,
+            SpectralClass::DC,
+            SpectralClass::DO,
+            SpectralClass::DQ,
+            SpectralClass::DZ,
+            SpectralClass::NS,
+            SpectralClass::BH,
         ]
     }
 }
 
+impl fmt::Display for SpectralClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// Yerkes (MK) luminosity class
+#[allow(clippy::upper_case_acronyms)] // Roman numerals, not acronyms
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum LuminosityClass {
+    Ia,  // Luminous supergiant
+    Ib,  // Less luminous supergiant
+    II,  // Bright giant
+    III, // Giant
+    IV,  // Subgiant
+    V,   // Main sequence (dwarf)
+}
+
+impl LuminosityClass {
+    pub fn code(self) -> &'static str {
+        match self {
+            LuminosityClass::Ia => "Ia",
+            LuminosityClass::Ib => "Ib",
+            LuminosityClass::II => "II",
+            LuminosityClass::III => "III",
+            LuminosityClass::IV => "IV",
+            LuminosityClass::V => "V",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "Ia" => Some(LuminosityClass::Ia),
+            "Ib" => Some(LuminosityClass::Ib),
+            "II" => Some(LuminosityClass::II),
+            "III" => Some(LuminosityClass::III),
+            "IV" => Some(LuminosityClass::IV),
+            "V" => Some(LuminosityClass::V),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LuminosityClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// Where a star is in its life
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EvolutionaryStage {
+    /// Substellar object that never burned hydrogen
+    BrownDwarf,
+    MainSequence,
+    Subgiant,
+    /// Red giant branch through asymptotic giant branch
+    Giant,
+    BrightGiant,
+    Supergiant,
+    WhiteDwarf,
+    NeutronStar,
+    BlackHole,
+}
+
+impl EvolutionaryStage {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "brown_dwarf" => Some(EvolutionaryStage::BrownDwarf),
+            "main_sequence" => Some(EvolutionaryStage::MainSequence),
+            "subgiant" => Some(EvolutionaryStage::Subgiant),
+            "giant" => Some(EvolutionaryStage::Giant),
+            "bright_giant" => Some(EvolutionaryStage::BrightGiant),
+            "supergiant" => Some(EvolutionaryStage::Supergiant),
+            "white_dwarf" => Some(EvolutionaryStage::WhiteDwarf),
+            "neutron_star" => Some(EvolutionaryStage::NeutronStar),
+            "black_hole" => Some(EvolutionaryStage::BlackHole),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EvolutionaryStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvolutionaryStage::BrownDwarf => write!(f, "brown_dwarf"),
+            EvolutionaryStage::MainSequence => write!(f, "main_sequence"),
+            EvolutionaryStage::Subgiant => write!(f, "subgiant"),
+            EvolutionaryStage::Giant => write!(f, "giant"),
+            EvolutionaryStage::BrightGiant => write!(f, "bright_giant"),
+            EvolutionaryStage::Supergiant => write!(f, "supergiant"),
+            EvolutionaryStage::WhiteDwarf => write!(f, "white_dwarf"),
+            EvolutionaryStage::NeutronStar => write!(f, "neutron_star"),
+            EvolutionaryStage::BlackHole => write!(f, "black_hole"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StarProperties {
     pub spectral_class: SpectralClass,
-    pub spectral_subclass: i32,
+    pub spectral_subclass: Option<i32>,
+    pub luminosity_class: Option<LuminosityClass>,
+    pub evolutionary_stage: EvolutionaryStage,
     pub temperature_k: f64,
     pub mass_solar: f64,
     pub radius_solar: f64,
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use std::fmt;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StarSystem {
@@ -10,6 +11,14 @@ pub struct StarSystem {
     pub x_ly: f64,
     pub y_ly: f64,
     pub z_ly: f64,
+    /// Density zone that placed this system (None for background systems)
+    pub zone_id: Option<Uuid>,
+    /// Faction owning this system (None when unclaimed)
+    pub faction_id: Option<Uuid>,
+    /// Rival faction whose claim is nearly as strong as the owner's
+    pub contested_by: Option<Uuid>,
+    /// Next to a system of another faction or an unclaimed one
+    pub border: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -18,22 +27,60 @@ pub enum SystemType {
     Solo,
     Binary,
     Trinary,
+    Quadruple,
+    Quintuple,
+    Sextuple,
 }
 
 impl SystemType {
-    pub fn to_string(&self) -> String {
-        match self {
-            SystemType::Solo => "solo".to_string(),
-            SystemType::Binary => "binary".to_string(),
-            SystemType::Trinary => "trinary".to_string(),
-        }
-    }
+    /// Most stars a generated system can hold
+    pub const MAX_STARS: usize = 6;
 
     pub fn num_stars(&self) -> usize {
         match self {
             SystemType::Solo => 1,
             SystemType::Binary => 2,
             SystemType::Trinary => 3,
+            SystemType::Quadruple => 4,
+            SystemType::Quintuple => 5,
+            SystemType::Sextuple => 6,
+        }
+    }
+
+    /// System type holding `num_stars` stars (clamped to 1-6)
+    pub fn from_num_stars(num_stars: usize) -> Self {
+        match num_stars {
+            0 | 1 => SystemType::Solo,
+            2 => SystemType::Binary,
+            3 => SystemType::Trinary,
+            4 => SystemType::Quadruple,
+            5 => SystemType::Quintuple,
+            _ => SystemType::Sextuple,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "solo" => Some(SystemType::Solo),
+            "binary" => Some(SystemType::Binary),
+            "trinary" => Some(SystemType::Trinary),
+            "quadruple" => Some(SystemType::Quadruple),
+            "quintuple" => Some(SystemType::Quintuple),
+            "sextuple" => Some(SystemType::Sextuple),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SystemType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SystemType::Solo => write!(f, "solo"),
+            SystemType::Binary => write!(f, "binary"),
+            SystemType::Trinary => write!(f, "trinary"),
+            SystemType::Quadruple => write!(f, "quadruple"),
+            SystemType::Quintuple => write!(f, "quintuple"),
+            SystemType::Sextuple => write!(f, "sextuple"),
         }
     }
 }
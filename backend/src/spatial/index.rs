@@ -0,0 +1,132 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+use crate::models::{Position3D, StarSystem};
+use super::KdTree;
+
+/// Most project indexes kept in memory; the oldest is dropped beyond this
+const MAX_CACHED_PROJECTS: usize = 32;
+
+/// Star systems of one project with a k-d tree over their positions
+#[derive(Debug)]
+pub struct ProjectIndex {
+    pub systems: Vec<StarSystem>,
+    /// Position of each system, in the same order
+    pub positions: Vec<Position3D>,
+    pub tree: KdTree,
+    by_name: HashMap<String, usize>,
+}
+
+impl ProjectIndex {
+    pub fn new(systems: Vec<StarSystem>) -> Self {
+        let positions: Vec<Position3D> = systems.iter().map(system_position).collect();
+        let by_name = systems.iter().enumerate().map(|(i, s)| (s.name.clone(), i)).collect();
+        ProjectIndex { tree: KdTree::new(&positions), systems, positions, by_name }
+    }
+
+    /// Index of the system with this name
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.by_name.get(name).copied()
+    }
+
+    pub fn position(&self, index: usize) -> Position3D {
+        self.positions[index]
+    }
+}
+
+fn system_position(system: &StarSystem) -> Position3D {
+    Position3D { x: system.x_ly, y: system.y_ly, z: system.z_ly }
+}
+
+/// Project indexes shared between requests
+/// Systems never change after generation, so an index stays valid for
+/// the life of its project
+#[derive(Debug, Default)]
+pub struct SpatialCache {
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    indexes: HashMap<Uuid, Arc<ProjectIndex>>,
+    // Insertion order, oldest first
+    built: VecDeque<Uuid>,
+}
+
+impl SpatialCache {
+    pub fn get(&self, project_id: Uuid) -> Option<Arc<ProjectIndex>> {
+        self.state.lock().unwrap().indexes.get(&project_id).cloned()
+    }
+
+    /// Keep an index, returning the one already cached if another request
+    /// built it first
+    pub fn insert(&self, project_id: Uuid, index: ProjectIndex) -> Arc<ProjectIndex> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(existing) = state.indexes.get(&project_id) {
+            return existing.clone();
+        }
+
+        while state.built.len() >= MAX_CACHED_PROJECTS {
+            if let Some(oldest) = state.built.pop_front() {
+                state.indexes.remove(&oldest);
+            }
+        }
+        let index = Arc::new(index);
+        state.indexes.insert(project_id, index.clone());
+        state.built.push_back(project_id);
+        index
+    }
+
+    /// Forget a project's index after its systems change
+    pub fn remove(&self, project_id: Uuid) {
+        let mut state = self.state.lock().unwrap();
+        state.indexes.remove(&project_id);
+        state.built.retain(|&id| id != project_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SystemType;
+
+    fn system(name: &str, x: f64) -> StarSystem {
+        StarSystem {
+            id: Some(Uuid::new_v4()),
+            project_id: Uuid::nil(),
+            name: name.to_string(),
+            system_type: SystemType::Solo,
+            x_ly: x,
+            y_ly: 0.0,
+            z_ly: 0.0,
+            zone_id: None,
+            faction_id: None,
+            contested_by: None,
+            border: false,
+        }
+    }
+
+    #[test]
+    fn test_cache_keeps_newest_projects() {
+        let cache = SpatialCache::default();
+        let first = Uuid::new_v4();
+        let index = cache.insert(first, ProjectIndex::new(vec![system("S0001", 0.0), system("S0002", 4.0)]));
+        assert_eq!(index.find("S0002"), Some(1));
+        assert_eq!(index.position(1).x, 4.0);
+        assert!(index.find("S0003").is_none());
+
+        // A second build of the same project yields the cached index
+        let again = cache.insert(first, ProjectIndex::new(Vec::new()));
+        assert_eq!(again.systems.len(), 2);
+
+        // A removed project is rebuilt on its next insert
+        cache.remove(first);
+        assert!(cache.get(first).is_none());
+        cache.insert(first, ProjectIndex::new(vec![system("S0001", 0.0), system("S0002", 4.0)]));
+
+        for _ in 0..MAX_CACHED_PROJECTS {
+            cache.insert(Uuid::new_v4(), ProjectIndex::new(Vec::new()));
+        }
+        assert!(cache.get(first).is_none());
+    }
+}
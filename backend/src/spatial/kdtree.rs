@@ -0,0 +1,294 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use crate::models::Position3D;
+
+/// Balanced 3-d tree over a fixed set of points
+/// The tree is implicit: `order` holds point indices so that the middle
+/// element of every range splits it on the axis cycling with depth, the
+/// lower half before it and the upper half after it
+#[derive(Debug, Clone)]
+pub struct KdTree {
+    points: Vec<[f64; 3]>,
+    order: Vec<usize>,
+}
+
+/// Result of a cylinder query
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CylinderHit {
+    pub index: usize,
+    /// Perpendicular distance from the axis
+    pub axis_distance: f64,
+    /// Distance along the axis from its start
+    pub along: f64,
+}
+
+impl KdTree {
+    pub fn new(positions: &[Position3D]) -> Self {
+        let points: Vec<[f64; 3]> = positions.iter().map(|p| [p.x, p.y, p.z]).collect();
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        build(&points, &mut order, 0);
+        KdTree { points, order }
+    }
+
+    /// The `k` points closest to `target` as (index, distance), nearest first
+    pub fn nearest(&self, target: &Position3D, k: usize) -> Vec<(usize, f64)> {
+        self.nearest_where(target, k, |_| true)
+    }
+
+    /// The `k` closest points that `accept` lets through
+    pub fn nearest_where<F: Fn(usize) -> bool>(&self, target: &Position3D, k: usize, accept: F) -> Vec<(usize, f64)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let target = [target.x, target.y, target.z];
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        self.nearest_in(0, self.order.len(), 0, &target, k, &accept, &mut best);
+
+        let mut found: Vec<(usize, f64)> = best.into_iter().map(|c| (c.index, c.distance_sq.sqrt())).collect();
+        found.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        found
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn nearest_in<F: Fn(usize) -> bool>(
+        &self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        target: &[f64; 3],
+        k: usize,
+        accept: &F,
+        best: &mut BinaryHeap<Candidate>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let index = self.order[mid];
+        let point = &self.points[index];
+
+        if accept(index) {
+            best.push(Candidate { distance_sq: distance_sq(point, target), index });
+            if best.len() > k {
+                best.pop();
+            }
+        }
+
+        let axis = depth % 3;
+        let offset = target[axis] - point[axis];
+        let (near, far) = if offset < 0.0 { ((lo, mid), (mid + 1, hi)) } else { ((mid + 1, hi), (lo, mid)) };
+        self.nearest_in(near.0, near.1, depth + 1, target, k, accept, best);
+        // The far side can only help if the splitting plane is closer than the worst kept point
+        if best.len() < k || offset * offset < best.peek().map_or(f64::INFINITY, |c| c.distance_sq) {
+            self.nearest_in(far.0, far.1, depth + 1, target, k, accept, best);
+        }
+    }
+
+    /// Points within `radius` of `center` as (index, distance), nearest first
+    pub fn within_radius(&self, center: &Position3D, radius: f64) -> Vec<(usize, f64)> {
+        let center = [center.x, center.y, center.z];
+        let bounds = ([center[0] - radius, center[1] - radius, center[2] - radius], [center[0] + radius, center[1] + radius, center[2] + radius]);
+        let radius_sq = radius * radius;
+
+        let mut found = Vec::new();
+        self.range(0, self.order.len(), 0, &bounds, &mut |index, point| {
+            let d = distance_sq(point, &center);
+            if d <= radius_sq {
+                found.push((index, d.sqrt()));
+            }
+        });
+        found.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        found
+    }
+
+    /// Indices of the points inside the axis-aligned box, in index order
+    pub fn within_box(&self, min: &Position3D, max: &Position3D) -> Vec<usize> {
+        let bounds = ([min.x, min.y, min.z], [max.x, max.y, max.z]);
+        let mut found = Vec::new();
+        self.range(0, self.order.len(), 0, &bounds, &mut |index, _| found.push(index));
+        found.sort_unstable();
+        found
+    }
+
+    /// Points within `radius` of the segment from `start` to `end`, ordered
+    /// along it; the cylinder is capped flat at both ends
+    pub fn within_cylinder(&self, start: &Position3D, end: &Position3D, radius: f64) -> Vec<CylinderHit> {
+        let a = [start.x, start.y, start.z];
+        let b = [end.x, end.y, end.z];
+        let axis = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let length = distance_sq(&a, &b).sqrt();
+        let bounds = (
+            [a[0].min(b[0]) - radius, a[1].min(b[1]) - radius, a[2].min(b[2]) - radius],
+            [a[0].max(b[0]) + radius, a[1].max(b[1]) + radius, a[2].max(b[2]) + radius],
+        );
+
+        let mut found = Vec::new();
+        self.range(0, self.order.len(), 0, &bounds, &mut |index, point| {
+            let along = if length > 0.0 {
+                ((point[0] - a[0]) * axis[0] + (point[1] - a[1]) * axis[1] + (point[2] - a[2]) * axis[2]) / length
+            } else {
+                0.0
+            };
+            if !(0.0..=length).contains(&along) {
+                return;
+            }
+            let t = if length > 0.0 { along / length } else { 0.0 };
+            let foot = [a[0] + axis[0] * t, a[1] + axis[1] * t, a[2] + axis[2] * t];
+            let axis_distance = distance_sq(point, &foot).sqrt();
+            if axis_distance <= radius {
+                found.push(CylinderHit { index, axis_distance, along });
+            }
+        });
+        found.sort_by(|x, y| x.along.total_cmp(&y.along).then(x.index.cmp(&y.index)));
+        found
+    }
+
+    /// Visit every point inside `bounds`, skipping subtrees that lie wholly outside
+    fn range<F: FnMut(usize, &[f64; 3])>(&self, lo: usize, hi: usize, depth: usize, bounds: &([f64; 3], [f64; 3]), visit: &mut F) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let index = self.order[mid];
+        let point = &self.points[index];
+        let (min, max) = bounds;
+
+        if (0..3).all(|i| min[i] <= point[i] && point[i] <= max[i]) {
+            visit(index, point);
+        }
+
+        let axis = depth % 3;
+        if min[axis] <= point[axis] {
+            self.range(lo, mid, depth + 1, bounds, visit);
+        }
+        if max[axis] >= point[axis] {
+            self.range(mid + 1, hi, depth + 1, bounds, visit);
+        }
+    }
+}
+
+fn build(points: &[[f64; 3]], order: &mut [usize], depth: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+    let mid = order.len() / 2;
+    let axis = depth % 3;
+    order.select_nth_unstable_by(mid, |&a, &b| points[a][axis].total_cmp(&points[b][axis]));
+
+    let (lower, upper) = order.split_at_mut(mid);
+    build(points, lower, depth + 1);
+    build(points, &mut upper[1..], depth + 1);
+}
+
+fn distance_sq(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+/// Entry of the k-nearest max-heap, farthest on top
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance_sq: f64,
+    index: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance_sq.total_cmp(&other.distance_sq).then(self.index.cmp(&other.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::seeded_rng;
+    use rand::Rng;
+
+    fn random_positions(n: usize, seed: u64) -> Vec<Position3D> {
+        let mut rng = seeded_rng(seed);
+        (0..n)
+            .map(|_| Position3D {
+                x: rng.gen_range(-100.0..100.0),
+                y: rng.gen_range(-100.0..100.0),
+                z: rng.gen_range(-20.0..20.0),
+            })
+            .collect()
+    }
+
+    fn distance(a: &Position3D, b: &Position3D) -> f64 {
+        ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn test_nearest_and_radius_match_brute_force() {
+        let positions = random_positions(2000, 7);
+        let tree = KdTree::new(&positions);
+
+        for target in random_positions(25, 8) {
+            let mut expected: Vec<(usize, f64)> = positions.iter().enumerate().map(|(i, p)| (i, distance(p, &target))).collect();
+            expected.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+
+            let nearest = tree.nearest(&target, 10);
+            assert_eq!(nearest.iter().map(|n| n.0).collect::<Vec<_>>(), expected[..10].iter().map(|e| e.0).collect::<Vec<_>>());
+            assert!((nearest[0].1 - expected[0].1).abs() < 1e-12);
+
+            let within = tree.within_radius(&target, 15.0);
+            let expected_within: Vec<usize> = expected.iter().take_while(|e| e.1 <= 15.0).map(|e| e.0).collect();
+            assert_eq!(within.iter().map(|w| w.0).collect::<Vec<_>>(), expected_within);
+        }
+
+        assert_eq!(tree.nearest(&positions[0], 5000).len(), 2000);
+        let odd = tree.nearest_where(&positions[0], 3, |i| i % 2 == 1);
+        assert!(odd.iter().all(|(i, _)| i % 2 == 1) && odd.len() == 3);
+        assert!(tree.nearest(&positions[0], 0).is_empty());
+        assert!(KdTree::new(&[]).nearest(&positions[0], 3).is_empty());
+    }
+
+    #[test]
+    fn test_box_and_cylinder_match_brute_force() {
+        let positions = random_positions(2000, 11);
+        let tree = KdTree::new(&positions);
+
+        let min = Position3D { x: -30.0, y: 10.0, z: -5.0 };
+        let max = Position3D { x: 25.0, y: 60.0, z: 20.0 };
+        let expected: Vec<usize> = (0..positions.len())
+            .filter(|&i| {
+                let p = &positions[i];
+                (min.x..=max.x).contains(&p.x) && (min.y..=max.y).contains(&p.y) && (min.z..=max.z).contains(&p.z)
+            })
+            .collect();
+        assert_eq!(tree.within_box(&min, &max), expected);
+
+        let start = Position3D { x: -80.0, y: -60.0, z: -10.0 };
+        let end = Position3D { x: 70.0, y: 50.0, z: 10.0 };
+        let hits = tree.within_cylinder(&start, &end, 8.0);
+        let length = distance(&start, &end);
+        let expected: Vec<usize> = (0..positions.len())
+            .filter(|&i| {
+                let p = &positions[i];
+                let along = ((p.x - start.x) * (end.x - start.x) + (p.y - start.y) * (end.y - start.y) + (p.z - start.z) * (end.z - start.z)) / length;
+                let axis_distance = (distance(p, &start).powi(2) - along * along).max(0.0).sqrt();
+                (0.0..=length).contains(&along) && axis_distance <= 8.0
+            })
+            .collect();
+        let mut found: Vec<usize> = hits.iter().map(|h| h.index).collect();
+        found.sort_unstable();
+        assert_eq!(found, expected);
+        assert!(!hits.is_empty());
+        assert!(hits.windows(2).all(|w| w[0].along <= w[1].along));
+        assert!(hits.iter().all(|h| h.axis_distance <= 8.0 && h.along <= length));
+    }
+}
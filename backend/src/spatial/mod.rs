@@ -0,0 +1,7 @@
+pub mod kdtree;
+pub mod index;
+pub mod routing;
+
+pub use kdtree::*;
+pub use index::*;
+pub use routing::*;
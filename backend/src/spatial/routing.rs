@@ -0,0 +1,409 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+use crate::models::Position3D;
+use super::KdTree;
+
+/// Days in a Julian year, for travel times
+pub const DAYS_PER_YEAR: f64 = 365.25;
+
+/// Connections a route may follow between systems
+#[derive(Debug)]
+pub enum TravelGraph<'a> {
+    /// Stored lanes as adjacency lists of (neighbour, length in ly)
+    Lanes(Vec<Vec<(usize, f64)>>),
+    /// A jump to any system up to `max_jump_ly` away
+    FreeFlight { tree: &'a KdTree, max_jump_ly: f64 },
+}
+
+impl TravelGraph<'_> {
+    /// Lane graph over `n` systems from (system, system, length) triples
+    pub fn from_lanes<I: IntoIterator<Item = (usize, usize, f64)>>(n: usize, lanes: I) -> Self {
+        let mut adjacency = vec![Vec::new(); n];
+        for (a, b, length) in lanes {
+            adjacency[a].push((b, length));
+            adjacency[b].push((a, length));
+        }
+        TravelGraph::Lanes(adjacency)
+    }
+
+    /// Systems one jump from `i`, with the jump length
+    pub fn neighbours(&self, positions: &[Position3D], i: usize) -> Vec<(usize, f64)> {
+        match self {
+            TravelGraph::Lanes(adjacency) => adjacency[i].clone(),
+            TravelGraph::FreeFlight { tree, max_jump_ly } => {
+                tree.within_radius(&positions[i], *max_jump_ly).into_iter().filter(|&(j, _)| j != i).collect()
+            }
+        }
+    }
+
+    /// Upper bound on a single jump, for the A* jump estimate
+    fn longest_jump(&self) -> f64 {
+        match self {
+            TravelGraph::Lanes(adjacency) => {
+                adjacency.iter().flatten().map(|&(_, length)| length).fold(0.0, f64::max)
+            }
+            TravelGraph::FreeFlight { max_jump_ly, .. } => *max_jump_ly,
+        }
+    }
+}
+
+/// What a route minimises
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RouteCost {
+    /// Light-years flown
+    Distance,
+    /// Number of jumps
+    Jumps,
+    /// Days at `speed_c` times light speed, plus `jump_time_days` per jump
+    Time { speed_c: f64, jump_time_days: f64 },
+}
+
+impl fmt::Display for RouteCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteCost::Distance => write!(f, "distance"),
+            RouteCost::Jumps => write!(f, "jumps"),
+            RouteCost::Time { .. } => write!(f, "time"),
+        }
+    }
+}
+
+impl RouteCost {
+    fn of_jump(&self, length_ly: f64) -> f64 {
+        match *self {
+            RouteCost::Distance => length_ly,
+            RouteCost::Jumps => 1.0,
+            RouteCost::Time { speed_c, jump_time_days } => length_ly / speed_c * DAYS_PER_YEAR + jump_time_days,
+        }
+    }
+
+    /// Lower bound on the cost of covering a straight-line distance
+    fn estimate(&self, distance_ly: f64, longest_jump_ly: f64) -> f64 {
+        let jumps = if distance_ly > 0.0 && longest_jump_ly > 0.0 { (distance_ly / longest_jump_ly).ceil() } else { 0.0 };
+        match *self {
+            RouteCost::Distance => distance_ly,
+            RouteCost::Jumps => jumps,
+            RouteCost::Time { speed_c, jump_time_days } => distance_ly / speed_c * DAYS_PER_YEAR + jumps * jump_time_days,
+        }
+    }
+}
+
+/// Cheapest route from `from` to `to` as the systems visited, both ends
+/// included (A*; ties in cost go to the shorter route). Systems marked in
+/// `blocked` are never entered, though the ends may be. None if `to`
+/// cannot be reached
+pub fn find_route(
+    graph: &TravelGraph,
+    positions: &[Position3D],
+    from: usize,
+    to: usize,
+    cost: RouteCost,
+    blocked: &[bool],
+) -> Option<Vec<usize>> {
+    let longest_jump = graph.longest_jump();
+    let estimate = |i: usize| {
+        let d = distance(&positions[i], &positions[to]);
+        (cost.estimate(d, longest_jump), d)
+    };
+
+    let n = positions.len();
+    let mut best: Vec<(f64, f64)> = vec![(f64::INFINITY, f64::INFINITY); n];
+    let mut previous: Vec<Option<usize>> = vec![None; n];
+    let mut done = vec![false; n];
+    let mut open = BinaryHeap::new();
+
+    best[from] = (0.0, 0.0);
+    let (h_cost, h_distance) = estimate(from);
+    open.push(Open { cost: h_cost, distance: h_distance, system: from });
+
+    while let Some(Open { system, .. }) = open.pop() {
+        if done[system] {
+            continue;
+        }
+        done[system] = true;
+        if system == to {
+            let mut route = vec![to];
+            while let Some(p) = previous[*route.last().unwrap()] {
+                route.push(p);
+            }
+            route.reverse();
+            return Some(route);
+        }
+
+        for (next, length) in graph.neighbours(positions, system) {
+            if done[next] || (blocked[next] && next != to) {
+                continue;
+            }
+            let candidate = (best[system].0 + cost.of_jump(length), best[system].1 + length);
+            if lexical(candidate, best[next]) == Ordering::Less {
+                best[next] = candidate;
+                previous[next] = Some(system);
+                let (h_cost, h_distance) = estimate(next);
+                open.push(Open { cost: candidate.0 + h_cost, distance: candidate.1 + h_distance, system: next });
+            }
+        }
+    }
+
+    None
+}
+
+/// How a system is reached from an origin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reach {
+    pub jumps: usize,
+    /// Length of the shortest route with that many jumps
+    pub distance_ly: f64,
+    /// System the last jump starts from (None for the origin)
+    pub previous: Option<usize>,
+}
+
+/// Fewest jumps, then shortest distance, from `from` to every system at
+/// most `max_jumps` jumps away (Dijkstra on jumps and distance); None for
+/// the rest
+pub fn reachable(graph: &TravelGraph, positions: &[Position3D], from: usize, max_jumps: Option<usize>) -> Vec<Option<Reach>> {
+    let mut reach: Vec<Option<Reach>> = vec![None; positions.len()];
+    let mut done = vec![false; positions.len()];
+    let mut open = BinaryHeap::new();
+
+    reach[from] = Some(Reach { jumps: 0, distance_ly: 0.0, previous: None });
+    open.push(Open { cost: 0.0, distance: 0.0, system: from });
+
+    while let Some(Open { system, .. }) = open.pop() {
+        if done[system] {
+            continue;
+        }
+        done[system] = true;
+        let here = reach[system].unwrap();
+        if max_jumps.is_some_and(|max| here.jumps >= max) {
+            continue;
+        }
+
+        for (next, length) in graph.neighbours(positions, system) {
+            if done[next] {
+                continue;
+            }
+            let candidate = Reach { jumps: here.jumps + 1, distance_ly: here.distance_ly + length, previous: Some(system) };
+            let better = reach[next].is_none_or(|r| {
+                lexical((candidate.jumps as f64, candidate.distance_ly), (r.jumps as f64, r.distance_ly)) == Ordering::Less
+            });
+            if better {
+                reach[next] = Some(candidate);
+                open.push(Open { cost: candidate.jumps as f64, distance: candidate.distance_ly, system: next });
+            }
+        }
+    }
+
+    reach
+}
+
+/// Cheapest way to a system from the nearest of several sources
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathStep {
+    pub cost: f64,
+    /// System the path arrives from (None at a source)
+    pub previous: Option<usize>,
+    /// Source the path starts at
+    pub source: usize,
+}
+
+/// Cheapest path from any of `sources` to every system, pricing each jump
+/// from `a` to `b` of length `l` at `jump_cost(a, b, l)` and stopping at
+/// `max_cost` (multi-source Dijkstra); None where none is found
+pub fn shortest_paths(
+    graph: &TravelGraph,
+    positions: &[Position3D],
+    sources: &[usize],
+    max_cost: Option<f64>,
+    jump_cost: impl Fn(usize, usize, f64) -> f64,
+) -> Vec<Option<PathStep>> {
+    let mut best: Vec<Option<PathStep>> = vec![None; positions.len()];
+    let mut done = vec![false; positions.len()];
+    let mut open = BinaryHeap::new();
+
+    for &source in sources {
+        best[source] = Some(PathStep { cost: 0.0, previous: None, source });
+        open.push(Open { cost: 0.0, distance: 0.0, system: source });
+    }
+
+    while let Some(Open { cost: here, system, .. }) = open.pop() {
+        if done[system] {
+            continue;
+        }
+        done[system] = true;
+        let source = best[system].unwrap().source;
+
+        for (next, length) in graph.neighbours(positions, system) {
+            let cost = here + jump_cost(system, next, length);
+            if done[next] || max_cost.is_some_and(|max| cost > max) {
+                continue;
+            }
+            if best[next].is_none_or(|step| cost < step.cost) {
+                best[next] = Some(PathStep { cost, previous: Some(system), source });
+                open.push(Open { cost, distance: cost, system: next });
+            }
+        }
+    }
+
+    best
+}
+
+/// Shortest distance through the graph from `from` to every system, None
+/// where it cannot be reached
+pub fn shortest_distances(graph: &TravelGraph, positions: &[Position3D], from: usize) -> Vec<Option<f64>> {
+    shortest_paths(graph, positions, &[from], None, |_, _, length| length)
+        .into_iter()
+        .map(|step| step.map(|s| s.cost))
+        .collect()
+}
+
+/// Connected component of every system, numbered from 0 in order of each
+/// component's first system
+pub fn components(graph: &TravelGraph, positions: &[Position3D]) -> Vec<usize> {
+    let mut label = vec![usize::MAX; positions.len()];
+    let mut count = 0;
+    for start in 0..positions.len() {
+        if label[start] != usize::MAX {
+            continue;
+        }
+        label[start] = count;
+        let mut stack = vec![start];
+        while let Some(system) = stack.pop() {
+            for (next, _) in graph.neighbours(positions, system) {
+                if label[next] == usize::MAX {
+                    label[next] = count;
+                    stack.push(next);
+                }
+            }
+        }
+        count += 1;
+    }
+    label
+}
+
+fn lexical(a: (f64, f64), b: (f64, f64)) -> Ordering {
+    a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+}
+
+fn distance(a: &Position3D, b: &Position3D) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+}
+
+/// Entry of the A* open set, cheapest estimate on top
+#[derive(Debug, Clone, Copy)]
+struct Open {
+    cost: f64,
+    distance: f64,
+    system: usize,
+}
+
+impl PartialEq for Open {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Open {}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        lexical((other.cost, other.distance), (self.cost, self.distance)).then(other.system.cmp(&self.system))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x: f64, y: f64) -> Position3D {
+        Position3D { x, y, z: 0.0 }
+    }
+
+    /// A row of systems 1 ly apart, 0-1-2-3-4, and a 5 ly detour from 0 to
+    /// 4 in two jumps through system 5
+    fn row() -> (Vec<Position3D>, TravelGraph<'static>) {
+        let positions = vec![pos(0.0, 0.0), pos(1.0, 0.0), pos(2.0, 0.0), pos(3.0, 0.0), pos(4.0, 0.0), pos(2.0, 1.0)];
+        let lanes = vec![(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (3, 4, 1.0), (0, 5, 2.5), (5, 4, 2.5)];
+        let graph = TravelGraph::from_lanes(positions.len(), lanes);
+        (positions, graph)
+    }
+
+    #[test]
+    fn test_route_follows_the_chosen_cost() {
+        let (positions, graph) = row();
+        let open = vec![false; positions.len()];
+
+        assert_eq!(find_route(&graph, &positions, 0, 4, RouteCost::Distance, &open), Some(vec![0, 1, 2, 3, 4]));
+        assert_eq!(find_route(&graph, &positions, 0, 4, RouteCost::Jumps, &open), Some(vec![0, 5, 4]));
+        // Slow ships prefer the short way; a long stop per jump favours few jumps
+        let slow = RouteCost::Time { speed_c: 1.0, jump_time_days: 1.0 };
+        let stops = RouteCost::Time { speed_c: 1000.0, jump_time_days: 30.0 };
+        assert_eq!(find_route(&graph, &positions, 0, 4, slow, &open), Some(vec![0, 1, 2, 3, 4]));
+        assert_eq!(find_route(&graph, &positions, 0, 4, stops, &open), Some(vec![0, 5, 4]));
+
+        let mut blocked = open.clone();
+        blocked[2] = true;
+        assert_eq!(find_route(&graph, &positions, 0, 4, RouteCost::Distance, &blocked), Some(vec![0, 5, 4]));
+        blocked[5] = true;
+        assert_eq!(find_route(&graph, &positions, 0, 4, RouteCost::Distance, &blocked), None);
+        assert_eq!(find_route(&graph, &positions, 3, 3, RouteCost::Distance, &blocked), Some(vec![3]));
+    }
+
+    #[test]
+    fn test_free_flight_respects_jump_range() {
+        let positions: Vec<Position3D> = (0..10).map(|i| pos(i as f64 * 3.0, 0.0)).collect();
+        let tree = KdTree::new(&positions);
+        let open = vec![false; positions.len()];
+
+        let long = TravelGraph::FreeFlight { tree: &tree, max_jump_ly: 10.0 };
+        assert_eq!(find_route(&long, &positions, 0, 9, RouteCost::Jumps, &open), Some(vec![0, 3, 6, 9]));
+        let route = find_route(&long, &positions, 0, 9, RouteCost::Distance, &open).unwrap();
+        assert_eq!((route[0], *route.last().unwrap()), (0, 9));
+
+        let short = TravelGraph::FreeFlight { tree: &tree, max_jump_ly: 2.5 };
+        assert_eq!(find_route(&short, &positions, 0, 9, RouteCost::Distance, &open), None);
+    }
+
+    #[test]
+    fn test_reachability_and_components() {
+        let (positions, graph) = row();
+
+        let reach = reachable(&graph, &positions, 0, None);
+        assert_eq!(reach[4], Some(Reach { jumps: 2, distance_ly: 5.0, previous: Some(5) }));
+        assert_eq!(reach[3].map(|r| (r.jumps, r.previous)), Some((3, Some(2))));
+        assert_eq!(reach[0].map(|r| r.jumps), Some(0));
+
+        let near = reachable(&graph, &positions, 0, Some(1));
+        assert_eq!(near.iter().filter(|r| r.is_some()).count(), 3);
+        assert!(near[2].is_none());
+
+        // Two clusters 20 ly apart split at a 5 ly range and join at 25
+        let mut positions: Vec<Position3D> = (0..4).map(|i| pos(i as f64, 0.0)).collect();
+        positions.extend((0..3).map(|i| pos(23.0 + i as f64, 0.0)));
+        let tree = KdTree::new(&positions);
+        let short = TravelGraph::FreeFlight { tree: &tree, max_jump_ly: 5.0 };
+        assert_eq!(components(&short, &positions), vec![0, 0, 0, 0, 1, 1, 1]);
+        assert!(reachable(&short, &positions, 0, None)[4].is_none());
+        let long = TravelGraph::FreeFlight { tree: &tree, max_jump_ly: 25.0 };
+        assert!(components(&long, &positions).iter().all(|&c| c == 0));
+        assert_eq!(reachable(&long, &positions, 0, None)[6].map(|r| r.jumps), Some(1));
+
+        let distances = shortest_distances(&short, &positions, 1);
+        assert_eq!(distances[3], Some(2.0));
+        assert_eq!(distances[5], None);
+
+        // Each system is served by the nearer of two sources
+        let paths = shortest_paths(&short, &positions, &[0, 6], None, |_, _, length| length);
+        assert_eq!(paths[2].map(|p| (p.source, p.previous)), Some((0, Some(0))));
+        assert_eq!(paths[4].map(|p| (p.source, p.cost)), Some((6, 2.0)));
+        let capped = shortest_paths(&short, &positions, &[0], Some(2.5), |_, _, length| length * 2.0);
+        assert_eq!(capped[1].map(|p| p.cost), Some(2.0));
+        assert!(capped[2].is_none());
+    }
+}
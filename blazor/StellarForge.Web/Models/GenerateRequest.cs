@@ -11,8 +11,33 @@ public class GenerateRequest
     public double? SizeYLy { get; set; }
     public double? SizeZLy { get; set; }
 
-    // Sphere parameters
+    // Sphere parameters (outer radius for spiral, disk, bulge and elliptical)
     public double? RadiusLy { get; set; } = 100.0;
+
+    // Galaxy shape parameters
+    public int? ArmCount { get; set; }
+    public double? PitchAngleDeg { get; set; }
+    public double? ArmSpreadLy { get; set; }
+    public double? ScaleLengthLy { get; set; }
+    public double? ScaleHeightLy { get; set; }
+    public double? EffectiveRadiusLy { get; set; }
+    public double? AxisRatio { get; set; }
+
+    // Minimum distance between systems (optional)
+    public double? MinSeparationLy { get; set; }
+
+    // Initial mass function: salpeter, kroupa or chabrier
+    public string Imf { get; set; } = "kroupa";
+
+    // Star formation history: constant, burst or declining
+    public string StarFormation { get; set; } = "constant";
+    public double? BurstAgeGyr { get; set; }
+    public double? BurstSpreadGyr { get; set; }
+    public double? SfhTimescaleGyr { get; set; }
+
+    // Optional generation seed (random if empty). Seeds the backend picks
+    // stay within 0..long.MaxValue, so they round-trip through this field
+    public long? Seed { get; set; }
 }
 
 public class GenerateResponse
@@ -24,6 +49,13 @@ public class GenerateResponse
     public int SoloSystems { get; set; }
     public int BinarySystems { get; set; }
     public int TrinarySystems { get; set; }
+    public int HigherOrderSystems { get; set; }
+    public int NumPlanetsTotal { get; set; }
+    public int NumMoonsTotal { get; set; }
+    public int NumBeltsTotal { get; set; }
+    public ulong Seed { get; set; }
+    public double? MinSeparationLy { get; set; }
+    public double? AchievedSeparationLy { get; set; }
     public long GenerationTimeMs { get; set; }
 }
 
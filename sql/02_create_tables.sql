@@ -17,44 +17,129 @@ CREATE TABLE IF NOT EXISTS projects (
     updated_at TIMESTAMPTZ DEFAULT NOW(),
 
     -- Generation parameters
-    distribution_type VARCHAR(20) NOT NULL CHECK (distribution_type IN ('cube', 'sphere')),
+    distribution_type VARCHAR(20) NOT NULL CHECK (distribution_type IN ('cube', 'sphere', 'spiral', 'disk', 'bulge', 'elliptical')),
     num_star_systems INTEGER NOT NULL CHECK (num_star_systems > 0 AND num_star_systems <= 10000),
+    seed BIGINT, -- generator seed (u64 bit pattern); same seed + parameters = same galaxy
 
     -- Cube parameters (NULL if sphere)
     size_x_ly DOUBLE PRECISION CHECK (size_x_ly > 0),
     size_y_ly DOUBLE PRECISION CHECK (size_y_ly > 0),
     size_z_ly DOUBLE PRECISION CHECK (size_z_ly > 0),
 
-    -- Sphere parameters (NULL if cube)
+    -- Sphere parameters (NULL if cube; outer radius for spiral, disk, bulge, elliptical)
     radius_ly DOUBLE PRECISION CHECK (radius_ly > 0),
 
+    -- Galaxy shape parameters (NULL unless used by the distribution)
+    arm_count INTEGER CHECK (arm_count >= 1 AND arm_count <= 12),
+    pitch_angle_deg DOUBLE PRECISION CHECK (pitch_angle_deg >= 1 AND pitch_angle_deg <= 80),
+    arm_spread_ly DOUBLE PRECISION CHECK (arm_spread_ly > 0),
+    scale_length_ly DOUBLE PRECISION CHECK (scale_length_ly > 0),
+    scale_height_ly DOUBLE PRECISION CHECK (scale_height_ly > 0),
+    effective_radius_ly DOUBLE PRECISION CHECK (effective_radius_ly > 0),
+    axis_ratio DOUBLE PRECISION CHECK (axis_ratio > 0 AND axis_ratio <= 1),
+
+    -- Poisson-disk placement (NULL = systems placed independently)
+    min_separation_ly DOUBLE PRECISION CHECK (min_separation_ly > 0),
+
+    -- Initial mass function stellar masses are drawn from
+    imf VARCHAR(20) NOT NULL DEFAULT 'kroupa' CHECK (imf IN ('salpeter', 'kroupa', 'chabrier')),
+
+    -- Star formation history system ages are drawn from
+    star_formation VARCHAR(20) NOT NULL DEFAULT 'constant'
+        CHECK (star_formation IN ('constant', 'burst', 'declining')),
+    burst_age_gyr DOUBLE PRECISION CHECK (burst_age_gyr >= 0),
+    burst_spread_gyr DOUBLE PRECISION CHECK (burst_spread_gyr >= 0),
+    sfh_timescale_gyr DOUBLE PRECISION CHECK (sfh_timescale_gyr > 0),
+
+    -- Star catalogue import (NULL for generated projects)
+    catalogue VARCHAR(10) CHECK (catalogue IN ('hyg', 'gaia')),
+    completeness_radius_ly DOUBLE PRECISION CHECK (completeness_radius_ly > 0 AND completeness_radius_ly < radius_ly),
+
     -- Statistics (updated after generation)
     total_stars INTEGER DEFAULT 0,
     solo_systems INTEGER DEFAULT 0,
     binary_systems INTEGER DEFAULT 0,
     trinary_systems INTEGER DEFAULT 0,
+    higher_order_systems INTEGER DEFAULT 0,
 
-    -- Ensure either cube OR sphere params are set
+    -- Ensure the parameters required by each distribution are set
     CONSTRAINT valid_distribution CHECK (
         (distribution_type = 'cube' AND size_x_ly IS NOT NULL AND size_y_ly IS NOT NULL AND size_z_ly IS NOT NULL AND radius_ly IS NULL) OR
-        (distribution_type = 'sphere' AND radius_ly IS NOT NULL AND size_x_ly IS NULL AND size_y_ly IS NULL AND size_z_ly IS NULL)
+        (distribution_type <> 'cube' AND radius_ly IS NOT NULL AND size_x_ly IS NULL AND size_y_ly IS NULL AND size_z_ly IS NULL AND (
+            distribution_type = 'sphere' OR
+            (distribution_type = 'spiral' AND arm_count IS NOT NULL AND pitch_angle_deg IS NOT NULL AND arm_spread_ly IS NOT NULL AND scale_height_ly IS NOT NULL) OR
+            (distribution_type = 'disk' AND scale_length_ly IS NOT NULL AND scale_height_ly IS NOT NULL) OR
+            (distribution_type = 'bulge' AND scale_length_ly IS NOT NULL) OR
+            (distribution_type = 'elliptical' AND effective_radius_ly IS NOT NULL)
+        ))
     )
 );
 
 COMMENT ON TABLE projects IS 'Star generation projects/save sessions';
-COMMENT ON COLUMN projects.distribution_type IS 'cube = random XYZ within bounds, sphere = random polar coordinates';
+COMMENT ON COLUMN projects.distribution_type IS 'cube = random XYZ within bounds, sphere = random polar coordinates, spiral = logarithmic arms, disk = exponential disk, bulge = exponential spheroid, elliptical = de Vaucouleurs profile';
 COMMENT ON COLUMN projects.num_star_systems IS 'Number of star systems to generate (not individual stars)';
+COMMENT ON COLUMN projects.pitch_angle_deg IS 'Spiral arm pitch angle; smaller = more tightly wound';
+COMMENT ON COLUMN projects.arm_spread_ly IS 'Gaussian width of stars around each spiral arm';
+COMMENT ON COLUMN projects.scale_length_ly IS 'Exponential scale length of a disk or bulge';
+COMMENT ON COLUMN projects.scale_height_ly IS 'Exponential scale height of a disk or spiral';
+COMMENT ON COLUMN projects.effective_radius_ly IS 'Half-light radius of an elliptical (de Vaucouleurs) profile';
+COMMENT ON COLUMN projects.axis_ratio IS 'Flattening c/a of a bulge or elliptical (1 = spherical)';
+COMMENT ON COLUMN projects.min_separation_ly IS 'Minimum distance between any two star systems (blue-noise placement)';
+COMMENT ON COLUMN projects.imf IS 'Initial mass function (salpeter, kroupa or chabrier); star properties derive from the sampled mass';
+COMMENT ON COLUMN projects.star_formation IS 'Star formation history: constant, burst (burst_age_gyr, burst_spread_gyr) or declining (sfh_timescale_gyr)';
+COMMENT ON COLUMN projects.seed IS 'Generator seed stored as the signed bit pattern of a u64';
+COMMENT ON COLUMN projects.catalogue IS 'Star catalogue layout the project was imported from (hyg or gaia); the sphere radius_ly is the import radius';
+COMMENT ON COLUMN projects.completeness_radius_ly IS 'Catalogue stars stop here; procedural systems at the same density fill out to radius_ly';
+
+-- =============================================================================
+-- DENSITY ZONES TABLE
+-- Clusters, voids and gradients inside a project's volume
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS density_zones (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    zone_index INTEGER NOT NULL CHECK (zone_index >= 0),
+    zone_type VARCHAR(20) NOT NULL CHECK (zone_type IN ('open_cluster', 'globular_cluster', 'void', 'gradient')),
+
+    -- Zone sphere in light-years
+    center_x_ly DOUBLE PRECISION NOT NULL,
+    center_y_ly DOUBLE PRECISION NOT NULL,
+    center_z_ly DOUBLE PRECISION NOT NULL,
+    radius_ly DOUBLE PRECISION NOT NULL CHECK (radius_ly > 0),
+
+    star_share DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (star_share >= 0 AND star_share <= 1),
+
+    -- Gradient parameters (NULL unless zone_type = 'gradient')
+    gradient_axis CHAR(1) CHECK (gradient_axis IN ('x', 'y', 'z')),
+    gradient_strength DOUBLE PRECISION CHECK (gradient_strength >= -1 AND gradient_strength <= 1),
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+
+    UNIQUE(project_id, zone_index),
+    CONSTRAINT void_has_no_stars CHECK (zone_type <> 'void' OR star_share = 0),
+    CONSTRAINT gradient_params CHECK (
+        zone_type <> 'gradient' OR (gradient_axis IS NOT NULL AND gradient_strength IS NOT NULL)
+    )
+);
+
+COMMENT ON TABLE density_zones IS 'Density zones (clusters, voids, gradients) used when placing star systems';
+COMMENT ON COLUMN density_zones.zone_type IS 'open_cluster = Gaussian blob, globular_cluster = Plummer profile, void = no stars, gradient = linear density change along an axis';
+COMMENT ON COLUMN density_zones.star_share IS 'Fraction of the project''s star systems placed in this zone';
+COMMENT ON COLUMN density_zones.gradient_strength IS 'Density runs from (1 - s) to (1 + s) along gradient_axis';
 
 -- =============================================================================
 -- STAR SYSTEMS TABLE
--- Each entry is one star system (may contain 1-3 stars)
+-- Each entry is one star system (may contain 1-6 stars)
 -- =============================================================================
 
 CREATE TABLE IF NOT EXISTS star_systems (
     id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
     project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
-    name VARCHAR(20) NOT NULL, -- S0001, S0002, etc.
-    system_type VARCHAR(20) NOT NULL CHECK (system_type IN ('solo', 'binary', 'trinary')),
+    name VARCHAR(64) NOT NULL, -- S0001, S0002, or a catalogue name such as Gaia DR3 4472832130942575872
+    system_type VARCHAR(20) NOT NULL CHECK (system_type IN (
+        'solo', 'binary', 'trinary', 'quadruple', 'quintuple', 'sextuple'
+    )),
 
     -- 3D position in light-years
     position GEOMETRY(PointZ, 4326) NOT NULL, -- PostGIS 3D point
@@ -62,19 +147,23 @@ CREATE TABLE IF NOT EXISTS star_systems (
     y_ly DOUBLE PRECISION NOT NULL,
     z_ly DOUBLE PRECISION NOT NULL,
 
+    -- Density zone that placed this system (NULL = background distribution)
+    zone_id UUID REFERENCES density_zones(id) ON DELETE SET NULL,
+
     created_at TIMESTAMPTZ DEFAULT NOW(),
 
     UNIQUE(project_id, name)
 );
 
-COMMENT ON TABLE star_systems IS 'Star systems (containers that hold 1-3 stars)';
+COMMENT ON TABLE star_systems IS 'Star systems (containers that hold 1-6 stars)';
 COMMENT ON COLUMN star_systems.name IS 'System name in format S#### (e.g., S0001, S0002)';
-COMMENT ON COLUMN star_systems.system_type IS 'solo = 1 star, binary = 2 stars, trinary = 3 stars';
+COMMENT ON COLUMN star_systems.system_type IS 'solo = 1 star, binary = 2, trinary = 3, quadruple = 4, quintuple = 5, sextuple = 6; multiplicity rises with primary mass';
 COMMENT ON COLUMN star_systems.position IS 'PostGIS 3D point for spatial queries';
+COMMENT ON COLUMN star_systems.zone_id IS 'Density zone that produced this system; NULL for the background distribution';
 
 -- =============================================================================
 -- STARS TABLE
--- Individual stars (1 per solo system, 2 per binary, 3 per trinary)
+-- Individual stars (1 per solo system, 2 per binary, up to 6 per sextuple)
 -- =============================================================================
 
 CREATE TABLE IF NOT EXISTS stars (
@@ -82,16 +171,42 @@ CREATE TABLE IF NOT EXISTS stars (
     system_id UUID NOT NULL REFERENCES star_systems(id) ON DELETE CASCADE,
     project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
 
-    name VARCHAR(20) NOT NULL, -- S0001, S0001a, S0001b, etc.
-    spectral_class CHAR(1) NOT NULL CHECK (spectral_class IN ('O', 'B', 'A', 'F', 'G', 'K', 'M')),
+    name VARCHAR(64) NOT NULL, -- S0001, S0001a, S0001b, or a catalogue name
+    component VARCHAR(1) NOT NULL DEFAULT '' CHECK (component ~ '^[a-f]?$'),
+    spectral_class VARCHAR(2) NOT NULL CHECK (spectral_class IN (
+        'O', 'B', 'A', 'F', 'G', 'K', 'M',       -- main sequence and evolved stars
+        'L', 'T', 'Y',                           -- brown dwarfs
+        'DA', 'DB', 'DC', 'DO', 'DQ', 'DZ',      -- white dwarfs
+        'NS', 'BH'                               -- neutron stars, black holes
+    )),
     spectral_subclass INTEGER CHECK (spectral_subclass >= 0 AND spectral_subclass <= 9),
-    luminosity_class VARCHAR(5) DEFAULT 'V', -- V = main sequence
+    luminosity_class VARCHAR(3) CHECK (luminosity_class IN ('Ia', 'Ib', 'II', 'III', 'IV', 'V')), -- NULL for degenerate objects
 
-    -- Physical properties
-    temperature_k DOUBLE PRECISION NOT NULL CHECK (temperature_k >= 2400 AND temperature_k <= 50000),
+    -- Physical properties (neutron stars and black holes have zero temperature and luminosity)
+    temperature_k DOUBLE PRECISION NOT NULL CHECK (temperature_k >= 0 AND temperature_k <= 200000),
     mass_solar DOUBLE PRECISION NOT NULL CHECK (mass_solar > 0),
     radius_solar DOUBLE PRECISION NOT NULL CHECK (radius_solar > 0),
-    luminosity_solar DOUBLE PRECISION NOT NULL CHECK (luminosity_solar > 0),
+    luminosity_solar DOUBLE PRECISION NOT NULL CHECK (luminosity_solar >= 0),
+
+    -- Age and composition (shared by every star in a system)
+    age_gyr DOUBLE PRECISION NOT NULL CHECK (age_gyr >= 0),
+    metallicity_feh DOUBLE PRECISION NOT NULL,
+    evolutionary_stage VARCHAR(20) NOT NULL CHECK (evolutionary_stage IN (
+        'brown_dwarf', 'main_sequence', 'subgiant', 'giant', 'bright_giant',
+        'supergiant', 'white_dwarf', 'neutron_star', 'black_hole'
+    )),
+
+    -- Habitable zone in AU (Kopparapu et al. 2014, including companion light); NULL if none
+    hz_optimistic_inner_au DOUBLE PRECISION CHECK (hz_optimistic_inner_au > 0),
+    hz_conservative_inner_au DOUBLE PRECISION CHECK (hz_conservative_inner_au > 0),
+    hz_conservative_outer_au DOUBLE PRECISION CHECK (hz_conservative_outer_au > 0),
+    hz_optimistic_outer_au DOUBLE PRECISION CHECK (hz_optimistic_outer_au > 0),
+
+    -- Photometry; NULL for neutron stars and black holes
+    absolute_magnitude_v DOUBLE PRECISION,
+    bolometric_correction DOUBLE PRECISION,
+    color_index_bv DOUBLE PRECISION,
+    color_hex CHAR(7) CHECK (color_hex ~ '^#[0-9a-f]{6}$'),
 
     created_at TIMESTAMPTZ DEFAULT NOW(),
 
@@ -99,14 +214,331 @@ CREATE TABLE IF NOT EXISTS stars (
 );
 
 COMMENT ON TABLE stars IS 'Individual stars within star systems';
-COMMENT ON COLUMN stars.name IS 'Star name: S0001 for solo, S0001a/S0001b for binary/trinary';
-COMMENT ON COLUMN stars.spectral_class IS 'O, B, A, F, G, K, M (hot to cool)';
-COMMENT ON COLUMN stars.spectral_subclass IS '0-9 subclass (optional, 0 = hottest of class)';
-COMMENT ON COLUMN stars.luminosity_class IS 'V = main sequence (dwarf), III = giant, I = supergiant, etc.';
+COMMENT ON COLUMN stars.name IS 'Star name: S0001 for solo, S0001a/S0001b/... for multiples, a = heaviest; imported stars keep catalogue names';
+COMMENT ON COLUMN stars.component IS 'Letter within the system (a = heaviest), matched by orbits and barycentres; empty for solo stars';
+COMMENT ON COLUMN stars.spectral_class IS 'O-M (hot to cool), L/T/Y brown dwarfs, DA-DZ white dwarfs, NS neutron star, BH black hole';
+COMMENT ON COLUMN stars.spectral_subclass IS '0-9 subclass (0 = hottest of class; white dwarfs use the 50400/T index); NULL for NS and BH';
+COMMENT ON COLUMN stars.age_gyr IS 'Age in Gyr, drawn per system from the project star formation history';
+COMMENT ON COLUMN stars.metallicity_feh IS 'Iron abundance [Fe/H] in dex relative to the Sun';
+COMMENT ON COLUMN stars.evolutionary_stage IS 'Life stage; stars older than their main-sequence lifetime are giants or remnants';
+COMMENT ON COLUMN stars.luminosity_class IS 'V = main sequence (dwarf), IV = subgiant, III = giant, II = bright giant, Ib/Ia = supergiant; NULL for white dwarfs, NS and BH';
 COMMENT ON COLUMN stars.temperature_k IS 'Surface temperature in Kelvin';
 COMMENT ON COLUMN stars.mass_solar IS 'Mass in solar masses (1.0 = Sun)';
 COMMENT ON COLUMN stars.radius_solar IS 'Radius in solar radii (1.0 = Sun)';
 COMMENT ON COLUMN stars.luminosity_solar IS 'Luminosity in solar luminosities (1.0 = Sun)';
+COMMENT ON COLUMN stars.hz_conservative_inner_au IS 'Runaway greenhouse limit; optimistic_inner is the recent Venus limit';
+COMMENT ON COLUMN stars.hz_conservative_outer_au IS 'Maximum greenhouse limit; optimistic_outer is the early Mars limit';
+COMMENT ON COLUMN stars.absolute_magnitude_v IS 'Absolute visual magnitude M_V (Sun = 4.81)';
+COMMENT ON COLUMN stars.bolometric_correction IS 'BC_V = M_bol - M_V (Flower 1996, Torres 2010)';
+COMMENT ON COLUMN stars.color_index_bv IS 'B-V colour index (Sun = 0.65)';
+COMMENT ON COLUMN stars.color_hex IS 'Blackbody display colour in sRGB, #rrggbb';
+
+-- =============================================================================
+-- ORBITS TABLE
+-- Keplerian orbits of multiple-system components (one fewer than the system's stars)
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS orbits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    system_id UUID NOT NULL REFERENCES star_systems(id) ON DELETE CASCADE,
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+
+    level INTEGER NOT NULL CHECK (level >= 0),
+    primary_component VARCHAR(10) NOT NULL,   -- a, or ab, abc, ... for outer orbits
+    secondary_component VARCHAR(10) NOT NULL, -- b, c, ...
+
+    -- Orbital elements
+    semi_major_axis_au DOUBLE PRECISION NOT NULL CHECK (semi_major_axis_au > 0),
+    eccentricity DOUBLE PRECISION NOT NULL CHECK (eccentricity >= 0 AND eccentricity < 1),
+    inclination_deg DOUBLE PRECISION NOT NULL CHECK (inclination_deg >= 0 AND inclination_deg <= 180),
+    period_years DOUBLE PRECISION NOT NULL CHECK (period_years > 0),
+    mass_ratio DOUBLE PRECISION NOT NULL CHECK (mass_ratio > 0),
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+
+    UNIQUE(system_id, level, secondary_component)
+);
+
+COMMENT ON TABLE orbits IS 'Orbits of the components of multiple star systems';
+COMMENT ON COLUMN orbits.level IS 'Hierarchy level: 0 = innermost pair, each higher level orbits everything inside it';
+COMMENT ON COLUMN orbits.primary_component IS 'Star suffix (a) or the inner stars it encloses (ab, abc, ...)';
+COMMENT ON COLUMN orbits.secondary_component IS 'Star suffix of the orbiting companion';
+COMMENT ON COLUMN orbits.semi_major_axis_au IS 'Semi-major axis of the relative orbit in AU';
+COMMENT ON COLUMN orbits.inclination_deg IS 'Inclination to the galactic plane in degrees (isotropic)';
+COMMENT ON COLUMN orbits.period_years IS 'Orbital period in years (Kepler third law)';
+COMMENT ON COLUMN orbits.mass_ratio IS 'Secondary mass over primary mass';
+
+-- =============================================================================
+-- PLANETS TABLE
+-- Planets of each star or close pair, numbered outwards from the host
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS planets (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    star_id UUID NOT NULL REFERENCES stars(id) ON DELETE CASCADE,
+    system_id UUID NOT NULL REFERENCES star_systems(id) ON DELETE CASCADE,
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    barycentre_orbit_id UUID REFERENCES orbits(id) ON DELETE CASCADE,
+
+    name VARCHAR(80) NOT NULL, -- S0001 I, S0001a III, S0001ab II, etc.
+    orbit_index INTEGER NOT NULL CHECK (orbit_index >= 1),
+    planet_type VARCHAR(20) NOT NULL CHECK (planet_type IN ('rocky', 'super_earth', 'ice_giant', 'gas_giant')),
+
+    -- Orbit around the host star
+    semi_major_axis_au DOUBLE PRECISION NOT NULL CHECK (semi_major_axis_au > 0),
+    eccentricity DOUBLE PRECISION NOT NULL CHECK (eccentricity >= 0 AND eccentricity < 1),
+    period_years DOUBLE PRECISION NOT NULL CHECK (period_years > 0),
+
+    -- Physical properties
+    mass_earth DOUBLE PRECISION NOT NULL CHECK (mass_earth > 0),
+    radius_earth DOUBLE PRECISION NOT NULL CHECK (radius_earth > 0),
+
+    -- Ring system (NULL = no rings), measured from the planet's centre
+    ring_inner_radius_km DOUBLE PRECISION CHECK (ring_inner_radius_km > 0),
+    ring_outer_radius_km DOUBLE PRECISION CHECK (ring_outer_radius_km > ring_inner_radius_km),
+
+    -- Habitability
+    insolation_earth DOUBLE PRECISION NOT NULL CHECK (insolation_earth >= 0),
+    habitability DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (habitability >= 0 AND habitability <= 1),
+
+    -- Surface, atmosphere and climate
+    albedo DOUBLE PRECISION NOT NULL CHECK (albedo >= 0 AND albedo < 1),
+    equilibrium_temperature_k DOUBLE PRECISION NOT NULL CHECK (equilibrium_temperature_k >= 0),
+    surface_temperature_k DOUBLE PRECISION NOT NULL CHECK (surface_temperature_k >= 0),
+    atmosphere VARCHAR(20) NOT NULL CHECK (atmosphere IN ('airless', 'thin', 'moderate', 'dense', 'hydrogen_envelope')),
+    surface_pressure_bar DOUBLE PRECISION CHECK (surface_pressure_bar >= 0),
+    water_fraction DOUBLE PRECISION NOT NULL CHECK (water_fraction >= 0 AND water_fraction <= 1),
+    tidally_locked BOOLEAN NOT NULL,
+    day_length_hours DOUBLE PRECISION NOT NULL CHECK (day_length_hours > 0),
+    climate VARCHAR(20) NOT NULL CHECK (climate IN ('barren', 'molten', 'hothouse', 'arid', 'tropical',
+                                                    'temperate', 'boreal', 'glacial', 'gaseous')),
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+
+    UNIQUE(system_id, name)
+);
+
+COMMENT ON TABLE planets IS 'Planets orbiting one star (S-type) or the barycentre of a close pair (P-type), inside Holman-Wiegert stability limits';
+COMMENT ON COLUMN planets.star_id IS 'Host star; the heaviest enclosed star for circumbinary planets';
+COMMENT ON COLUMN planets.barycentre_orbit_id IS 'Component orbit whose barycentre a circumbinary planet circles (NULL = orbits star_id alone)';
+COMMENT ON COLUMN planets.name IS 'Host name plus a Roman numeral counted outwards (S0001 I, S0001a III, S0001ab II)';
+COMMENT ON COLUMN planets.orbit_index IS '1 = innermost planet of its star or barycentre';
+COMMENT ON COLUMN planets.planet_type IS 'rocky < 2, super_earth 2-10, ice_giant 10-50, gas_giant > 50 Earth masses';
+COMMENT ON COLUMN planets.semi_major_axis_au IS 'Semi-major axis around the host star or barycentre in AU';
+COMMENT ON COLUMN planets.mass_earth IS 'Mass in Earth masses (1.0 = Earth)';
+COMMENT ON COLUMN planets.radius_earth IS 'Radius in Earth radii (1.0 = Earth)';
+COMMENT ON COLUMN planets.ring_inner_radius_km IS 'Inner ring edge in km from the planet centre (NULL = no rings)';
+COMMENT ON COLUMN planets.insolation_earth IS 'Orbit-averaged flux from every star in the system (1.0 = Earth)';
+COMMENT ON COLUMN planets.habitability IS 'Habitability score 0-1: habitable-zone position x terrestrial world x host stability';
+COMMENT ON COLUMN planets.equilibrium_temperature_k IS 'Blackbody temperature from insolation and Bond albedo in K';
+COMMENT ON COLUMN planets.surface_temperature_k IS 'Mean surface temperature including the greenhouse effect (1-bar level for giants) in K';
+COMMENT ON COLUMN planets.surface_pressure_bar IS 'Surface pressure in bar (NULL = no solid surface)';
+COMMENT ON COLUMN planets.water_fraction IS 'Fraction of the surface covered by water, liquid or frozen';
+COMMENT ON COLUMN planets.day_length_hours IS 'Sidereal rotation period in hours (equals the orbit when tidally locked)';
+COMMENT ON COLUMN planets.climate IS 'Koppen-like climate label: molten, hothouse, arid, tropical, temperate, boreal, glacial, barren or gaseous';
+
+-- =============================================================================
+-- MOONS TABLE
+-- Natural satellites of each planet, numbered outwards
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS moons (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    planet_id UUID NOT NULL REFERENCES planets(id) ON DELETE CASCADE,
+    star_id UUID NOT NULL REFERENCES stars(id) ON DELETE CASCADE,
+    system_id UUID NOT NULL REFERENCES star_systems(id) ON DELETE CASCADE,
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+
+    name VARCHAR(96) NOT NULL, -- S0001 V a, S0001a III b, etc.
+    orbit_index INTEGER NOT NULL CHECK (orbit_index >= 1),
+
+    -- Orbit around the planet
+    semi_major_axis_km DOUBLE PRECISION NOT NULL CHECK (semi_major_axis_km > 0),
+    eccentricity DOUBLE PRECISION NOT NULL CHECK (eccentricity >= 0 AND eccentricity < 1),
+    period_days DOUBLE PRECISION NOT NULL CHECK (period_days > 0),
+
+    -- Physical properties
+    mass_earth DOUBLE PRECISION NOT NULL CHECK (mass_earth > 0),
+    radius_km DOUBLE PRECISION NOT NULL CHECK (radius_km > 0),
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+
+    UNIQUE(planet_id, orbit_index)
+);
+
+COMMENT ON TABLE moons IS 'Moons orbiting planets, between the Roche limit and the stable part of the Hill sphere';
+COMMENT ON COLUMN moons.name IS 'Planet name plus a letter counted outwards (S0001 V a)';
+COMMENT ON COLUMN moons.orbit_index IS '1 = innermost moon of its planet';
+COMMENT ON COLUMN moons.semi_major_axis_km IS 'Semi-major axis around the planet in km';
+COMMENT ON COLUMN moons.mass_earth IS 'Mass in Earth masses (0.0123 = the Moon)';
+
+-- =============================================================================
+-- BELTS TABLE
+-- Asteroid and Kuiper belts around each star
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS belts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    star_id UUID NOT NULL REFERENCES stars(id) ON DELETE CASCADE,
+    system_id UUID NOT NULL REFERENCES star_systems(id) ON DELETE CASCADE,
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+
+    name VARCHAR(96) NOT NULL, -- S0001 Asteroid Belt, S0001b Kuiper Belt
+    belt_type VARCHAR(20) NOT NULL CHECK (belt_type IN ('asteroid_belt', 'kuiper_belt')),
+    inner_radius_au DOUBLE PRECISION NOT NULL CHECK (inner_radius_au > 0),
+    outer_radius_au DOUBLE PRECISION NOT NULL CHECK (outer_radius_au > inner_radius_au),
+    mass_earth DOUBLE PRECISION NOT NULL CHECK (mass_earth > 0),
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+
+    UNIQUE(star_id, belt_type)
+);
+
+COMMENT ON TABLE belts IS 'Belts of small bodies: asteroid belts inside a giant planet, Kuiper belts beyond the outermost planet';
+COMMENT ON COLUMN belts.mass_earth IS 'Total belt mass in Earth masses';
+
+-- =============================================================================
+-- CONSTELLATIONS TABLES
+-- Figures of bright stars in the sky of one observer system
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS constellations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    observer_system_id UUID NOT NULL REFERENCES star_systems(id) ON DELETE CASCADE,
+
+    name VARCHAR(30) NOT NULL, -- Serrath, Quaelix, etc.
+    seed BIGINT NOT NULL, -- u64 bit pattern, shared by every constellation of the observer
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+
+    UNIQUE(observer_system_id, name)
+);
+
+COMMENT ON TABLE constellations IS 'Procedural constellations: bright stars grouped by angular proximity as seen from the observer system';
+
+CREATE TABLE IF NOT EXISTS constellation_stars (
+    constellation_id UUID NOT NULL REFERENCES constellations(id) ON DELETE CASCADE,
+    star_id UUID NOT NULL REFERENCES stars(id) ON DELETE CASCADE,
+
+    bayer_index INTEGER NOT NULL CHECK (bayer_index >= 1),
+    designation VARCHAR(50) NOT NULL, -- Alpha Serrath, Gamma Serrath, 25 Serrath
+    line_to_star_id UUID REFERENCES stars(id) ON DELETE CASCADE,
+
+    PRIMARY KEY (constellation_id, star_id),
+    UNIQUE(constellation_id, bayer_index)
+);
+
+COMMENT ON COLUMN constellation_stars.bayer_index IS '1 = brightest star of the constellation as seen from the observer';
+COMMENT ON COLUMN constellation_stars.line_to_star_id IS 'Star this one is joined to by a figure line (NULL for the first star); the lines form a tree';
+
+-- =============================================================================
+-- LANES TABLE
+-- Travel network between star systems (one row per undirected lane)
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS lanes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    system_a_id UUID NOT NULL REFERENCES star_systems(id) ON DELETE CASCADE,
+    system_b_id UUID NOT NULL REFERENCES star_systems(id) ON DELETE CASCADE,
+
+    length_ly DOUBLE PRECISION NOT NULL CHECK (length_ly >= 0),
+    chokepoint BOOLEAN NOT NULL DEFAULT FALSE,
+
+    CHECK (system_a_id <> system_b_id),
+    UNIQUE(system_a_id, system_b_id)
+);
+
+COMMENT ON TABLE lanes IS 'Hyperlane / jump-route network: undirected travel links between star systems of one project';
+COMMENT ON COLUMN lanes.system_a_id IS 'Either end; lanes are undirected and stored once per pair';
+COMMENT ON COLUMN lanes.chokepoint IS 'Lane joining two chokepoint regions, the only link kept between them';
+
+-- =============================================================================
+-- FACTIONS TABLE
+-- Political powers dividing a project's systems among them
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS factions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    capital_system_id UUID NOT NULL REFERENCES star_systems(id) ON DELETE CASCADE,
+    strength DOUBLE PRECISION NOT NULL CHECK (strength > 0),
+
+    UNIQUE(project_id, name)
+);
+
+-- Ownership is kept on the systems themselves; star_systems is created first
+ALTER TABLE star_systems ADD COLUMN IF NOT EXISTS faction_id UUID REFERENCES factions(id) ON DELETE SET NULL;
+ALTER TABLE star_systems ADD COLUMN IF NOT EXISTS contested_by_faction_id UUID REFERENCES factions(id) ON DELETE SET NULL;
+ALTER TABLE star_systems ADD COLUMN IF NOT EXISTS border BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON TABLE factions IS 'Empires and other powers; each holds the systems whose faction_id points to it';
+COMMENT ON COLUMN factions.strength IS 'Relative reach; a faction twice as strong claims twice as far';
+COMMENT ON COLUMN star_systems.faction_id IS 'Faction owning the system; NULL when unclaimed';
+COMMENT ON COLUMN star_systems.contested_by_faction_id IS 'Rival whose claim comes within the contested margin of the owner''s';
+COMMENT ON COLUMN star_systems.border IS 'Adjacent (by lane, or as a near neighbour) to a system held by another faction or by none';
+
+-- =============================================================================
+-- ECONOMY TABLES
+-- Trade simulation per project: its clock, each system's market, the flows
+-- of the latest tick and the totals of every tick
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS economies (
+    project_id UUID PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    tick INTEGER NOT NULL DEFAULT 0 CHECK (tick >= 0),
+    network VARCHAR(20) NOT NULL CHECK (network IN ('lanes', 'straight_line')),
+    max_route_ly DOUBLE PRECISION CHECK (max_route_ly > 0),
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS system_markets (
+    system_id UUID PRIMARY KEY REFERENCES star_systems(id) ON DELETE CASCADE,
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+
+    -- One value per resource: food, ores, energy, goods
+    production DOUBLE PRECISION[] NOT NULL,
+    consumption DOUBLE PRECISION[] NOT NULL,
+    stock DOUBLE PRECISION[] NOT NULL,
+    imports DOUBLE PRECISION[] NOT NULL,
+    exports DOUBLE PRECISION[] NOT NULL,
+
+    throughput DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (throughput >= 0)
+);
+
+CREATE TABLE IF NOT EXISTS trade_flows (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    tick INTEGER NOT NULL,
+    resource VARCHAR(20) NOT NULL CHECK (resource IN ('food', 'ores', 'energy', 'goods')),
+    from_system_id UUID NOT NULL REFERENCES star_systems(id) ON DELETE CASCADE,
+    to_system_id UUID NOT NULL REFERENCES star_systems(id) ON DELETE CASCADE,
+    volume DOUBLE PRECISION NOT NULL CHECK (volume > 0)
+);
+
+CREATE TABLE IF NOT EXISTS economy_ticks (
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    tick INTEGER NOT NULL CHECK (tick > 0),
+    produced DOUBLE PRECISION NOT NULL,
+    consumed DOUBLE PRECISION NOT NULL,
+    traded DOUBLE PRECISION NOT NULL,
+    shortage DOUBLE PRECISION NOT NULL,
+
+    PRIMARY KEY (project_id, tick)
+);
+
+COMMENT ON TABLE economies IS 'Economy simulation of a project; tick counts the ticks simulated since it was started';
+COMMENT ON COLUMN economies.network IS 'lanes = goods travel along lanes through the systems between; straight_line = directly from seller to buyer';
+COMMENT ON COLUMN economies.max_route_ly IS 'Most a trade route may cost: its length plus the tariff on jumps between owners; NULL = unlimited';
+COMMENT ON TABLE system_markets IS 'Each system''s market after the latest tick';
+COMMENT ON COLUMN system_markets.stock IS 'Stockpile carried into the next tick';
+COMMENT ON COLUMN system_markets.throughput IS 'Volume bought, sold or carried through during the latest tick; hubs have the most';
+COMMENT ON TABLE trade_flows IS 'Goods moved during the latest tick: per lane and direction, or seller to buyer for straight-line trade';
+COMMENT ON TABLE economy_ticks IS 'Project-wide totals of every tick, for following the economy over time';
 
 -- =============================================================================
 -- INDEXES
@@ -121,13 +553,57 @@ CREATE INDEX idx_star_systems_project ON star_systems(project_id);
 CREATE INDEX idx_star_systems_name ON star_systems(name);
 CREATE INDEX idx_star_systems_position ON star_systems USING GIST(position);
 CREATE INDEX idx_star_systems_type ON star_systems(system_type);
+CREATE INDEX idx_star_systems_zone ON star_systems(zone_id);
+
+-- Density Zones
+CREATE INDEX idx_density_zones_project ON density_zones(project_id);
 
 -- Stars
 CREATE INDEX idx_stars_system ON stars(system_id);
 CREATE INDEX idx_stars_project ON stars(project_id);
 CREATE INDEX idx_stars_spectral ON stars(spectral_class);
+CREATE INDEX idx_stars_stage ON stars(evolutionary_stage);
 CREATE INDEX idx_stars_name ON stars(name);
 
+-- Orbits indexes
+CREATE INDEX idx_orbits_system ON orbits(system_id);
+CREATE INDEX idx_orbits_project ON orbits(project_id);
+
+-- Planets indexes
+CREATE INDEX idx_planets_star ON planets(star_id);
+CREATE INDEX idx_planets_barycentre ON planets(barycentre_orbit_id) WHERE barycentre_orbit_id IS NOT NULL;
+CREATE INDEX idx_planets_system ON planets(system_id);
+CREATE INDEX idx_planets_project ON planets(project_id);
+CREATE INDEX idx_planets_type ON planets(planet_type);
+CREATE INDEX idx_planets_habitability ON planets(project_id, habitability DESC) WHERE habitability > 0;
+CREATE INDEX idx_planets_climate ON planets(project_id, climate);
+
+-- Moons indexes
+CREATE INDEX idx_moons_planet ON moons(planet_id);
+CREATE INDEX idx_moons_system ON moons(system_id);
+CREATE INDEX idx_moons_project ON moons(project_id);
+
+-- Belts indexes
+CREATE INDEX idx_belts_system ON belts(system_id);
+CREATE INDEX idx_belts_project ON belts(project_id);
+
+-- Constellations indexes
+CREATE INDEX idx_constellations_observer ON constellations(observer_system_id);
+CREATE INDEX idx_constellations_project ON constellations(project_id);
+CREATE INDEX idx_constellation_stars_star ON constellation_stars(star_id);
+
+-- Lanes indexes
+CREATE INDEX idx_lanes_project ON lanes(project_id);
+CREATE INDEX idx_lanes_system_b ON lanes(system_b_id);
+
+-- Factions indexes
+CREATE INDEX idx_factions_project ON factions(project_id);
+CREATE INDEX idx_star_systems_faction ON star_systems(faction_id);
+
+-- Economy indexes
+CREATE INDEX idx_system_markets_throughput ON system_markets(project_id, throughput DESC);
+CREATE INDEX idx_trade_flows_project ON trade_flows(project_id, volume DESC);
+
 -- =============================================================================
 -- TRIGGERS
 -- =============================================================================
@@ -156,4 +632,4 @@ $$;
 
 -- Success message
 SELECT 'StellarForge Phase 1 tables created successfully!' AS status;
-SELECT 'Tables: projects, star_systems, stars' AS created_tables;
+SELECT 'Tables: projects, density_zones, star_systems, stars, orbits, planets, moons, belts, constellations, constellation_stars, lanes, factions, economies, system_markets, trade_flows, economy_ticks' AS created_tables;
@@ -19,13 +19,18 @@ SELECT
     SUM(CASE WHEN ss.system_type = 'solo' THEN 1 ELSE 0 END) AS solo_count,
     SUM(CASE WHEN ss.system_type = 'binary' THEN 1 ELSE 0 END) AS binary_count,
     SUM(CASE WHEN ss.system_type = 'trinary' THEN 1 ELSE 0 END) AS trinary_count,
+    SUM(CASE WHEN ss.system_type IN ('quadruple', 'quintuple', 'sextuple') THEN 1 ELSE 0 END) AS higher_order_count,
     SUM(CASE WHEN s.spectral_class = 'O' THEN 1 ELSE 0 END) AS o_class_count,
     SUM(CASE WHEN s.spectral_class = 'B' THEN 1 ELSE 0 END) AS b_class_count,
     SUM(CASE WHEN s.spectral_class = 'A' THEN 1 ELSE 0 END) AS a_class_count,
     SUM(CASE WHEN s.spectral_class = 'F' THEN 1 ELSE 0 END) AS f_class_count,
     SUM(CASE WHEN s.spectral_class = 'G' THEN 1 ELSE 0 END) AS g_class_count,
     SUM(CASE WHEN s.spectral_class = 'K' THEN 1 ELSE 0 END) AS k_class_count,
-    SUM(CASE WHEN s.spectral_class = 'M' THEN 1 ELSE 0 END) AS m_class_count
+    SUM(CASE WHEN s.spectral_class = 'M' THEN 1 ELSE 0 END) AS m_class_count,
+    SUM(CASE WHEN s.spectral_class IN ('L', 'T', 'Y') THEN 1 ELSE 0 END) AS brown_dwarf_count,
+    SUM(CASE WHEN s.spectral_class LIKE 'D_' THEN 1 ELSE 0 END) AS white_dwarf_count,
+    SUM(CASE WHEN s.spectral_class IN ('NS', 'BH') THEN 1 ELSE 0 END) AS remnant_count,
+    SUM(CASE WHEN s.luminosity_class IN ('Ia', 'Ib', 'II', 'III', 'IV') THEN 1 ELSE 0 END) AS evolved_count
 FROM projects p
 LEFT JOIN star_systems ss ON ss.project_id = p.id
 LEFT JOIN stars s ON s.project_id = p.id
@@ -47,6 +52,7 @@ BEGIN
         solo_systems = (SELECT COUNT(*) FROM star_systems WHERE project_id = project_uuid AND system_type = 'solo'),
         binary_systems = (SELECT COUNT(*) FROM star_systems WHERE project_id = project_uuid AND system_type = 'binary'),
         trinary_systems = (SELECT COUNT(*) FROM star_systems WHERE project_id = project_uuid AND system_type = 'trinary'),
+        higher_order_systems = (SELECT COUNT(*) FROM star_systems WHERE project_id = project_uuid AND system_type IN ('quadruple', 'quintuple', 'sextuple')),
         updated_at = NOW()
     WHERE id = project_uuid;
 END;
@@ -143,7 +149,7 @@ COMMENT ON FUNCTION get_systems_within_radius IS 'Find all systems within a sphe
 
 CREATE OR REPLACE FUNCTION get_spectral_distribution(project_uuid UUID)
 RETURNS TABLE (
-    spectral_class CHAR(1),
+    spectral_class VARCHAR(2),
     count BIGINT,
     percentage NUMERIC
 ) AS $$
@@ -174,6 +180,17 @@ BEGIN
             WHEN 'G' THEN 5
             WHEN 'K' THEN 6
             WHEN 'M' THEN 7
+            WHEN 'L' THEN 8
+            WHEN 'T' THEN 9
+            WHEN 'Y' THEN 10
+            WHEN 'DO' THEN 11
+            WHEN 'DB' THEN 12
+            WHEN 'DA' THEN 13
+            WHEN 'DQ' THEN 14
+            WHEN 'DZ' THEN 15
+            WHEN 'DC' THEN 16
+            WHEN 'NS' THEN 17
+            WHEN 'BH' THEN 18
         END;
 END;
 $$ LANGUAGE plpgsql;